@@ -0,0 +1,89 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSinkKind {
+    /// POSTs `{"source", "alerts": [...], "dropped"}` to `url`.
+    Webhook,
+    Telegram,
+    Discord,
+    /// Slack incoming webhook or any endpoint taking `{"text": ...}`.
+    Slack,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AlertSinkConfig {
+    #[serde(rename = "kind", alias = "type")]
+    pub kind: AlertSinkKind,
+    /// Target URL; for Telegram an optional Bot API base (default `https://api.telegram.org`).
+    #[serde(rename = "url", alias = "webhook_url", default)]
+    pub url: String,
+    #[serde(rename = "bot_token", default)]
+    pub bot_token: String,
+    #[serde(rename = "chat_id", default)]
+    pub chat_id: String,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AlertConfig {
+    #[serde(rename = "enabled", default)]
+    pub enabled: bool,
+    #[serde(rename = "sinks", alias = "notifiers", default)]
+    pub sinks: Vec<AlertSinkConfig>,
+    /// Alerts raised within this window after the first pending one go out as one message.
+    #[serde(rename = "batch_window_secs", default = "default_batch_window_secs")]
+    pub batch_window_secs: u64,
+    /// Minimum time between two deliveries; alerts raised meanwhile wait for the next batch.
+    #[serde(rename = "min_interval_secs", alias = "rate_limit_secs", default = "default_min_interval_secs")]
+    pub min_interval_secs: u64,
+    /// Alerts held per batch; further ones are counted as dropped.
+    #[serde(rename = "max_batch", default = "default_max_batch")]
+    pub max_batch: usize,
+    /// Alert when a trade lands with at least this gain, in base-token units (unset = never).
+    #[serde(rename = "landed_profit_above", alias = "profit_above", default)]
+    pub landed_profit_above: Option<f64>,
+    /// Alert after this many submissions in a row fail to send or to land (0 = never).
+    #[serde(rename = "failed_submissions", alias = "consecutive_failures", default = "default_failed_submissions")]
+    pub failed_submissions: u32,
+    /// Alert when a wallet's SOL drops below this many SOL (0 = never).
+    #[serde(rename = "sol_balance_floor", alias = "min_sol", default = "default_sol_balance_floor")]
+    pub sol_balance_floor: f64,
+    /// Alert when the Yellowstone stream has been down this long (0 = never).
+    #[serde(rename = "geyser_down_secs", default = "default_geyser_down_secs")]
+    pub geyser_down_secs: u64,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sinks: Vec::new(),
+            batch_window_secs: default_batch_window_secs(),
+            min_interval_secs: default_min_interval_secs(),
+            max_batch: default_max_batch(),
+            landed_profit_above: None,
+            failed_submissions: default_failed_submissions(),
+            sol_balance_floor: default_sol_balance_floor(),
+            geyser_down_secs: default_geyser_down_secs(),
+        }
+    }
+}
+
+fn default_batch_window_secs() -> u64 {
+    5
+}
+fn default_min_interval_secs() -> u64 {
+    30
+}
+fn default_max_batch() -> usize {
+    50
+}
+fn default_failed_submissions() -> u32 {
+    10
+}
+fn default_sol_balance_floor() -> f64 {
+    0.05
+}
+fn default_geyser_down_secs() -> u64 {
+    60
+}
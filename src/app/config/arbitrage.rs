@@ -16,6 +16,27 @@ pub struct StrategyConfig {
     pub poll_quotes: bool,
     #[serde(rename = "poll_interval_ms", alias = "polling_interval_ms", default = "default_poll_interval_ms")]
     pub poll_interval_ms: u64,
+    /// How the two Jupiter quotes of an arbitrage are turned into a swap instruction.
+    #[serde(rename = "route_builder", alias = "swap_builder", default)]
+    pub route_builder: RouteBuilder,
+    /// Slippage applied to the merged route's `in_amount + min_profit` floor. Anything above 0 lets the route land below min profit.
+    #[serde(rename = "route_slippage_bps", default)]
+    pub route_slippage_bps: u16,
+    /// Program id of the profit-guard program. When set, every Jupiter arbitrage ends with an instruction that reverts unless the base-token ATA gained at least min profit.
+    #[serde(rename = "profit_guard_program", alias = "guard_program", default)]
+    pub profit_guard_program: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteBuilder {
+    /// Both route plans sent to Jupiter `/swap-instructions` as one synthetic quote.
+    #[default]
+    #[serde(alias = "jupiter_api", alias = "api")]
+    SwapApi,
+    /// Each leg built by `/swap-instructions`, then merged locally into one `route` instruction.
+    #[serde(alias = "merged")]
+    MergedRoute,
 }
 
 fn default_live_trading() -> bool {
@@ -39,8 +60,31 @@ pub struct BaseTokenConfig {
     pub threshold: f64,
     #[serde(rename = "min_profit_quote_units", alias = "min_profit", alias = "min_profit_amount")]
     pub min_profit: f64,
+    /// Min profit in USD, converted at the token's current price; replaces `min_profit`
+    /// while a price is known.
+    #[serde(rename = "min_profit_usd", default)]
+    pub min_profit_usd: Option<f64>,
     #[serde(rename = "notional_range", alias = "amount_range", alias = "input_amount_range")]
     pub amount_range: [f64; 2],
     #[serde(rename = "grid_steps", alias = "steps", alias = "input_amount_steps")]
     pub steps: u64,
+    /// Balance floor in token units; the instrument pauses while its ATA holds less.
+    #[serde(rename = "min_balance", alias = "balance_floor", default)]
+    pub min_balance: f64,
+    /// Mints to arbitrage against. Empty: USDC and USDT for SOL, `default_quote_mint` otherwise.
+    #[serde(rename = "target_mints", alias = "targets", alias = "quote_mints", default)]
+    pub target_mints: Vec<String>,
+    /// Only route through these Jupiter DEX labels (Jupiter `dexes`). Empty allows all.
+    #[serde(rename = "dexes", alias = "allow_dexes", default)]
+    pub dexes: Vec<String>,
+    /// Never route through these Jupiter DEX labels (Jupiter `excludeDexes`).
+    #[serde(rename = "exclude_dexes", alias = "deny_dexes", default)]
+    pub exclude_dexes: Vec<String>,
+    /// Hops per leg. 1 requests direct routes from Jupiter (`onlyDirectRoutes`); quotes with
+    /// more hops than a higher limit are discarded.
+    #[serde(rename = "max_route_hops", alias = "max_hops", default)]
+    pub max_route_hops: Option<usize>,
+    /// Jupiter `onlyDirectRoutes`. Unset: multi-hop when polling, direct for big trades.
+    #[serde(rename = "only_direct_routes", alias = "direct_routes_only", default)]
+    pub only_direct_routes: Option<bool>,
 }
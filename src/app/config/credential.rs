@@ -2,8 +2,15 @@ use serde::Deserialize;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct NodeConfig {
-    #[serde(rename = "signer_keypair_path", alias = "keypair_path", alias = "wallet_path")]
+    /// Where the wallet key lives: "file" (plaintext), "keystore" (encrypted) or "remote" (signer socket).
+    #[serde(default)]
+    pub signer: SignerKind,
+    /// Plaintext key file, or the encrypted keystore when `signer = "keystore"`.
+    #[serde(rename = "signer_keypair_path", alias = "keypair_path", alias = "wallet_path", default)]
     pub keypair_path: String,
+    /// Unix socket of the remote signer when `signer = "remote"`.
+    #[serde(default)]
+    pub remote_signer_socket: Option<String>,
     #[serde(rename = "rpc_endpoint", alias = "rpc_url")]
     pub rpc_url: String,
     #[serde(rename = "submit_endpoint", alias = "submit_url")]
@@ -19,6 +26,29 @@ pub struct NodeConfig {
     pub submission_services: Option<Vec<String>>,
 }
 
+/// Additional trading wallet. The `[connection]` signer with `nonce_account_pubkey` is always
+/// wallet 0; each extra wallet needs its own durable nonce account.
+#[derive(Debug, Deserialize, Clone)]
+pub struct WalletConfig {
+    #[serde(default)]
+    pub signer: SignerKind,
+    #[serde(rename = "signer_keypair_path", alias = "keypair_path", default)]
+    pub keypair_path: String,
+    #[serde(default)]
+    pub remote_signer_socket: Option<String>,
+    #[serde(rename = "nonce_account_pubkey", alias = "nonce_account")]
+    pub nonce_account: String,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SignerKind {
+    #[default]
+    File,
+    Keystore,
+    Remote,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct SwapApiConfig {
     #[serde(rename = "endpoint", alias = "base_url", alias = "jupiter_endpoint")]
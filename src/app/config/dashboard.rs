@@ -0,0 +1,24 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DashboardConfig {
+    /// Replace console logs with a live terminal dashboard (also `--dashboard`). Ignored when
+    /// stdout is not a terminal.
+    #[serde(rename = "enabled", alias = "tui", default)]
+    pub enabled: bool,
+    #[serde(rename = "refresh_ms", default = "default_refresh_ms")]
+    pub refresh_ms: u64,
+}
+
+impl Default for DashboardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            refresh_ms: default_refresh_ms(),
+        }
+    }
+}
+
+fn default_refresh_ms() -> u64 {
+    500
+}
@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FlashLoanConfig {
+    /// Fund trades with a flash borrow/repay pair instead of wallet inventory.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Lending program (Kamino Lend interface).
+    #[serde(rename = "program", alias = "program_id", default = "default_flash_loan_program")]
+    pub program: String,
+    #[serde(rename = "lending_market", alias = "market", default)]
+    pub lending_market: String,
+    /// Reserve to borrow from for each base token. Instruments without a reserve use inventory.
+    /// Vaults and the flash loan fee are read from each reserve account at startup.
+    #[serde(default)]
+    pub reserves: Vec<FlashReserveConfig>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FlashReserveConfig {
+    #[serde(rename = "token_mint", alias = "mint")]
+    pub mint: String,
+    pub reserve: String,
+}
+
+impl Default for FlashLoanConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            program: default_flash_loan_program(),
+            lending_market: String::new(),
+            reserves: Vec::new(),
+        }
+    }
+}
+
+fn default_flash_loan_program() -> String {
+    "KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD".to_string()
+}
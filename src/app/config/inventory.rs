@@ -0,0 +1,48 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct InventoryConfig {
+    /// How often wallet SOL and instrument ATA balances are re-read over RPC. Geyser account
+    /// updates refresh them in between when the stream is running.
+    #[serde(rename = "refresh_interval_ms", alias = "balance_refresh_ms", default = "default_refresh_interval_ms")]
+    pub refresh_interval_ms: u64,
+    /// SOL kept for fees, tips and rent. All instruments pause while the wallet holds less.
+    #[serde(rename = "min_sol_reserve", alias = "sol_fee_reserve", default = "default_min_sol_reserve")]
+    pub min_sol_reserve: f64,
+    /// Create missing ATAs for base tokens, quote targets and intermediate tokens up front,
+    /// and drop their create instructions from arbitrage transactions.
+    #[serde(rename = "provision_atas", alias = "create_atas", default = "default_provision_atas")]
+    pub provision_atas: bool,
+    /// Close zero-balance token accounts of other mints to reclaim rent, once they have stayed
+    /// empty and unused by any route for 30 minutes. Needs a non-zero maintenance interval.
+    #[serde(rename = "close_empty_atas", alias = "reclaim_rent", default)]
+    pub close_empty_atas: bool,
+    /// How often provisioning and cleanup re-run after startup, in seconds (0 = startup only).
+    #[serde(rename = "ata_maintenance_interval_secs", default = "default_ata_maintenance_interval_secs")]
+    pub ata_maintenance_interval_secs: u64,
+}
+
+impl Default for InventoryConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval_ms: default_refresh_interval_ms(),
+            min_sol_reserve: default_min_sol_reserve(),
+            provision_atas: default_provision_atas(),
+            close_empty_atas: false,
+            ata_maintenance_interval_secs: default_ata_maintenance_interval_secs(),
+        }
+    }
+}
+
+fn default_refresh_interval_ms() -> u64 {
+    5_000
+}
+fn default_min_sol_reserve() -> f64 {
+    0.02
+}
+fn default_provision_atas() -> bool {
+    true
+}
+fn default_ata_maintenance_interval_secs() -> u64 {
+    3_600
+}
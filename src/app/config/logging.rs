@@ -0,0 +1,54 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Minutely,
+    Hourly,
+    #[default]
+    Daily,
+    Never,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct LoggingConfig {
+    /// Write typed trading events as JSON lines (OpportunityFound, Submitted, Landed,
+    /// QuoteFailed, BigTradeDetected) to a rotating file.
+    #[serde(rename = "event_log_enabled", alias = "events", default = "default_event_log_enabled")]
+    pub event_log_enabled: bool,
+    #[serde(rename = "event_log_dir", alias = "dir", default = "default_event_log_dir")]
+    pub event_log_dir: String,
+    /// File name prefix; files are named `<prefix>.<date>.jsonl` unless rotation is `never`.
+    #[serde(rename = "event_log_prefix", default = "default_event_log_prefix")]
+    pub event_log_prefix: String,
+    #[serde(rename = "rotation", default)]
+    pub rotation: LogRotation,
+    /// Rotated files kept; older ones are deleted (0 keeps all).
+    #[serde(rename = "max_files", alias = "keep_files", default = "default_max_files")]
+    pub max_files: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            event_log_enabled: default_event_log_enabled(),
+            event_log_dir: default_event_log_dir(),
+            event_log_prefix: default_event_log_prefix(),
+            rotation: LogRotation::default(),
+            max_files: default_max_files(),
+        }
+    }
+}
+
+fn default_event_log_enabled() -> bool {
+    true
+}
+fn default_event_log_dir() -> String {
+    "logs".to_string()
+}
+fn default_event_log_prefix() -> String {
+    "events".to_string()
+}
+fn default_max_files() -> usize {
+    14
+}
@@ -0,0 +1,32 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MarketConfig {
+    /// Pool accounts mirrored from Geyser. The DEX is detected from the account owner program.
+    #[serde(rename = "pools", alias = "pool_accounts", default)]
+    pub pools: Vec<String>,
+    /// A cached pool is reported stale when the stream has not confirmed it for this many slots.
+    #[serde(rename = "max_staleness_slots", alias = "stale_after_slots", default = "default_max_staleness_slots")]
+    pub max_staleness_slots: u64,
+    /// Quote cached pools locally and submit with native swap instructions (no Jupiter calls).
+    #[serde(rename = "direct_execution_enabled", alias = "direct_execution", default)]
+    pub direct_execution_enabled: bool,
+    /// Slippage allowed on the first leg of a direct trade, in basis points.
+    #[serde(rename = "direct_slippage_bps", default)]
+    pub direct_slippage_bps: u64,
+}
+
+impl Default for MarketConfig {
+    fn default() -> Self {
+        Self {
+            pools: Vec::new(),
+            max_staleness_slots: default_max_staleness_slots(),
+            direct_execution_enabled: false,
+            direct_slippage_bps: 0,
+        }
+    }
+}
+
+fn default_max_staleness_slots() -> u64 {
+    10
+}
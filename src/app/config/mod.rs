@@ -1,14 +1,45 @@
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use serde::Deserialize;
 use std::fs;
+use tracing::warn;
 
+pub mod alerts;
 pub mod arbitrage;
 pub mod credential;
+pub mod dashboard;
 pub mod fee;
+pub mod flash_loan;
+pub mod inventory;
+pub mod logging;
+pub mod market;
+pub mod opportunity_db;
+pub mod rebalance;
+pub mod risk;
+pub mod scheduler;
+pub mod sol_price;
+pub mod telemetry;
+pub mod token_price;
+pub mod validate;
+pub mod venue;
 
+pub use alerts::*;
 pub use arbitrage::*;
 pub use credential::*;
+pub use dashboard::*;
 pub use fee::*;
+pub use flash_loan::*;
+pub use inventory::*;
+pub use logging::*;
+pub use market::*;
+pub use opportunity_db::*;
+pub use rebalance::*;
+pub use risk::*;
+pub use scheduler::*;
+pub use sol_price::*;
+pub use telemetry::*;
+pub use token_price::*;
+pub use validate::*;
+pub use venue::*;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
@@ -20,14 +51,77 @@ pub struct Config {
     pub strategy: StrategyConfig,
     #[serde(rename = "fees", alias = "tx_cost", alias = "fee")]
     pub tx_cost: TxCostConfig,
+    #[serde(rename = "sol_price", alias = "price_oracle", default)]
+    pub sol_price: SolPriceConfig,
+    #[serde(rename = "token_prices", alias = "token_price", alias = "prices", default)]
+    pub token_prices: TokenPriceConfig,
+    #[serde(rename = "markets", alias = "market", default)]
+    pub markets: MarketConfig,
+    #[serde(rename = "flash_loan", alias = "flash_loans", default)]
+    pub flash_loan: FlashLoanConfig,
+    #[serde(rename = "inventory", alias = "balances", default)]
+    pub inventory: InventoryConfig,
+    #[serde(rename = "rebalance", alias = "rebalancer", default)]
+    pub rebalance: RebalanceConfig,
+    #[serde(rename = "risk", alias = "circuit_breaker", default)]
+    pub risk: RiskConfig,
+    #[serde(rename = "scheduler", alias = "opportunity_scheduler", default)]
+    pub scheduler: SchedulerConfig,
+    #[serde(rename = "venues", alias = "venue_policy", default)]
+    pub venues: VenueConfig,
+    #[serde(rename = "opportunity_db", alias = "opportunities", default)]
+    pub opportunity_db: OpportunityDbConfig,
+    #[serde(rename = "logging", alias = "log", default)]
+    pub logging: LoggingConfig,
+    #[serde(rename = "telemetry", alias = "http", default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(rename = "dashboard", alias = "tui", default)]
+    pub dashboard: DashboardConfig,
+    #[serde(rename = "alerts", alias = "notifier", default)]
+    pub alerts: AlertConfig,
+    /// Extra trading wallets, each with its own nonce account and inventory.
+    #[serde(rename = "wallets", alias = "trading_wallets", default)]
+    pub wallets: Vec<WalletConfig>,
 }
 
-pub static CONFIG: Lazy<Config> = Lazy::new(|| {
-    let path = "settings.toml";
-    let content = fs::read_to_string(path).unwrap_or_else(|_| {
-        fs::read_to_string("Config.toml").unwrap_or_else(|e| {
-            panic!("Failed to read settings.toml or Config.toml: {}", e)
-        })
-    });
-    toml::from_str(&content).expect("Failed to parse config")
-});
+static LOADED_CONFIG: OnceCell<Config> = OnceCell::new();
+
+/// Read, parse and validate `settings.toml` (or `Config.toml`). All errors are returned
+/// together, each with its TOML path. Call once at startup, then `log_config_warnings` once
+/// logging is set up.
+pub fn init_config() -> Result<&'static Config, anyhow::Error> {
+    LOADED_CONFIG.get_or_try_init(load_config)
+}
+
+fn load_config() -> Result<Config, anyhow::Error> {
+    let (path, content) = ["settings.toml", "Config.toml"]
+        .into_iter()
+        .find_map(|path| fs::read_to_string(path).ok().map(|content| (path, content)))
+        .ok_or_else(|| anyhow::anyhow!("Neither settings.toml nor Config.toml could be read"))?;
+    let config: Config = toml::from_str(&content).map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+
+    let errors: Vec<String> = validate_config(&config)
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .map(|issue| format!("  {}", issue))
+        .collect();
+    if !errors.is_empty() {
+        return Err(anyhow::anyhow!(
+            "{} has {} invalid setting(s):\n{}",
+            path,
+            errors.len(),
+            errors.join("\n")
+        ));
+    }
+    Ok(config)
+}
+
+/// The validated config. Panics with the validation report if `init_config` would fail.
+pub static CONFIG: Lazy<&'static Config> = Lazy::new(|| init_config().unwrap_or_else(|e| panic!("{:#}", e)));
+
+/// Log the non-fatal issues of the loaded config.
+pub fn log_config_warnings(config: &Config) {
+    for issue in validate_config(config).iter().filter(|issue| issue.severity == Severity::Warning) {
+        warn!(path = %issue.path, "{}", issue.message);
+    }
+}
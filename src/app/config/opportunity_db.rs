@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct OpportunityDbConfig {
+    /// Persist every evaluated quote round trip, with its costs and what became of it, to SQLite.
+    #[serde(rename = "enabled", default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(rename = "path", alias = "db_path", default = "default_path")]
+    pub path: String,
+}
+
+impl Default for OpportunityDbConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            path: default_path(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_path() -> String {
+    "opportunities.db".to_string()
+}
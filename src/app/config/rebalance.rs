@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RebalanceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// How often balances are compared against the target weights, in seconds.
+    #[serde(rename = "interval_secs", alias = "check_interval_secs", default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Target share of total inventory value per base token. Weights are normalized.
+    #[serde(rename = "target_weights", alias = "weights", default)]
+    pub target_weights: Vec<TokenWeight>,
+    /// Rebalance only when a token's share is off its target by more than this fraction.
+    #[serde(rename = "drift_threshold", alias = "max_drift", default = "default_drift_threshold")]
+    pub drift_threshold: f64,
+    #[serde(rename = "max_slippage_bps", alias = "slippage_bps", default = "default_max_slippage_bps")]
+    pub max_slippage_bps: u16,
+    /// Skip rebalancing while an arbitrage was submitted within this many seconds.
+    #[serde(rename = "quiet_period_secs", alias = "cooldown_secs", default = "default_quiet_period_secs")]
+    pub quiet_period_secs: u64,
+    /// Smallest rebalance swap worth sending, in USD.
+    #[serde(rename = "min_trade_usd", default = "default_min_trade_usd")]
+    pub min_trade_usd: f64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TokenWeight {
+    #[serde(rename = "token_mint", alias = "mint")]
+    pub mint: String,
+    pub weight: f64,
+}
+
+impl Default for RebalanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_secs: default_interval_secs(),
+            target_weights: Vec::new(),
+            drift_threshold: default_drift_threshold(),
+            max_slippage_bps: default_max_slippage_bps(),
+            quiet_period_secs: default_quiet_period_secs(),
+            min_trade_usd: default_min_trade_usd(),
+        }
+    }
+}
+
+fn default_interval_secs() -> u64 {
+    300
+}
+fn default_drift_threshold() -> f64 {
+    0.1
+}
+fn default_max_slippage_bps() -> u16 {
+    50
+}
+fn default_quiet_period_secs() -> u64 {
+    60
+}
+fn default_min_trade_usd() -> f64 {
+    10.0
+}
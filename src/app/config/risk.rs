@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct RiskConfig {
+    /// Consecutive trades that land without profit before arbitrage submission halts
+    /// (0 = never, the default). Needs `venues.track_landings`.
+    #[serde(rename = "max_consecutive_losses", alias = "loss_streak_limit", default = "default_max_consecutive_losses")]
+    pub max_consecutive_losses: u32,
+    /// How long submission stays halted once tripped.
+    #[serde(rename = "cooldown_secs", alias = "halt_secs", default = "default_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self {
+            max_consecutive_losses: default_max_consecutive_losses(),
+            cooldown_secs: default_cooldown_secs(),
+        }
+    }
+}
+
+fn default_max_consecutive_losses() -> u32 {
+    0
+}
+fn default_cooldown_secs() -> u64 {
+    900
+}
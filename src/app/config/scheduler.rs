@@ -0,0 +1,48 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SchedulerConfig {
+    /// Off: every mode allocates a wallet and submits its best candidate on its own.
+    #[serde(rename = "enabled", default = "default_enabled")]
+    pub enabled: bool,
+    /// A candidate's deadline: it is dropped once its quotes are older than this, including while
+    /// it waits for a free wallet. Also how long a dispatched trade blocks overlapping candidates.
+    #[serde(rename = "max_age_ms", alias = "freshness_ms", default = "default_max_age_ms")]
+    pub max_age_ms: u64,
+    #[serde(rename = "queue_capacity", default = "default_queue_capacity")]
+    pub queue_capacity: usize,
+    /// Landing probability assumed for a source before tracked landings say otherwise.
+    #[serde(rename = "landing_prior", default = "default_landing_prior")]
+    pub landing_prior: f64,
+    /// How many tracked landings the prior is worth.
+    #[serde(rename = "landing_prior_weight", default = "default_landing_prior_weight")]
+    pub landing_prior_weight: f64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            max_age_ms: default_max_age_ms(),
+            queue_capacity: default_queue_capacity(),
+            landing_prior: default_landing_prior(),
+            landing_prior_weight: default_landing_prior_weight(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+fn default_max_age_ms() -> u64 {
+    1_000
+}
+fn default_queue_capacity() -> usize {
+    256
+}
+fn default_landing_prior() -> f64 {
+    0.5
+}
+fn default_landing_prior_weight() -> f64 {
+    10.0
+}
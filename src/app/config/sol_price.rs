@@ -0,0 +1,69 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSourceKind {
+    /// 1 SOL -> USDC quote from the configured Jupiter endpoint.
+    Jupiter,
+    /// Pyth pull oracle price update account (`pyth_account`).
+    Pyth,
+    /// Switchboard on-demand pull feed account (`switchboard_account`).
+    Switchboard,
+    /// CoinGecko simple price API, polled every `coingecko_interval_secs`.
+    Coingecko,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SolPriceConfig {
+    #[serde(rename = "sources", default = "default_sources")]
+    pub sources: Vec<PriceSourceKind>,
+    #[serde(rename = "refresh_interval_secs", alias = "refresh_secs", default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    /// CoinGecko's public API is rate limited, so it is asked less often than the others.
+    #[serde(rename = "coingecko_interval_secs", default = "default_coingecko_interval_secs")]
+    pub coingecko_interval_secs: u64,
+    #[serde(rename = "pyth_account", alias = "pyth_price_account", default = "default_pyth_account")]
+    pub pyth_account: String,
+    #[serde(rename = "switchboard_account", alias = "switchboard_feed", default)]
+    pub switchboard_account: Option<String>,
+    /// A source's last price older than this is left out of the median.
+    #[serde(rename = "max_source_age_secs", default = "default_max_source_age_secs")]
+    pub max_source_age_secs: u64,
+    /// Non-SOL instruments are not traded while the price is older than this (0 = never block).
+    #[serde(rename = "stale_after_secs", alias = "max_age_secs", default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+}
+
+impl Default for SolPriceConfig {
+    fn default() -> Self {
+        Self {
+            sources: default_sources(),
+            refresh_interval_secs: default_refresh_interval_secs(),
+            coingecko_interval_secs: default_coingecko_interval_secs(),
+            pyth_account: default_pyth_account(),
+            switchboard_account: None,
+            max_source_age_secs: default_max_source_age_secs(),
+            stale_after_secs: default_stale_after_secs(),
+        }
+    }
+}
+
+fn default_sources() -> Vec<PriceSourceKind> {
+    vec![PriceSourceKind::Jupiter, PriceSourceKind::Pyth, PriceSourceKind::Coingecko]
+}
+fn default_refresh_interval_secs() -> u64 {
+    5
+}
+fn default_coingecko_interval_secs() -> u64 {
+    60
+}
+/// Pyth SOL/USD price feed account (shard 0) on mainnet.
+fn default_pyth_account() -> String {
+    "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJJ1f9E".to_string()
+}
+fn default_max_source_age_secs() -> u64 {
+    120
+}
+fn default_stale_after_secs() -> u64 {
+    30
+}
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TelemetryConfig {
+    /// Serve `/metrics` (Prometheus) and the control API (`/venues`) over HTTP.
+    #[serde(rename = "enabled", alias = "http_enabled", default)]
+    pub enabled: bool,
+    #[serde(rename = "listen_addr", alias = "bind", default = "default_listen_addr")]
+    pub listen_addr: String,
+    /// Where `/healthz` and `/readyz` are served; they are always on. Default: `listen_addr`.
+    #[serde(rename = "health_listen_addr", alias = "health_bind", default)]
+    pub health_listen_addr: Option<String>,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen_addr: default_listen_addr(),
+            health_listen_addr: None,
+        }
+    }
+}
+
+fn default_listen_addr() -> String {
+    "127.0.0.1:9184".to_string()
+}
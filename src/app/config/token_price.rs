@@ -0,0 +1,33 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct TokenPriceConfig {
+    #[serde(rename = "refresh_interval_secs", alias = "refresh_secs", default = "default_refresh_interval_secs")]
+    pub refresh_interval_secs: u64,
+    /// A token price older than this is treated as unknown.
+    #[serde(rename = "stale_after_secs", alias = "max_age_secs", default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+    /// Size of the USDC -> token quote each price is read from, in USD.
+    #[serde(rename = "probe_usd", alias = "quote_size_usd", default = "default_probe_usd")]
+    pub probe_usd: f64,
+}
+
+impl Default for TokenPriceConfig {
+    fn default() -> Self {
+        Self {
+            refresh_interval_secs: default_refresh_interval_secs(),
+            stale_after_secs: default_stale_after_secs(),
+            probe_usd: default_probe_usd(),
+        }
+    }
+}
+
+fn default_refresh_interval_secs() -> u64 {
+    30
+}
+fn default_stale_after_secs() -> u64 {
+    300
+}
+fn default_probe_usd() -> f64 {
+    100.0
+}
@@ -0,0 +1,736 @@
+//! Semantic checks run on the parsed config before anything starts. Each issue names the TOML
+//! path it refers to (e.g. `strategy.instruments[0].grid_steps`); errors abort startup,
+//! warnings are logged.
+
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+
+use super::*;
+
+/// Submission services `submission_services` accepts: aliases, the `[dex_api]` key each one
+/// needs, and how to read that key.
+type ServiceSpec = (&'static [&'static str], &'static str, fn(&SwapApiConfig) -> &str);
+
+const SUBMISSION_SERVICES: [ServiceSpec; 9] = [
+    (&["jito"], "jito_api_key", |a| a.jito_api_key.as_str()),
+    (&["liljit"], "liljit_endpoint", |a| a.liljit_endpoint.as_str()),
+    (&["helius"], "helius_api_key", |a| a.helius_api_key.as_str()),
+    (&["astralane", "astra"], "astralane_key", |a| a.astralane_key.as_str()),
+    (&["zeroslot", "zero_slot"], "zero_slot_key", |a| a.zero_slot_key.as_str()),
+    (&["nozomi"], "nozomi_api_key", |a| a.nozomi_api_key.as_str()),
+    (&["blockrazor", "brazor"], "blockrazor_key", |a| a.blockrazor_key.as_str()),
+    (&["bloxroute"], "bloxroute_key", |a| a.bloxroute_key.as_str()),
+    (&["nextblock"], "nextblock_key", |a| a.nextblock_key.as_str()),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: {}", level, self.path, self.message)
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<ConfigIssue>);
+
+impl Issues {
+    fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigIssue {
+            severity: Severity::Error,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn warn(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigIssue {
+            severity: Severity::Warning,
+            path: path.into(),
+            message: message.into(),
+        });
+    }
+
+    fn pubkey(&mut self, path: impl Into<String>, value: &str) {
+        if value.parse::<Pubkey>().is_err() {
+            self.error(path, format!("\"{}\" is not a valid base58 public key", value));
+        }
+    }
+}
+
+/// All errors and warnings in `config`, in file order.
+pub fn validate_config(config: &Config) -> Vec<ConfigIssue> {
+    let mut issues = Issues::default();
+    validate_connection(config, &mut issues);
+    validate_strategy(&config.strategy, &mut issues);
+    validate_fees(&config.tx_cost, &mut issues);
+    validate_sol_price(&config.sol_price, &mut issues);
+    if config.token_prices.refresh_interval_secs == 0 {
+        issues.error("token_prices.refresh_interval_secs", "must be greater than 0");
+    }
+    if config.token_prices.probe_usd <= 0.0 {
+        issues.error("token_prices.probe_usd", "must be greater than 0");
+    }
+    if config.token_prices.stale_after_secs < config.token_prices.refresh_interval_secs {
+        issues.warn(
+            "token_prices.stale_after_secs",
+            "is shorter than refresh_interval_secs; prices expire between refreshes",
+        );
+    }
+    validate_markets(&config.markets, &mut issues);
+    validate_flash_loan(&config.flash_loan, &mut issues);
+    validate_inventory(&config.inventory, &mut issues);
+    validate_rebalance(&config.rebalance, &mut issues);
+    validate_venues(&config.venues, &mut issues);
+    validate_scheduler(&config.scheduler, &mut issues);
+    if config.risk.max_consecutive_losses > 0 && !config.venues.track_landings {
+        issues.warn(
+            "risk.max_consecutive_losses",
+            "has no effect while venues.track_landings is off",
+        );
+    }
+    if config.logging.event_log_enabled {
+        if config.logging.event_log_dir.trim().is_empty() {
+            issues.error("logging.event_log_dir", "must not be empty while the event log is enabled");
+        }
+        if config.logging.event_log_prefix.trim().is_empty() {
+            issues.error("logging.event_log_prefix", "must not be empty while the event log is enabled");
+        }
+    }
+    if config.opportunity_db.enabled && config.opportunity_db.path.trim().is_empty() {
+        issues.error("opportunity_db.path", "must not be empty while the opportunity database is enabled");
+    }
+    // Health is always served, on listen_addr unless health_listen_addr is set
+    let telemetry = &config.telemetry;
+    let listen_addrs = [
+        ("telemetry.listen_addr", Some(&telemetry.listen_addr).filter(|_| telemetry.enabled || telemetry.health_listen_addr.is_none())),
+        ("telemetry.health_listen_addr", telemetry.health_listen_addr.as_ref()),
+    ];
+    for (path, addr) in listen_addrs {
+        if let Some(addr) = addr.filter(|addr| addr.parse::<std::net::SocketAddr>().is_err()) {
+            issues.error(path, format!("\"{}\" is not a socket address like 127.0.0.1:9184", addr));
+        }
+    }
+    if config.dashboard.enabled && config.dashboard.refresh_ms == 0 {
+        issues.error("dashboard.refresh_ms", "must be greater than 0");
+    }
+    validate_alerts(config, &mut issues);
+    for (i, wallet) in config.wallets.iter().enumerate() {
+        let path = format!("wallets[{}]", i);
+        validate_signer(&path, wallet.signer, &wallet.keypair_path, wallet.remote_signer_socket.as_deref(), &mut issues);
+        issues.pubkey(format!("{}.nonce_account_pubkey", path), &wallet.nonce_account);
+        if wallet.nonce_account == config.strategy.nonce_account {
+            issues.error(
+                format!("{}.nonce_account_pubkey", path),
+                "each wallet needs its own nonce account; this one is strategy.nonce_account_pubkey",
+            );
+        }
+    }
+    issues.0
+}
+
+fn validate_signer(path: &str, kind: SignerKind, keypair_path: &str, socket: Option<&str>, issues: &mut Issues) {
+    match kind {
+        SignerKind::File | SignerKind::Keystore if keypair_path.trim().is_empty() => {
+            issues.error(format!("{}.signer_keypair_path", path), "required for a file or keystore signer");
+        }
+        SignerKind::Remote if socket.is_none_or(|s| s.trim().is_empty()) => {
+            issues.error(format!("{}.remote_signer_socket", path), "required when signer = \"remote\"");
+        }
+        _ => {}
+    }
+}
+
+fn validate_url(path: &str, url: &str, issues: &mut Issues) {
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        issues.error(path, format!("\"{}\" is not an http(s) URL", url));
+    }
+}
+
+fn validate_connection(config: &Config, issues: &mut Issues) {
+    let node = &config.node;
+    validate_signer("connection", node.signer, &node.keypair_path, node.remote_signer_socket.as_deref(), issues);
+    validate_url("connection.rpc_endpoint", &node.rpc_url, issues);
+    validate_url("connection.submit_endpoint", &node.submit_url, issues);
+    validate_url("dex_api.endpoint", &config.swap_api.base_url, issues);
+
+    if config.strategy.watch_flows || !config.markets.pools.is_empty() {
+        if node.geyser_url.as_deref().is_none_or(str::is_empty) {
+            issues.error(
+                "connection.geyser_endpoint",
+                "required by strategy.geyser_watch_enabled or markets.pools",
+            );
+        }
+        if node.geyser_token.is_none() {
+            issues.error(
+                "connection.geyser_auth_token",
+                "required by strategy.geyser_watch_enabled or markets.pools",
+            );
+        }
+    }
+
+    // Remote-signed transactions are built here and sent over RPC: the relay adapter needs keypairs
+    let remote_signer =
+        node.signer == SignerKind::Remote || config.wallets.iter().any(|w| w.signer == SignerKind::Remote);
+    if remote_signer {
+        if node.submission_services.iter().flatten().next().is_some() {
+            issues.error(
+                "connection.submission_services",
+                "not supported with signer = \"remote\"; remote-signed transactions go out over RPC",
+            );
+        }
+        if config.tx_cost.tip_sol > 0.0 || config.tx_cost.third_party_fee_profit_pct.is_some() {
+            issues.error(
+                "fees.relay_tip_sol",
+                "must be 0 (and third_party_fee_profit_pct unset) with signer = \"remote\"; no tip is paid",
+            );
+        }
+    }
+
+    for (i, name) in node.submission_services.iter().flatten().enumerate() {
+        let path = format!("connection.submission_services[{}]", i);
+        let lower = name.to_lowercase();
+        match SUBMISSION_SERVICES.iter().find(|(names, _, _)| names.contains(&lower.as_str())) {
+            None => {
+                let known: Vec<&str> = SUBMISSION_SERVICES.iter().map(|(names, _, _)| names[0]).collect();
+                issues.error(path, format!("unknown service \"{}\"; expected one of {}", name, known.join(", ")));
+            }
+            Some((_, key, read_key)) if read_key(&config.swap_api).is_empty() => {
+                issues.warn(path, format!("\"{}\" is ignored until dex_api.{} is set", name, key));
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+fn validate_strategy(strategy: &StrategyConfig, issues: &mut Issues) {
+    issues.pubkey("strategy.nonce_account_pubkey", &strategy.nonce_account);
+    if let Some(mint) = &strategy.quote_mint {
+        issues.pubkey("strategy.default_quote_mint", mint);
+    }
+    if let Some(program) = &strategy.profit_guard_program {
+        issues.pubkey("strategy.profit_guard_program", program);
+    }
+    if strategy.poll_quotes && strategy.poll_interval_ms == 0 {
+        issues.error("strategy.poll_interval_ms", "must be greater than 0");
+    }
+    if strategy.route_slippage_bps > 10_000 {
+        issues.error("strategy.route_slippage_bps", "must be at most 10000");
+    } else if strategy.route_slippage_bps > 0 {
+        issues.warn("strategy.route_slippage_bps", "above 0 lets merged routes land below min profit");
+    }
+    if !strategy.watch_flows && !strategy.poll_quotes {
+        issues.warn("strategy", "neither geyser_watch_enabled nor polling_enabled is set");
+    }
+
+    if strategy.base_tokens.is_empty() {
+        issues.error("strategy.instruments", "at least one instrument is required");
+    }
+    for (i, token) in strategy.base_tokens.iter().enumerate() {
+        let path = format!("strategy.instruments[{}]", i);
+        issues.pubkey(format!("{}.token_mint", path), &token.mint);
+        let [min, max] = token.amount_range;
+        if !(min > 0.0 && min.is_finite() && max.is_finite()) {
+            issues.error(format!("{}.notional_range", path), "bounds must be positive finite numbers");
+        } else if min > max {
+            issues.error(
+                format!("{}.notional_range", path),
+                format!("lower bound {} is above upper bound {}", min, max),
+            );
+        }
+        if token.steps < 2 {
+            issues.error(format!("{}.grid_steps", path), format!("must be at least 2, got {}", token.steps));
+        }
+        if token.min_profit < 0.0 {
+            issues.error(format!("{}.min_profit_quote_units", path), "must not be negative");
+        }
+        if token.min_profit_usd.is_some_and(|usd| usd < 0.0) {
+            issues.error(format!("{}.min_profit_usd", path), "must not be negative");
+        }
+        if token.min_balance < 0.0 {
+            issues.error(format!("{}.min_balance", path), "must not be negative");
+        }
+        for (j, mint) in token.target_mints.iter().enumerate() {
+            issues.pubkey(format!("{}.target_mints[{}]", path, j), mint);
+            if *mint == token.mint {
+                issues.error(format!("{}.target_mints[{}]", path, j), "an instrument cannot target itself");
+            }
+        }
+        if token.max_route_hops == Some(0) {
+            issues.error(format!("{}.max_route_hops", path), "must be at least 1");
+        }
+        if let Some(dex) = token.dexes.iter().find(|d| token.exclude_dexes.contains(d)) {
+            issues.warn(
+                format!("{}.exclude_dexes", path),
+                format!("\"{}\" is also in dexes; it will never be used", dex),
+            );
+        }
+        if strategy.base_tokens[..i].iter().any(|t| t.mint == token.mint) {
+            issues.warn(format!("{}.token_mint", path), "instrument listed twice");
+        }
+    }
+}
+
+fn validate_fees(fees: &TxCostConfig, issues: &mut Issues) {
+    if fees.compute_units == 0 || fees.compute_units > 1_400_000 {
+        issues.error("fees.compute_unit_limit", "must be between 1 and 1400000");
+    }
+    if fees.tip_sol < 0.0 {
+        issues.error("fees.relay_tip_sol", "must not be negative");
+    }
+    if let Some(pct) = fees.third_party_fee_profit_pct {
+        if !(pct > 0.0 && pct <= 1.0) {
+            issues.error(
+                "fees.third_party_fee_profit_pct",
+                format!("must be in (0, 1] (e.g. 0.5 = 50%), got {}", pct),
+            );
+        }
+    }
+    if fees.sol_usd <= 0.0 {
+        issues.error("fees.sol_price_usd", "must be greater than 0");
+    }
+}
+
+fn validate_sol_price(sol_price: &SolPriceConfig, issues: &mut Issues) {
+    if sol_price.sources.is_empty() {
+        issues.error("sol_price.sources", "must list at least one source");
+    }
+    if sol_price.refresh_interval_secs == 0 {
+        issues.error("sol_price.refresh_interval_secs", "must be greater than 0");
+    }
+    if sol_price.sources.contains(&PriceSourceKind::Pyth) {
+        issues.pubkey("sol_price.pyth_account", &sol_price.pyth_account);
+    }
+    if sol_price.sources.contains(&PriceSourceKind::Switchboard) {
+        match &sol_price.switchboard_account {
+            Some(account) => issues.pubkey("sol_price.switchboard_account", account),
+            None => issues.error("sol_price.switchboard_account", "required when sources include switchboard"),
+        }
+    }
+    if sol_price.max_source_age_secs < sol_price.refresh_interval_secs {
+        issues.warn(
+            "sol_price.max_source_age_secs",
+            "is shorter than refresh_interval_secs; sources expire between refreshes",
+        );
+    }
+    if sol_price.stale_after_secs > 0 && sol_price.stale_after_secs < sol_price.refresh_interval_secs {
+        issues.warn(
+            "sol_price.stale_after_secs",
+            "is shorter than refresh_interval_secs; non-SOL instruments will pause between refreshes",
+        );
+    }
+}
+
+fn validate_markets(markets: &MarketConfig, issues: &mut Issues) {
+    for (i, pool) in markets.pools.iter().enumerate() {
+        issues.pubkey(format!("markets.pools[{}]", i), pool);
+    }
+    if markets.direct_slippage_bps > 10_000 {
+        issues.error("markets.direct_slippage_bps", "must be at most 10000");
+    }
+    if markets.direct_execution_enabled && markets.pools.is_empty() {
+        issues.warn("markets.direct_execution_enabled", "has no effect without markets.pools");
+    }
+}
+
+fn validate_flash_loan(flash_loan: &FlashLoanConfig, issues: &mut Issues) {
+    if !flash_loan.enabled {
+        return;
+    }
+    issues.pubkey("flash_loan.program", &flash_loan.program);
+    issues.pubkey("flash_loan.lending_market", &flash_loan.lending_market);
+    if flash_loan.reserves.is_empty() {
+        issues.warn("flash_loan.reserves", "enabled without reserves; every instrument uses inventory");
+    }
+    for (i, reserve) in flash_loan.reserves.iter().enumerate() {
+        issues.pubkey(format!("flash_loan.reserves[{}].token_mint", i), &reserve.mint);
+        issues.pubkey(format!("flash_loan.reserves[{}].reserve", i), &reserve.reserve);
+    }
+}
+
+fn validate_inventory(inventory: &InventoryConfig, issues: &mut Issues) {
+    if inventory.refresh_interval_ms == 0 {
+        issues.error("inventory.refresh_interval_ms", "must be greater than 0");
+    }
+    if inventory.min_sol_reserve < 0.0 {
+        issues.error("inventory.min_sol_reserve", "must not be negative");
+    }
+}
+
+fn validate_rebalance(rebalance: &RebalanceConfig, issues: &mut Issues) {
+    if !rebalance.enabled {
+        return;
+    }
+    if rebalance.interval_secs == 0 {
+        issues.error("rebalance.interval_secs", "must be greater than 0");
+    }
+    if !(rebalance.drift_threshold > 0.0 && rebalance.drift_threshold <= 1.0) {
+        issues.error("rebalance.drift_threshold", "must be in (0, 1]");
+    }
+    if rebalance.max_slippage_bps > 10_000 {
+        issues.error("rebalance.max_slippage_bps", "must be at most 10000");
+    }
+    if rebalance.target_weights.len() < 2 {
+        issues.error("rebalance.target_weights", "at least two tokens are needed to rebalance");
+    }
+    for (i, weight) in rebalance.target_weights.iter().enumerate() {
+        issues.pubkey(format!("rebalance.target_weights[{}].token_mint", i), &weight.mint);
+        if weight.weight < 0.0 {
+            issues.error(format!("rebalance.target_weights[{}].weight", i), "must not be negative");
+        }
+    }
+    if rebalance.target_weights.iter().map(|w| w.weight).sum::<f64>() <= 0.0 {
+        issues.error("rebalance.target_weights", "weights must sum to more than 0");
+    }
+}
+
+fn validate_alerts(config: &Config, issues: &mut Issues) {
+    let alerts = &config.alerts;
+    if !alerts.enabled {
+        return;
+    }
+    if alerts.sinks.is_empty() {
+        issues.warn("alerts.sinks", "alerts are enabled without sinks; nothing is delivered");
+    }
+    for (i, sink) in alerts.sinks.iter().enumerate() {
+        let path = format!("alerts.sinks[{}]", i);
+        match sink.kind {
+            AlertSinkKind::Telegram => {
+                if sink.bot_token.trim().is_empty() {
+                    issues.error(format!("{}.bot_token", path), "required for a telegram sink");
+                }
+                if sink.chat_id.trim().is_empty() {
+                    issues.error(format!("{}.chat_id", path), "required for a telegram sink");
+                }
+                if !sink.url.is_empty() {
+                    validate_url(&format!("{}.url", path), &sink.url, issues);
+                }
+            }
+            _ => validate_url(&format!("{}.url", path), &sink.url, issues),
+        }
+    }
+    if alerts.max_batch == 0 {
+        issues.error("alerts.max_batch", "must be greater than 0");
+    }
+    if alerts.sol_balance_floor < 0.0 {
+        issues.error("alerts.sol_balance_floor", "must not be negative");
+    }
+    if alerts.landed_profit_above.is_some_and(|p| p < 0.0) {
+        issues.error("alerts.landed_profit_above", "must not be negative");
+    }
+    if !config.venues.track_landings {
+        if alerts.landed_profit_above.is_some() {
+            issues.warn("alerts.landed_profit_above", "has no effect while venues.track_landings is off");
+        }
+        if alerts.failed_submissions > 0 {
+            issues.warn(
+                "alerts.failed_submissions",
+                "only counts submissions that fail to send (not relay submissions that fail to land) while venues.track_landings is off",
+            );
+        }
+    }
+}
+
+fn validate_scheduler(scheduler: &SchedulerConfig, issues: &mut Issues) {
+    if !scheduler.enabled {
+        return;
+    }
+    if scheduler.max_age_ms == 0 {
+        issues.error("scheduler.max_age_ms", "must be greater than 0");
+    }
+    if scheduler.queue_capacity == 0 {
+        issues.error("scheduler.queue_capacity", "must be greater than 0");
+    }
+    if !(scheduler.landing_prior > 0.0 && scheduler.landing_prior <= 1.0) {
+        issues.error("scheduler.landing_prior", format!("must be in (0, 1], got {}", scheduler.landing_prior));
+    }
+    if scheduler.landing_prior_weight < 0.0 {
+        issues.error("scheduler.landing_prior_weight", "must not be negative");
+    }
+}
+
+fn validate_venues(venues: &VenueConfig, issues: &mut Issues) {
+    if venues.blacklist_after_failures == 0 {
+        return;
+    }
+    if venues.failure_window_secs == 0 {
+        issues.error("venues.failure_window_secs", "must be greater than 0");
+    }
+    if venues.blacklist_secs == 0 {
+        issues.error("venues.blacklist_secs", "must be greater than 0");
+    }
+    if !venues.simulate_before_submit && !venues.track_landings {
+        issues.warn(
+            "venues.blacklist_after_failures",
+            "has no effect with both simulate_before_submit and track_landings off",
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Severity::{Error, Warning};
+
+    const BASE: &str = r#"
+        [connection]
+        signer_keypair_path = "./id.json"
+        rpc_endpoint = "https://rpc.example"
+        submit_endpoint = "https://rpc.example"
+
+        [dex_api]
+        endpoint = "https://quote-api.jup.ag/v6"
+
+        [strategy]
+        instruments = [
+            { token_mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", min_delta_threshold = 1000, min_profit_quote_units = 0.005, notional_range = [10, 600], grid_steps = 20 },
+        ]
+        nonce_account_pubkey = "SysvarRent111111111111111111111111111111111"
+        geyser_watch_enabled = false
+        polling_enabled = true
+
+        [fees]
+        compute_unit_limit = 500000
+        priority_fee_lamports = 0
+        relay_tip_sol = 0.00001
+    "#;
+
+    /// `BASE` with the tables of `patch` merged over it (nested tables merge, other values replace).
+    fn config_with(patch: &str) -> Config {
+        fn merge(base: &mut toml::Table, patch: toml::Table) {
+            for (key, value) in patch {
+                match (base.get_mut(&key), value) {
+                    (Some(toml::Value::Table(base)), toml::Value::Table(patch)) => merge(base, patch),
+                    (_, value) => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        let mut table: toml::Table = BASE.parse().unwrap();
+        merge(&mut table, patch.parse().unwrap_or_else(|e| panic!("bad patch {}: {}", patch, e)));
+        toml::Value::Table(table).try_into().unwrap_or_else(|e| panic!("patch {}: {}", patch, e))
+    }
+
+    /// Inline table of the base instrument with `overrides` replacing or adding fields.
+    fn instrument<'a>(overrides: &[(&'a str, &'a str)]) -> String {
+        let mut fields: Vec<(&'a str, &'a str)> = vec![
+            ("token_mint", "\"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v\""),
+            ("min_delta_threshold", "1000"),
+            ("min_profit_quote_units", "0.005"),
+            ("notional_range", "[10, 600]"),
+            ("grid_steps", "20"),
+        ];
+        for (key, value) in overrides {
+            match fields.iter_mut().find(|(k, _)| k == key) {
+                Some(field) => field.1 = *value,
+                None => fields.push((*key, *value)),
+            }
+        }
+        let fields: Vec<String> = fields.iter().map(|(k, v)| format!("{} = {}", k, v)).collect();
+        format!("{{ {} }}", fields.join(", "))
+    }
+
+    fn has_issue(patch: &str, severity: Severity, path: &str) -> bool {
+        validate_config(&config_with(patch))
+            .iter()
+            .any(|issue| issue.severity == severity && issue.path == path)
+    }
+
+    #[test]
+    fn base_config_has_no_issues() {
+        let issues = validate_config(&config_with(""));
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn reports_each_issue_at_its_path() {
+        let cases: &[(&str, Severity, &str)] = &[
+            // connection and signers
+            ("[connection]\nsigner = \"keystore\"\nsigner_keypair_path = \"\"", Error, "connection.signer_keypair_path"),
+            ("[connection]\nsigner = \"remote\"", Error, "connection.remote_signer_socket"),
+            ("[connection]\nsigner = \"remote\"\nremote_signer_socket = \"/run/s.sock\"", Error, "fees.relay_tip_sol"),
+            (
+                "[connection]\nsigner = \"remote\"\nremote_signer_socket = \"/run/s.sock\"\nsubmission_services = [\"jito\"]",
+                Error,
+                "connection.submission_services",
+            ),
+            ("[connection]\nrpc_endpoint = \"ws://rpc\"", Error, "connection.rpc_endpoint"),
+            ("[connection]\nsubmit_endpoint = \"rpc\"", Error, "connection.submit_endpoint"),
+            ("[dex_api]\nendpoint = \"jup\"", Error, "dex_api.endpoint"),
+            ("[strategy]\ngeyser_watch_enabled = true", Error, "connection.geyser_endpoint"),
+            ("[strategy]\ngeyser_watch_enabled = true", Error, "connection.geyser_auth_token"),
+            ("[connection]\nsubmission_services = [\"pigeon\"]", Error, "connection.submission_services[0]"),
+            ("[connection]\nsubmission_services = [\"jito\"]", Warning, "connection.submission_services[0]"),
+            // strategy
+            ("[strategy]\nnonce_account_pubkey = \"nope\"", Error, "strategy.nonce_account_pubkey"),
+            ("[strategy]\ndefault_quote_mint = \"nope\"", Error, "strategy.default_quote_mint"),
+            ("[strategy]\nprofit_guard_program = \"nope\"", Error, "strategy.profit_guard_program"),
+            ("[strategy]\npoll_interval_ms = 0", Error, "strategy.poll_interval_ms"),
+            ("[strategy]\nroute_slippage_bps = 10001", Error, "strategy.route_slippage_bps"),
+            ("[strategy]\nroute_slippage_bps = 5", Warning, "strategy.route_slippage_bps"),
+            ("[strategy]\npolling_enabled = false", Warning, "strategy"),
+            ("[strategy]\ninstruments = []", Error, "strategy.instruments"),
+            // fees
+            ("[fees]\ncompute_unit_limit = 0", Error, "fees.compute_unit_limit"),
+            ("[fees]\nrelay_tip_sol = -1.0", Error, "fees.relay_tip_sol"),
+            ("[fees]\nthird_party_fee_profit_pct = 1.5", Error, "fees.third_party_fee_profit_pct"),
+            ("[fees]\nsol_price_usd = 0.0", Error, "fees.sol_price_usd"),
+            // sol_price
+            ("[sol_price]\nsources = []", Error, "sol_price.sources"),
+            ("[sol_price]\nrefresh_interval_secs = 0", Error, "sol_price.refresh_interval_secs"),
+            ("[sol_price]\npyth_account = \"nope\"", Error, "sol_price.pyth_account"),
+            ("[sol_price]\nsources = [\"switchboard\"]", Error, "sol_price.switchboard_account"),
+            (
+                "[sol_price]\nsources = [\"switchboard\"]\nswitchboard_account = \"nope\"",
+                Error,
+                "sol_price.switchboard_account",
+            ),
+            ("[sol_price]\nmax_source_age_secs = 1", Warning, "sol_price.max_source_age_secs"),
+            ("[sol_price]\nstale_after_secs = 1", Warning, "sol_price.stale_after_secs"),
+            // token_prices
+            ("[token_prices]\nrefresh_interval_secs = 0", Error, "token_prices.refresh_interval_secs"),
+            ("[token_prices]\nprobe_usd = 0.0", Error, "token_prices.probe_usd"),
+            ("[token_prices]\nstale_after_secs = 1", Warning, "token_prices.stale_after_secs"),
+            // markets and flash loans
+            ("[markets]\npools = [\"nope\"]", Error, "markets.pools[0]"),
+            ("[markets]\npools = [\"nope\"]", Error, "connection.geyser_endpoint"),
+            ("[markets]\ndirect_slippage_bps = 10001", Error, "markets.direct_slippage_bps"),
+            ("[markets]\ndirect_execution_enabled = true", Warning, "markets.direct_execution_enabled"),
+            ("[flash_loan]\nenabled = true\nprogram = \"nope\"", Error, "flash_loan.program"),
+            ("[flash_loan]\nenabled = true", Error, "flash_loan.lending_market"),
+            ("[flash_loan]\nenabled = true", Warning, "flash_loan.reserves"),
+            (
+                "[flash_loan]\nenabled = true\nreserves = [{ token_mint = \"nope\", reserve = \"nope\" }]",
+                Error,
+                "flash_loan.reserves[0].reserve",
+            ),
+            // inventory and rebalance
+            ("[inventory]\nrefresh_interval_ms = 0", Error, "inventory.refresh_interval_ms"),
+            ("[inventory]\nmin_sol_reserve = -1.0", Error, "inventory.min_sol_reserve"),
+            ("[rebalance]\nenabled = true\ninterval_secs = 0", Error, "rebalance.interval_secs"),
+            ("[rebalance]\nenabled = true\ndrift_threshold = 0.0", Error, "rebalance.drift_threshold"),
+            ("[rebalance]\nenabled = true\nmax_slippage_bps = 10001", Error, "rebalance.max_slippage_bps"),
+            ("[rebalance]\nenabled = true", Error, "rebalance.target_weights"),
+            (
+                "[rebalance]\nenabled = true\ntarget_weights = [{ token_mint = \"nope\", weight = -1.0 }]",
+                Error,
+                "rebalance.target_weights[0].token_mint",
+            ),
+            (
+                "[rebalance]\nenabled = true\ntarget_weights = [{ token_mint = \"nope\", weight = -1.0 }]",
+                Error,
+                "rebalance.target_weights[0].weight",
+            ),
+            // venues, scheduler, risk
+            ("[venues]\nfailure_window_secs = 0", Error, "venues.failure_window_secs"),
+            ("[venues]\nblacklist_secs = 0", Error, "venues.blacklist_secs"),
+            ("[venues]\ntrack_landings = false", Warning, "venues.blacklist_after_failures"),
+            (
+                "[venues]\ntrack_landings = false\n[risk]\nmax_consecutive_losses = 5",
+                Warning,
+                "risk.max_consecutive_losses",
+            ),
+            ("[scheduler]\nmax_age_ms = 0", Error, "scheduler.max_age_ms"),
+            ("[scheduler]\nqueue_capacity = 0", Error, "scheduler.queue_capacity"),
+            ("[scheduler]\nlanding_prior = 0.0", Error, "scheduler.landing_prior"),
+            ("[scheduler]\nlanding_prior_weight = -1.0", Error, "scheduler.landing_prior_weight"),
+            // logging, storage, telemetry, dashboard
+            ("[logging]\nevent_log_dir = \" \"", Error, "logging.event_log_dir"),
+            ("[logging]\nevent_log_prefix = \"\"", Error, "logging.event_log_prefix"),
+            ("[opportunity_db]\npath = \"\"", Error, "opportunity_db.path"),
+            ("[telemetry]\nenabled = true\nlisten_addr = \"localhost\"", Error, "telemetry.listen_addr"),
+            ("[telemetry]\nlisten_addr = \"localhost\"", Error, "telemetry.listen_addr"),
+            ("[telemetry]\nhealth_listen_addr = \"localhost\"", Error, "telemetry.health_listen_addr"),
+            ("[dashboard]\nenabled = true\nrefresh_ms = 0", Error, "dashboard.refresh_ms"),
+            // alerts
+            ("[alerts]\nenabled = true", Warning, "alerts.sinks"),
+            ("[alerts]\nenabled = true\nsinks = [{ kind = \"telegram\" }]", Error, "alerts.sinks[0].bot_token"),
+            ("[alerts]\nenabled = true\nsinks = [{ kind = \"telegram\" }]", Error, "alerts.sinks[0].chat_id"),
+            (
+                "[alerts]\nenabled = true\nsinks = [{ kind = \"telegram\", bot_token = \"t\", chat_id = \"c\", url = \"api\" }]",
+                Error,
+                "alerts.sinks[0].url",
+            ),
+            ("[alerts]\nenabled = true\nsinks = [{ kind = \"webhook\", url = \"hook\" }]", Error, "alerts.sinks[0].url"),
+            ("[alerts]\nenabled = true\nmax_batch = 0", Error, "alerts.max_batch"),
+            ("[alerts]\nenabled = true\nsol_balance_floor = -1.0", Error, "alerts.sol_balance_floor"),
+            ("[alerts]\nenabled = true\nlanded_profit_above = -1.0", Error, "alerts.landed_profit_above"),
+            (
+                "[alerts]\nenabled = true\nlanded_profit_above = 1.0\n[venues]\ntrack_landings = false",
+                Warning,
+                "alerts.landed_profit_above",
+            ),
+            ("[alerts]\nenabled = true\n[venues]\ntrack_landings = false", Warning, "alerts.failed_submissions"),
+            // extra wallets
+            (
+                "[[wallets]]\nsigner = \"remote\"\nnonce_account_pubkey = \"SysvarC1ock11111111111111111111111111111111\"",
+                Error,
+                "wallets[0].remote_signer_socket",
+            ),
+            (
+                "[[wallets]]\nsigner_keypair_path = \"w1.json\"\nnonce_account_pubkey = \"nope\"",
+                Error,
+                "wallets[0].nonce_account_pubkey",
+            ),
+            (
+                "[[wallets]]\nsigner_keypair_path = \"w1.json\"\nnonce_account_pubkey = \"SysvarRent111111111111111111111111111111111\"",
+                Error,
+                "wallets[0].nonce_account_pubkey",
+            ),
+        ];
+        for (patch, severity, path) in cases {
+            assert!(has_issue(patch, *severity, path), "expected {:?} at {} for:\n{}", severity, path, patch);
+        }
+    }
+
+    #[test]
+    fn reports_instrument_issues() {
+        let cases: &[(&[(&str, &str)], Severity, &str)] = &[
+            (&[("token_mint", "\"nope\"")], Error, "token_mint"),
+            (&[("notional_range", "[0, 10]")], Error, "notional_range"),
+            (&[("notional_range", "[10, 5]")], Error, "notional_range"),
+            (&[("grid_steps", "1")], Error, "grid_steps"),
+            (&[("min_profit_quote_units", "-1.0")], Error, "min_profit_quote_units"),
+            (&[("min_profit_usd", "-1.0")], Error, "min_profit_usd"),
+            (&[("min_balance", "-1.0")], Error, "min_balance"),
+            (&[("target_mints", "[\"nope\"]")], Error, "target_mints[0]"),
+            (&[("target_mints", "[\"EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v\"]")], Error, "target_mints[0]"),
+            (&[("max_route_hops", "0")], Error, "max_route_hops"),
+            (&[("dexes", "[\"Raydium\"]"), ("exclude_dexes", "[\"Raydium\"]")], Warning, "exclude_dexes"),
+        ];
+        for (overrides, severity, field) in cases {
+            let patch = format!("[strategy]\ninstruments = [{}]", instrument(overrides));
+            let path = format!("strategy.instruments[0].{}", field);
+            assert!(has_issue(&patch, *severity, &path), "expected {:?} at {} for:\n{}", severity, path, patch);
+        }
+
+        let twice = format!("[strategy]\ninstruments = [{}, {}]", instrument(&[]), instrument(&[]));
+        assert!(has_issue(&twice, Warning, "strategy.instruments[1].token_mint"));
+    }
+
+    #[test]
+    fn remote_signer_without_tips_or_relays_is_valid() {
+        let patch = "[connection]\nsigner = \"remote\"\nremote_signer_socket = \"/run/s.sock\"\n[fees]\nrelay_tip_sol = 0.0";
+        let errors: Vec<_> = validate_config(&config_with(patch))
+            .into_iter()
+            .filter(|issue| issue.severity == Error)
+            .collect();
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+}
@@ -0,0 +1,52 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct VenueConfig {
+    /// Jupiter DEX labels never routed through, for every instrument (added to each
+    /// instrument's own `exclude_dexes`).
+    #[serde(rename = "deny_dexes", alias = "exclude_dexes", default)]
+    pub deny_dexes: Vec<String>,
+    /// Failures of routes through a DEX within `failure_window_secs` that blacklist it
+    /// (0 = never blacklist automatically).
+    #[serde(rename = "blacklist_after_failures", alias = "max_failures", default = "default_blacklist_after_failures")]
+    pub blacklist_after_failures: u32,
+    #[serde(rename = "failure_window_secs", default = "default_failure_window_secs")]
+    pub failure_window_secs: u64,
+    /// How long a blacklisted DEX is excluded from quotes.
+    #[serde(rename = "blacklist_secs", alias = "blacklist_duration_secs", default = "default_blacklist_secs")]
+    pub blacklist_secs: u64,
+    /// Simulate each polling trade over RPC before submitting; failures count against the
+    /// route's DEXes and the trade is dropped.
+    #[serde(rename = "simulate_before_submit", alias = "preflight", default)]
+    pub simulate_before_submit: bool,
+    /// Watch each submitted trade's nonce and balance; trades that land without growing the
+    /// base-token balance count against the route's DEXes.
+    #[serde(rename = "track_landings", default = "default_track_landings")]
+    pub track_landings: bool,
+}
+
+impl Default for VenueConfig {
+    fn default() -> Self {
+        Self {
+            deny_dexes: Vec::new(),
+            blacklist_after_failures: default_blacklist_after_failures(),
+            failure_window_secs: default_failure_window_secs(),
+            blacklist_secs: default_blacklist_secs(),
+            simulate_before_submit: false,
+            track_landings: default_track_landings(),
+        }
+    }
+}
+
+fn default_blacklist_after_failures() -> u32 {
+    3
+}
+fn default_failure_window_secs() -> u64 {
+    600
+}
+fn default_blacklist_secs() -> u64 {
+    1_800
+}
+fn default_track_landings() -> bool {
+    true
+}
@@ -1,18 +1,60 @@
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use solana_sdk::pubkey::Pubkey;
 
 use crate::app::config;
+use crate::{STABLE_QUOTE_MINTS, WSOL};
 
 pub static BASE_TOKENS: Lazy<Vec<config::BaseTokenConfig>> =
     Lazy::new(|| config::CONFIG.strategy.base_tokens.clone());
 
-pub static NONCE_ADDR: Lazy<Pubkey> =
-    Lazy::new(|| Pubkey::from_str_const(&config::CONFIG.strategy.nonce_account));
+/// Wallet 0's nonce account, parsed (and reported when invalid) by `init_wallets`.
+pub static NONCE_ADDR: Lazy<Pubkey> = Lazy::new(|| crate::wallets()[0].nonce_account);
 
 pub static TARGET_TOKEN: Lazy<String> = Lazy::new(|| {
     config::CONFIG
         .strategy
         .quote_mint
         .clone()
-        .unwrap_or_else(|| "So11111111111111111111111111111111111111112".to_string())
+        .unwrap_or_else(|| WSOL.to_string())
 });
+
+static PROFIT_GUARD_PROGRAM: OnceCell<Option<Pubkey>> = OnceCell::new();
+
+/// Parse `profit_guard_program`. Called once at startup, before any trade is built.
+pub fn init_profit_guard_program() -> Result<Option<Pubkey>, anyhow::Error> {
+    PROFIT_GUARD_PROGRAM
+        .get_or_try_init(|| {
+            config::CONFIG
+                .strategy
+                .profit_guard_program
+                .as_deref()
+                .map(|id| {
+                    id.parse()
+                        .map_err(|e| anyhow::anyhow!("invalid strategy.profit_guard_program {}: {}", id, e))
+                })
+                .transpose()
+        })
+        .copied()
+}
+
+/// The profit guard program, when one is configured.
+pub fn profit_guard_program() -> Option<Pubkey> {
+    PROFIT_GUARD_PROGRAM.get().copied().flatten()
+}
+
+/// The instrument config for `mint`, if it is one.
+pub fn base_token_config(mint: &str) -> Option<&'static config::BaseTokenConfig> {
+    BASE_TOKENS.iter().find(|t| t.mint == mint)
+}
+
+/// Mints an instrument is arbitraged against: its `target_mints`, else USDC and USDT for SOL
+/// and `default_quote_mint` for everything else.
+pub fn instrument_targets(instrument: &config::BaseTokenConfig) -> Vec<String> {
+    if !instrument.target_mints.is_empty() {
+        instrument.target_mints.clone()
+    } else if instrument.mint == WSOL.to_string() {
+        STABLE_QUOTE_MINTS.iter().map(|m| m.to_string()).collect()
+    } else {
+        vec![TARGET_TOKEN.clone()]
+    }
+}
@@ -1,5 +1,4 @@
-use once_cell::sync::Lazy;
-use serde_json::Value;
+use once_cell::sync::{Lazy, OnceCell};
 use solana_client::nonblocking::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig,
@@ -8,40 +7,104 @@ use solana_sdk::{
 };
 use std::{fs, sync::Arc};
 
-use crate::app::config;
-
-fn load_keypair_from_str(key_str: &str, path: &str) -> Keypair {
-    if let Ok(json_val) = serde_json::from_str::<Value>(key_str) {
-        let bytes: Vec<u8> = serde_json::from_value(json_val).expect("Invalid JSON key format");
-        Keypair::try_from(&bytes[..]).expect("Failed to create Keypair from JSON")
-    } else {
-        let key_bytes = bs58::decode(key_str)
-            .into_vec()
-            .unwrap_or_else(|e| panic!("Key file is not valid Base58: {}. Path: {}", e, path));
-        Keypair::try_from(&key_bytes[..])
-            .unwrap_or_else(|e| panic!("Failed to create Keypair from key file: {}. Path: {}", e, path))
+use crate::app::config::{self, SignerKind};
+use crate::app::settings::signer::*;
+
+/// A trading wallet: its signer, the durable nonce account it advances, and the keypair list
+/// handed to the relayer adapter (empty for a remote signer).
+pub struct TradingWallet {
+    pub index: usize,
+    pub pubkey: Pubkey,
+    pub signer: &'static WalletSigner,
+    pub nonce_account: Pubkey,
+    pub signers: Vec<&'static Keypair>,
+}
+
+static WALLETS: OnceCell<Vec<TradingWallet>> = OnceCell::new();
+
+/// Load wallet 0 from `[connection]` and any `[[wallets]]`. Call once at startup, before
+/// anything touches `PUBKEY`, `SIGNERS` or `wallets()`.
+pub fn init_wallets() -> Result<&'static [TradingWallet], anyhow::Error> {
+    WALLETS.get_or_try_init(load_wallets).map(Vec::as_slice)
+}
+
+fn load_wallets() -> Result<Vec<TradingWallet>, anyhow::Error> {
+    let node = &config::CONFIG.node;
+    let mut specs = vec![(
+        node.signer,
+        node.keypair_path.as_str(),
+        node.remote_signer_socket.as_deref(),
+        config::CONFIG.strategy.nonce_account.as_str(),
+    )];
+    specs.extend(config::CONFIG.wallets.iter().map(|w| {
+        (
+            w.signer,
+            w.keypair_path.as_str(),
+            w.remote_signer_socket.as_deref(),
+            w.nonce_account.as_str(),
+        )
+    }));
+
+    let mut wallets: Vec<TradingWallet> = Vec::with_capacity(specs.len());
+    for (index, (kind, path, socket, nonce_account)) in specs.into_iter().enumerate() {
+        // Wallets live for the whole process; leaking gives the adapter its 'static keypairs
+        let signer: &'static WalletSigner = Box::leak(Box::new(load_signer(kind, path, socket)?));
+        let pubkey = signer.pubkey();
+        let nonce_account: Pubkey = nonce_account
+            .parse()
+            .map_err(|e| anyhow::anyhow!("wallet {}: invalid nonce account {}: {}", index, nonce_account, e))?;
+        if wallets.iter().any(|w| w.pubkey == pubkey || w.nonce_account == nonce_account) {
+            return Err(anyhow::anyhow!("wallet {}: signer or nonce account used twice", index));
+        }
+        wallets.push(TradingWallet {
+            index,
+            pubkey,
+            signer,
+            nonce_account,
+            signers: signer.keypair().into_iter().collect(),
+        });
     }
+    Ok(wallets)
 }
 
-pub static PRIVATE_KEY: Lazy<Keypair> = Lazy::new(|| {
-    let path = config::CONFIG.node.keypair_path.clone();
-    let content = fs::read_to_string(&path).expect("Unable to load Key file");
-    load_keypair_from_str(content.trim(), &path)
-});
+fn load_signer(kind: SignerKind, path: &str, socket: Option<&str>) -> Result<WalletSigner, anyhow::Error> {
+    match kind {
+        SignerKind::File => {
+            let content = fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Unable to read key file {}: {}", path, e))?;
+            let keypair = keypair_from_str(content.trim()).map_err(|e| anyhow::anyhow!("{} (path: {})", e, path))?;
+            Ok(WalletSigner::Local(keypair))
+        }
+        SignerKind::Keystore => {
+            let content = fs::read_to_string(path)
+                .map_err(|e| anyhow::anyhow!("Unable to read keystore {}: {}", path, e))?;
+            let password = keystore_password(&format!("Password for keystore {}: ", path))?;
+            Ok(WalletSigner::Local(decrypt_keystore(&content, &password)?))
+        }
+        SignerKind::Remote => {
+            let socket =
+                socket.ok_or_else(|| anyhow::anyhow!("signer = \"remote\" requires remote_signer_socket"))?;
+            Ok(WalletSigner::Remote(RemoteSigner::connect(socket)?))
+        }
+    }
+}
+
+/// All trading wallets; index 0 is the `[connection]` wallet. Panics before `init_wallets`.
+pub fn wallets() -> &'static [TradingWallet] {
+    WALLETS.get().expect("wallets not initialized")
+}
 
-pub async fn keyfile_status() -> Keypair {
-    let path = config::CONFIG.node.keypair_path.clone();
-    let content = fs::read_to_string(&path).expect("Unable to load Key file");
-    load_keypair_from_str(content.trim(), &path)
+/// The `[connection]` wallet's signer.
+pub fn wallet() -> &'static WalletSigner {
+    wallets()[0].signer
 }
 
-pub fn get_signer() -> String {
-    let path = config::CONFIG.node.keypair_path.clone();
-    let content = fs::read_to_string(&path).expect("Unable to load Key file");
-    content.trim().to_string()
+/// Trading wallet with signer `pubkey`, if it is one of ours.
+pub fn wallet_by_pubkey(pubkey: &Pubkey) -> Option<&'static TradingWallet> {
+    wallets().iter().find(|w| w.pubkey == *pubkey)
 }
 
-pub static PUBKEY: Lazy<Pubkey> = Lazy::new(|| PRIVATE_KEY.pubkey());
+pub static PUBKEY: Lazy<Pubkey> = Lazy::new(|| wallets()[0].pubkey);
 
 pub static RPC_ENDPOINT: Lazy<String> = Lazy::new(|| config::CONFIG.node.rpc_url.clone());
 pub static SUBMIT_ENDPOINT: Lazy<String> = Lazy::new(|| config::CONFIG.node.submit_url.clone());
@@ -65,4 +128,5 @@ pub static YELLOWSTONE_GRPC_ENDPOINT: Lazy<Option<String>> =
 pub static YELLOWSTONE_GRPC_TOKEN: Lazy<Option<String>> =
     Lazy::new(|| config::CONFIG.node.geyser_token.clone());
 
-pub static SIGNERS: Lazy<Vec<&'static Keypair>> = Lazy::new(|| vec![&*PRIVATE_KEY]);
+/// Wallet 0's keypair signers for the relayer adapter; empty with a remote signer.
+pub static SIGNERS: Lazy<Vec<&'static Keypair>> = Lazy::new(|| wallets()[0].signers.clone());
@@ -0,0 +1,31 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::app::config::FlashLoanConfig;
+
+/// Lending program, market and per-mint reserves of `[flash_loan]`, parsed.
+#[derive(Debug, Clone)]
+pub struct FlashLoanIds {
+    pub program: Pubkey,
+    pub market: Pubkey,
+    /// (base token mint, reserve)
+    pub reserves: Vec<(String, Pubkey)>,
+}
+
+/// Parse the `[flash_loan]` addresses; the error names the offending field.
+pub fn parse_flash_loan_ids(config: &FlashLoanConfig) -> Result<FlashLoanIds, anyhow::Error> {
+    let parse = |path: String, value: &str| -> Result<Pubkey, anyhow::Error> {
+        value
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid {} {}: {}", path, value, e))
+    };
+    let mut reserves = Vec::with_capacity(config.reserves.len());
+    for (i, r) in config.reserves.iter().enumerate() {
+        parse(format!("flash_loan.reserves[{}].token_mint", i), &r.mint)?;
+        reserves.push((r.mint.clone(), parse(format!("flash_loan.reserves[{}].reserve", i), &r.reserve)?));
+    }
+    Ok(FlashLoanIds {
+        program: parse("flash_loan.program".to_string(), &config.program)?,
+        market: parse("flash_loan.lending_market".to_string(), &config.lending_market)?,
+        reserves,
+    })
+}
@@ -0,0 +1,21 @@
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use tracing::error;
+
+use crate::app::config;
+
+/// Pool accounts to mirror into the local pool state cache.
+pub static MARKET_POOLS: Lazy<Vec<Pubkey>> = Lazy::new(|| {
+    config::CONFIG
+        .markets
+        .pools
+        .iter()
+        .filter_map(|addr| match addr.parse() {
+            Ok(pool) => Some(pool),
+            Err(e) => {
+                error!(pool = %addr, error = %e, "Invalid pool address in [markets].pools; pool skipped");
+                None
+            }
+        })
+        .collect()
+});
@@ -1,11 +1,17 @@
 pub mod arbitrage;
 pub mod credential;
 pub mod fee;
+pub mod flash_loan;
+pub mod market;
 pub mod relayer;
 pub mod services;
+pub mod signer;
 
 pub use arbitrage::*;
 pub use credential::*;
 pub use fee::*;
+pub use flash_loan::*;
+pub use market::*;
 pub use relayer::*;
 pub use services::*;
+pub use signer::*;
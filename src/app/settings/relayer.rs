@@ -5,6 +5,13 @@
 
 use once_cell::sync::Lazy;
 use solana_relayer_adapter_rust::ultra_submit::{ServiceConfig, ServiceClient, ultra_submit_simple};
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    message::{v0, VersionedMessage},
+    transaction::VersionedTransaction,
+};
+use tracing::{info, warn};
 
 use crate::app::config;
 
@@ -117,7 +124,23 @@ pub fn use_low_latency_submission() -> bool {
     !RELAYER_SERVICES.is_empty()
 }
 
+/// Relay path `submit_with_services` takes for `signers`: the configured service names
+/// (comma-separated) or `rpc`.
+pub fn submission_label(signers: &[&solana_sdk::signature::Keypair]) -> String {
+    if signers.is_empty() || !use_low_latency_submission() {
+        return "rpc".to_string();
+    }
+    RELAYER_SERVICES.iter().map(|s| s.name).collect::<Vec<_>>().join(",")
+}
+
 /// Submit via low-latency services (when configured) or fall back to RPC. Call this instead of `ultra_submit` when you want config-driven submission.
+/// With a remote signer the adapter cannot sign (it needs `Keypair`s), so the transaction is
+/// built and signed here and sent over RPC only, without a tip.
+///
+/// False when the transaction was not sent (build, signing or RPC error); the caller still
+/// holds the nonce lease and should release it. Each such failure counts towards
+/// `alerts.failed_submissions`. The relay adapter reports no outcome, so relay submissions
+/// only count once landing tracking finds they did not land.
 pub async fn submit_with_services(
     tx_info: solana_relayer_adapter_rust::Tips,
     signers: &'static Vec<&'static solana_sdk::signature::Keypair>,
@@ -125,10 +148,39 @@ pub async fn submit_with_services(
     nonce_ix: solana_sdk::instruction::Instruction,
     alt: Vec<solana_sdk::message::AddressLookupTableAccount>,
     retry_count: u32,
-) {
+) -> bool {
+    let sent = send_with_services(tx_info, signers, recent_blockhash, nonce_ix, alt, retry_count).await;
+    if !sent {
+        crate::note_submission_failed("not sent");
+    }
+    sent
+}
+
+async fn send_with_services(
+    mut tx_info: solana_relayer_adapter_rust::Tips,
+    signers: &'static Vec<&'static solana_sdk::signature::Keypair>,
+    recent_blockhash: solana_sdk::hash::Hash,
+    nonce_ix: solana_sdk::instruction::Instruction,
+    alt: Vec<solana_sdk::message::AddressLookupTableAccount>,
+    retry_count: u32,
+) -> bool {
+    // Every submitter places the nonce advance and any compute budget it adds before pure_ix
+    let prefix_len = 1 + tx_info.cu.is_some() as usize + tx_info.priority_fee_micro_lamport.is_some() as usize;
+    if let Err(e) = crate::link_flash_repay(&mut tx_info.pure_ix, prefix_len) {
+        warn!(error = %e, "Flash loan transaction not submitted");
+        return false;
+    }
+    if signers.is_empty() {
+        crate::SUBMISSIONS.with_label_values(&["rpc"]).inc();
+        return submit_with_wallet(tx_info, recent_blockhash, nonce_ix, alt, retry_count).await;
+    }
     if use_low_latency_submission() {
+        for service in RELAYER_SERVICES.iter() {
+            crate::SUBMISSIONS.with_label_values(&[service.name]).inc();
+        }
         ultra_submit_simple(tx_info, signers, recent_blockhash, nonce_ix, alt, retry_count, RELAYER_SERVICES.clone()).await;
     } else {
+        crate::SUBMISSIONS.with_label_values(&["rpc"]).inc();
         solana_relayer_adapter_rust::ultra_submit(
             tx_info,
             signers,
@@ -148,4 +200,54 @@ pub async fn submit_with_services(
         )
         .await;
     }
+    true
+}
+
+async fn submit_with_wallet(
+    tx_info: solana_relayer_adapter_rust::Tips,
+    recent_blockhash: solana_sdk::hash::Hash,
+    nonce_ix: solana_sdk::instruction::Instruction,
+    alt: Vec<solana_sdk::message::AddressLookupTableAccount>,
+    retry_count: u32,
+) -> bool {
+    let mut ixs = vec![nonce_ix];
+    if let Some(cu) = tx_info.cu {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(cu as u32));
+    }
+    if let Some(price) = tx_info.priority_fee_micro_lamport {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+    }
+    ixs.extend(tx_info.pure_ix);
+
+    let Some(wallet) = crate::wallet_by_pubkey(&tx_info.payer) else {
+        warn!(payer = %tx_info.payer, "Payer is not a configured wallet");
+        return false;
+    };
+    let tx = match v0::Message::try_compile(&tx_info.payer, &ixs, &alt, recent_blockhash)
+        .map_err(anyhow::Error::from)
+        .and_then(|message| {
+            VersionedTransaction::try_new(VersionedMessage::V0(message), &[wallet.signer]).map_err(anyhow::Error::from)
+        }) {
+        Ok(tx) => tx,
+        Err(e) => {
+            warn!(error = %e, "Failed to build transaction for remote signer");
+            return false;
+        }
+    };
+
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        max_retries: Some(retry_count as usize),
+        ..Default::default()
+    };
+    match crate::SUBMIT_CLIENT.send_transaction_with_config(&tx, send_config).await {
+        Ok(signature) => {
+            info!(%signature, "Transaction sent over RPC");
+            true
+        }
+        Err(e) => {
+            warn!(error = %e, "RPC submission failed");
+            false
+        }
+    }
 }
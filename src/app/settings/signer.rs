@@ -0,0 +1,278 @@
+//! Wallet signer backends: a plaintext key file, an encrypted keystore (scrypt + AES-256-GCM),
+//! or a remote signer behind a local unix socket, all exposed as one `Signer`.
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    signer::{keypair::Keypair, Signer, SignerError},
+};
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+use tokio::runtime::RuntimeFlavor;
+
+/// Env var read for the keystore password before falling back to an interactive prompt.
+pub const KEYSTORE_PASSWORD_ENV: &str = "KEYSTORE_PASSWORD";
+
+const KEYSTORE_VERSION: u32 = 1;
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub enum WalletSigner {
+    Local(Keypair),
+    Remote(RemoteSigner),
+}
+
+impl WalletSigner {
+    /// The keypair when the key is held in-process (required by the relayer adapter).
+    pub fn keypair(&self) -> Option<&Keypair> {
+        match self {
+            WalletSigner::Local(keypair) => Some(keypair),
+            WalletSigner::Remote(_) => None,
+        }
+    }
+}
+
+impl Signer for WalletSigner {
+    fn try_pubkey(&self) -> Result<Pubkey, SignerError> {
+        match self {
+            WalletSigner::Local(keypair) => keypair.try_pubkey(),
+            WalletSigner::Remote(remote) => Ok(remote.pubkey),
+        }
+    }
+
+    fn try_sign_message(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        match self {
+            WalletSigner::Local(keypair) => keypair.try_sign_message(message),
+            WalletSigner::Remote(remote) => remote.sign(message),
+        }
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Parse a plaintext secret: JSON byte array (solana-keygen format) or base58.
+pub fn keypair_from_str(key_str: &str) -> Result<Keypair, anyhow::Error> {
+    if let Ok(json_val) = serde_json::from_str::<Value>(key_str) {
+        let bytes: Vec<u8> = serde_json::from_value(json_val)
+            .map_err(|e| anyhow::anyhow!("Invalid JSON key format: {}", e))?;
+        Keypair::try_from(&bytes[..]).map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {}", e))
+    } else {
+        let key_bytes = bs58::decode(key_str)
+            .into_vec()
+            .map_err(|e| anyhow::anyhow!("Key is neither JSON nor valid base58: {}", e))?;
+        Keypair::try_from(&key_bytes[..]).map_err(|e| anyhow::anyhow!("Invalid keypair bytes: {}", e))
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Encrypted keystore
+// -----------------------------------------------------------------------------
+
+/// Keystore file layout. Binary fields are base58.
+#[derive(Serialize, Deserialize)]
+struct Keystore {
+    version: u32,
+    pubkey: String,
+    kdf: KdfParams,
+    cipher: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct KdfParams {
+    name: String,
+    log_n: u8,
+    r: u32,
+    p: u32,
+    salt: String,
+}
+
+fn derive_key(password: &str, kdf: &KdfParams, salt: &[u8]) -> Result<[u8; 32], anyhow::Error> {
+    let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, 32)
+        .map_err(|e| anyhow::anyhow!("Invalid scrypt parameters: {}", e))?;
+    let mut key = [0u8; 32];
+    scrypt::scrypt(password.as_bytes(), salt, &params, &mut key)
+        .map_err(|e| anyhow::anyhow!("Key derivation failed: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt `keypair` under `password`; returns the keystore JSON.
+pub fn encrypt_keystore(keypair: &Keypair, password: &str) -> Result<String, anyhow::Error> {
+    let mut salt = [0u8; 32];
+    OsRng.fill_bytes(&mut salt);
+    let kdf = KdfParams {
+        name: "scrypt".to_string(),
+        log_n: SCRYPT_LOG_N,
+        r: SCRYPT_R,
+        p: SCRYPT_P,
+        salt: bs58::encode(salt).into_string(),
+    };
+    let key = derive_key(password, &kdf, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, keypair.to_bytes().as_ref())
+        .map_err(|_| anyhow::anyhow!("Encryption failed"))?;
+
+    let keystore = Keystore {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        kdf,
+        cipher: "aes-256-gcm".to_string(),
+        nonce: bs58::encode(nonce).into_string(),
+        ciphertext: bs58::encode(ciphertext).into_string(),
+    };
+    Ok(serde_json::to_string_pretty(&keystore)?)
+}
+
+/// Decrypt a keystore JSON with `password`.
+pub fn decrypt_keystore(json: &str, password: &str) -> Result<Keypair, anyhow::Error> {
+    let keystore: Keystore =
+        serde_json::from_str(json).map_err(|e| anyhow::anyhow!("Not a keystore file: {}", e))?;
+    if keystore.version != KEYSTORE_VERSION || keystore.kdf.name != "scrypt" || keystore.cipher != "aes-256-gcm" {
+        return Err(anyhow::anyhow!("Unsupported keystore format"));
+    }
+    let salt = bs58::decode(&keystore.kdf.salt).into_vec()?;
+    let nonce = bs58::decode(&keystore.nonce).into_vec()?;
+    let ciphertext = bs58::decode(&keystore.ciphertext).into_vec()?;
+    if nonce.len() != 12 {
+        return Err(anyhow::anyhow!("Invalid keystore nonce"));
+    }
+
+    let key = derive_key(password, &keystore.kdf, &salt)?;
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
+    let secret = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| anyhow::anyhow!("Wrong keystore password or corrupted keystore"))?;
+    let keypair =
+        Keypair::try_from(&secret[..]).map_err(|e| anyhow::anyhow!("Invalid keypair in keystore: {}", e))?;
+    if keypair.pubkey().to_string() != keystore.pubkey {
+        return Err(anyhow::anyhow!("Keystore pubkey does not match its key"));
+    }
+    Ok(keypair)
+}
+
+/// Keystore password from `KEYSTORE_PASSWORD`, else prompted on the terminal.
+pub fn keystore_password(prompt: &str) -> Result<String, anyhow::Error> {
+    if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+        return Ok(password);
+    }
+    rpassword::prompt_password(prompt).map_err(|e| anyhow::anyhow!("Failed to read password: {}", e))
+}
+
+// -----------------------------------------------------------------------------
+// Remote signer
+// -----------------------------------------------------------------------------
+
+/// Signer process reached over a unix socket with newline-delimited JSON:
+/// `{"method":"pubkey"}` -> `{"pubkey":"<base58>"}` and
+/// `{"method":"sign","message":"<base58>"}` -> `{"signature":"<base58>"}`
+/// (`{"error":"..."}` on refusal). Signatures are verified before use.
+pub struct RemoteSigner {
+    socket_path: String,
+    pubkey: Pubkey,
+}
+
+#[derive(Deserialize)]
+struct RemoteResponse {
+    pubkey: Option<String>,
+    signature: Option<String>,
+    error: Option<String>,
+}
+
+impl RemoteSigner {
+    pub fn connect(socket_path: &str) -> Result<Self, anyhow::Error> {
+        let response = remote_call(socket_path, &serde_json::json!({ "method": "pubkey" }))?;
+        let pubkey = response
+            .pubkey
+            .ok_or_else(|| anyhow::anyhow!("Remote signer returned no pubkey"))?
+            .parse()?;
+        Ok(Self {
+            socket_path: socket_path.to_string(),
+            pubkey,
+        })
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<Signature, SignerError> {
+        let request = serde_json::json!({
+            "method": "sign",
+            "message": bs58::encode(message).into_string(),
+        });
+        let response =
+            remote_call(&self.socket_path, &request).map_err(|e| SignerError::Connection(e.to_string()))?;
+        let signature: Signature = response
+            .signature
+            .ok_or_else(|| SignerError::Custom("Remote signer returned no signature".to_string()))?
+            .parse()
+            .map_err(|_| SignerError::Custom("Remote signer returned a malformed signature".to_string()))?;
+        if !signature.verify(self.pubkey.as_ref(), message) {
+            return Err(SignerError::Custom("Remote signature does not verify".to_string()));
+        }
+        Ok(signature)
+    }
+}
+
+/// The socket I/O blocks; on a multi-threaded runtime the worker's other tasks are handed off
+/// for the duration, so signing inside async code does not stall the executor.
+fn remote_call(socket_path: &str, request: &Value) -> Result<RemoteResponse, anyhow::Error> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| remote_call_blocking(socket_path, request))
+        }
+        _ => remote_call_blocking(socket_path, request),
+    }
+}
+
+fn remote_call_blocking(socket_path: &str, request: &Value) -> Result<RemoteResponse, anyhow::Error> {
+    let mut stream = UnixStream::connect(socket_path)
+        .map_err(|e| anyhow::anyhow!("Cannot reach remote signer at {}: {}", socket_path, e))?;
+    stream.set_read_timeout(Some(REMOTE_TIMEOUT))?;
+    stream.set_write_timeout(Some(REMOTE_TIMEOUT))?;
+    writeln!(stream, "{}", request)?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    let response: RemoteResponse = serde_json::from_str(line.trim())?;
+    if let Some(error) = response.error {
+        return Err(anyhow::anyhow!("Remote signer refused: {}", error));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keystore_round_trip() {
+        let keypair = Keypair::new();
+        let json = encrypt_keystore(&keypair, "correct horse").unwrap();
+        assert!(!json.contains(&bs58::encode(keypair.to_bytes()).into_string()));
+
+        let decrypted = decrypt_keystore(&json, "correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+        assert!(decrypt_keystore(&json, "wrong password").is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_keystore() {
+        let json = encrypt_keystore(&Keypair::new(), "pw").unwrap();
+        let mut keystore: Keystore = serde_json::from_str(&json).unwrap();
+        keystore.pubkey = Pubkey::new_unique().to_string();
+        let tampered = serde_json::to_string(&keystore).unwrap();
+        assert!(decrypt_keystore(&tampered, "pw").is_err());
+        assert!(decrypt_keystore("{}", "pw").is_err());
+    }
+}
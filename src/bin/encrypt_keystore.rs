@@ -0,0 +1,40 @@
+//! Encrypt a plaintext wallet key file into a keystore for `signer = "keystore"`.
+//!
+//! Usage: encrypt-keystore <plaintext key file> <keystore output>
+//! The password is read from `KEYSTORE_PASSWORD` or prompted (twice).
+
+use jupiter_arbitrage_bot_offchain::{encrypt_keystore, keypair_from_str, KEYSTORE_PASSWORD_ENV};
+use solana_sdk::signer::Signer;
+use std::fs;
+
+fn main() -> Result<(), anyhow::Error> {
+    let args: Vec<String> = std::env::args().collect();
+    let [_, input, output] = args.as_slice() else {
+        return Err(anyhow::anyhow!("Usage: encrypt-keystore <plaintext key file> <keystore output>"));
+    };
+    if fs::metadata(output).is_ok() {
+        return Err(anyhow::anyhow!("{} already exists; refusing to overwrite", output));
+    }
+
+    let content = fs::read_to_string(input)?;
+    let keypair = keypair_from_str(content.trim())?;
+
+    let password = match std::env::var(KEYSTORE_PASSWORD_ENV) {
+        Ok(password) => password,
+        Err(_) => {
+            let password = rpassword::prompt_password("New keystore password: ")?;
+            if rpassword::prompt_password("Repeat password: ")? != password {
+                return Err(anyhow::anyhow!("Passwords do not match"));
+            }
+            password
+        }
+    };
+    if password.is_empty() {
+        return Err(anyhow::anyhow!("Empty password"));
+    }
+
+    fs::write(output, encrypt_keystore(&keypair, &password)?)?;
+    println!("Keystore for {} written to {}", keypair.pubkey(), output);
+    println!("Set signer = \"keystore\" and signer_keypair_path = \"{}\", then delete {}", output, input);
+    Ok(())
+}
@@ -67,9 +67,15 @@ pub const PROGRAM_MAP: phf::Map<&'static str, &'static str> = phf_map! {
 pub const ROUTE_DISCRIMINATOR: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
 
 pub const WSOL: Pubkey = Pubkey::from_str_const("So11111111111111111111111111111111111111112");
+pub const USDC_MINT: Pubkey = Pubkey::from_str_const("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+pub const USDT_MINT: Pubkey = Pubkey::from_str_const("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB");
+/// Default quote targets of SOL.
+pub const STABLE_QUOTE_MINTS: [Pubkey; 2] = [USDC_MINT, USDT_MINT];
 
 pub const TOKEN_PROGRAM_ID: Pubkey =
     Pubkey::from_str_const("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
 pub const JUPITER_PROGRAM_ADDR: Pubkey =
     Pubkey::from_str_const("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
 pub const JUPITER_TRANSFER_AUTH: Pubkey =
@@ -77,6 +83,13 @@ pub const JUPITER_TRANSFER_AUTH: Pubkey =
 pub const JUPITER_EVENT_AUTH: Pubkey =
     Pubkey::from_str_const("D8cy77BBepLMngZx6ZukaTff5hCt1HrWyKk3Hnd9oitf");
 
+pub const RAYDIUM_AMM_PROGRAM: Pubkey =
+    Pubkey::from_str_const("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+pub const RAYDIUM_CPMM_PROGRAM: Pubkey =
+    Pubkey::from_str_const("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const WHIRLPOOL_PROGRAM: Pubkey =
+    Pubkey::from_str_const("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
 #[derive(Debug, Clone)]
 pub struct TokenInfo {
     pub mint: &'static str,
@@ -0,0 +1,228 @@
+//! Two-leg arbitrage built entirely from cached pool state: base -> target on one pool and
+//! target -> base on another. The second leg's minimum output is `in_amount + min_profit`,
+//! so the DEX program itself rejects a fill that would not be profitable.
+
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+use crate::{PoolSnapshot, PoolState, TOKEN_PROGRAM_ID};
+
+use super::{quote_exact_in, raydium_amm_swap_ix, raydium_cpmm_swap_ix, raydium_cpmm_token_program};
+
+#[derive(Debug, Clone)]
+pub struct DirectArbPlan {
+    pub base_mint: Pubkey,
+    pub target_mint: Pubkey,
+    pub leg1: PoolSnapshot,
+    pub leg2: PoolSnapshot,
+    pub amount_in: u64,
+    /// Target amount forwarded into leg 2 (leg 1 quote minus slippage allowance).
+    pub mid_amount: u64,
+    pub amount_out: u64,
+    /// Slot through which both pool snapshots were known to hold when quoted.
+    pub slot: u64,
+}
+
+/// Quote a round trip through `leg1` then `leg2`. None if either pool cannot be quoted locally
+/// or the round trip does not return more than `amount_in`.
+pub fn plan_direct_arb(
+    base_mint: &Pubkey,
+    target_mint: &Pubkey,
+    leg1: &PoolSnapshot,
+    leg2: &PoolSnapshot,
+    amount_in: u64,
+    slippage_bps: u64,
+) -> Option<DirectArbPlan> {
+    let leg1_out = quote_exact_in(leg1, base_mint, amount_in)?;
+    let mid_amount = leg1_out - (leg1_out as u128 * slippage_bps as u128 / 10_000) as u64;
+    let amount_out = quote_exact_in(leg2, target_mint, mid_amount)?;
+    if amount_out <= amount_in {
+        return None;
+    }
+
+    Some(DirectArbPlan {
+        base_mint: *base_mint,
+        target_mint: *target_mint,
+        leg1: leg1.clone(),
+        leg2: leg2.clone(),
+        amount_in,
+        mid_amount,
+        amount_out,
+        slot: leg1.valid_through_slot.min(leg2.valid_through_slot),
+    })
+}
+
+impl DirectArbPlan {
+    /// DEX labels of both legs, named as Jupiter routes name them, so landing outcomes feed
+    /// the same venue blacklist as routed trades.
+    pub fn venues(&self) -> Vec<String> {
+        let mut venues = vec![self.leg1.state.dex().to_string()];
+        if self.leg2.state.dex() != self.leg1.state.dex() {
+            venues.push(self.leg2.state.dex().to_string());
+        }
+        venues
+    }
+}
+
+/// Token program for `mint` as seen by `pool`.
+pub fn pool_token_program(pool: &PoolSnapshot, mint: &Pubkey) -> Option<Pubkey> {
+    match &pool.state {
+        PoolState::RaydiumAmm(_) => Some(TOKEN_PROGRAM_ID),
+        PoolState::RaydiumCpmm(state) => raydium_cpmm_token_program(state, mint),
+        PoolState::Whirlpool(_) => None,
+    }
+}
+
+/// Instructions for the plan: idempotent ATA create for the target token, then both legs.
+/// Needs no network access; the base-token ATA must already hold `amount_in`
+/// (for WSOL, a pre-funded wrapped SOL account).
+pub fn build_direct_arb_ixs(
+    plan: &DirectArbPlan,
+    owner: &Pubkey,
+    min_profit_raw: u64,
+) -> Option<Vec<Instruction>> {
+    let base_program = pool_token_program(&plan.leg1, &plan.base_mint)?;
+    let target_program = pool_token_program(&plan.leg1, &plan.target_mint)?;
+    let user_base =
+        get_associated_token_address_with_program_id(owner, &plan.base_mint, &base_program);
+    let user_target =
+        get_associated_token_address_with_program_id(owner, &plan.target_mint, &target_program);
+
+    Some(vec![
+        create_associated_token_account_idempotent(owner, owner, &plan.target_mint, &target_program),
+        leg_ix(
+            &plan.leg1,
+            owner,
+            &plan.base_mint,
+            user_base,
+            user_target,
+            plan.amount_in,
+            plan.mid_amount,
+        )?,
+        leg_ix(
+            &plan.leg2,
+            owner,
+            &plan.target_mint,
+            user_target,
+            user_base,
+            plan.mid_amount,
+            plan.amount_in.checked_add(min_profit_raw)?,
+        )?,
+    ])
+}
+
+fn leg_ix(
+    pool: &PoolSnapshot,
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    user_source: Pubkey,
+    user_destination: Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Option<Instruction> {
+    match &pool.state {
+        PoolState::RaydiumAmm(state) => Some(raydium_amm_swap_ix(
+            &pool.address,
+            state,
+            owner,
+            user_source,
+            user_destination,
+            amount_in,
+            minimum_amount_out,
+        )),
+        PoolState::RaydiumCpmm(state) => raydium_cpmm_swap_ix(
+            &pool.address,
+            state,
+            owner,
+            input_mint,
+            user_source,
+            user_destination,
+            amount_in,
+            minimum_amount_out,
+        ),
+        PoolState::Whirlpool(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RaydiumAmmPool;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    /// Raydium AMM pool of base `key(1)` against target `key(2)`.
+    fn amm_pool(address: u8, reserve_base: u64, reserve_target: u64) -> PoolSnapshot {
+        PoolSnapshot {
+            address: key(address),
+            state: PoolState::RaydiumAmm(RaydiumAmmPool {
+                status: 6,
+                base_need_take_pnl: 0,
+                quote_need_take_pnl: 0,
+                swap_fee_numerator: 25,
+                swap_fee_denominator: 10_000,
+                base_vault: key(address + 1),
+                quote_vault: key(address + 2),
+                base_mint: key(1),
+                quote_mint: key(2),
+                open_orders: key(address + 3),
+            }),
+            mint_a: key(1),
+            mint_b: key(2),
+            reserve_a: reserve_base,
+            reserve_b: reserve_target,
+            fee: (25, 10_000),
+            price_a_in_b: reserve_target as f64 / reserve_base as f64,
+            data_slot: address as u64,
+            valid_through_slot: address as u64,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn plans_a_round_trip_that_comes_out_ahead() {
+        let cheap = amm_pool(10, 1_000_000_000, 2_000_000_000);
+        let dear = amm_pool(20, 1_000_000_000, 1_000_000_000);
+
+        let plan = plan_direct_arb(&key(1), &key(2), &cheap, &dear, 1_000_000, 50).unwrap();
+        let leg1_out = quote_exact_in(&cheap, &key(1), 1_000_000).unwrap();
+        assert_eq!(plan.mid_amount, leg1_out - leg1_out * 50 / 10_000);
+        assert_eq!(plan.amount_out, quote_exact_in(&dear, &key(2), plan.mid_amount).unwrap());
+        assert!(plan.amount_out > plan.amount_in);
+        assert_eq!(plan.slot, 10);
+        assert_eq!(plan.venues(), vec!["Raydium".to_string()]);
+    }
+
+    #[test]
+    fn rejects_a_round_trip_that_does_not_come_out_ahead() {
+        let pool = amm_pool(10, 1_000_000_000, 2_000_000_000);
+        let other = amm_pool(20, 1_000_000_000, 2_000_000_000);
+        assert!(plan_direct_arb(&key(1), &key(2), &pool, &other, 1_000_000, 0).is_none());
+
+        // The profitable direction reversed loses on both legs.
+        let cheap = amm_pool(10, 1_000_000_000, 2_000_000_000);
+        let dear = amm_pool(20, 1_000_000_000, 1_000_000_000);
+        assert!(plan_direct_arb(&key(1), &key(2), &dear, &cheap, 1_000_000, 0).is_none());
+    }
+
+    #[test]
+    fn second_leg_demands_the_input_plus_min_profit() {
+        let cheap = amm_pool(10, 1_000_000_000, 2_000_000_000);
+        let dear = amm_pool(20, 1_000_000_000, 1_000_000_000);
+        let plan = plan_direct_arb(&key(1), &key(2), &cheap, &dear, 1_000_000, 50).unwrap();
+
+        let ixs = build_direct_arb_ixs(&plan, &key(30), 5_000).unwrap();
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[1].data[1..9], plan.amount_in.to_le_bytes());
+        assert_eq!(ixs[1].data[9..17], plan.mid_amount.to_le_bytes());
+        assert_eq!(ixs[2].data[1..9], plan.mid_amount.to_le_bytes());
+        assert_eq!(ixs[2].data[9..17], (plan.amount_in + 5_000).to_le_bytes());
+
+        assert!(build_direct_arb_ixs(&plan, &key(30), u64::MAX).is_none());
+    }
+}
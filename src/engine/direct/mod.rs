@@ -0,0 +1,12 @@
+//! Native swap instruction builders for common venues, driven by the local pool state cache.
+//! Lets an opportunity be turned into a signed transaction without calling the Jupiter API.
+
+pub mod compose;
+pub mod quote;
+pub mod raydium_amm;
+pub mod raydium_cpmm;
+
+pub use compose::*;
+pub use quote::*;
+pub use raydium_amm::*;
+pub use raydium_cpmm::*;
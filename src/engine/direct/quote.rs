@@ -0,0 +1,127 @@
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{PoolSnapshot, PoolState};
+
+use super::{raydium_amm_swappable, raydium_cpmm_swappable};
+
+/// Exact-in output of a swap on a cached pool. None for venues without a local quote model
+/// (concentrated liquidity), pools that are not accepting swaps, or an unknown input mint.
+pub fn quote_exact_in(pool: &PoolSnapshot, input_mint: &Pubkey, amount_in: u64) -> Option<u64> {
+    let swappable = match &pool.state {
+        PoolState::RaydiumAmm(state) => raydium_amm_swappable(state),
+        PoolState::RaydiumCpmm(state) => raydium_cpmm_swappable(state),
+        PoolState::Whirlpool(_) => false,
+    };
+    if !swappable {
+        return None;
+    }
+    let (reserve_in, reserve_out) = pool.reserves_for_input(input_mint)?;
+    constant_product_out(amount_in, reserve_in, reserve_out, pool.fee)
+}
+
+/// x*y=k output with the fee taken from the input (rounded up, as both Raydium programs do).
+pub fn constant_product_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee: (u64, u64),
+) -> Option<u64> {
+    let (fee_numerator, fee_denominator) = fee;
+    if fee_denominator == 0 || reserve_in == 0 || reserve_out == 0 {
+        return None;
+    }
+    let amount_in = amount_in as u128;
+    let fee_amount = (amount_in * fee_numerator as u128).div_ceil(fee_denominator as u128);
+    let amount_in_after_fee = amount_in.checked_sub(fee_amount)?;
+    let out = reserve_out as u128 * amount_in_after_fee / (reserve_in as u128 + amount_in_after_fee);
+    u64::try_from(out).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RaydiumAmmPool;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn amm_pool(status: u64, reserve_a: u64, reserve_b: u64) -> PoolSnapshot {
+        PoolSnapshot {
+            address: key(9),
+            state: PoolState::RaydiumAmm(RaydiumAmmPool {
+                status,
+                base_need_take_pnl: 0,
+                quote_need_take_pnl: 0,
+                swap_fee_numerator: 25,
+                swap_fee_denominator: 10_000,
+                base_vault: key(3),
+                quote_vault: key(4),
+                base_mint: key(1),
+                quote_mint: key(2),
+                open_orders: key(5),
+            }),
+            mint_a: key(1),
+            mint_b: key(2),
+            reserve_a,
+            reserve_b,
+            fee: (25, 10_000),
+            price_a_in_b: reserve_b as f64 / reserve_a as f64,
+            data_slot: 100,
+            valid_through_slot: 100,
+            stale: false,
+        }
+    }
+
+    #[test]
+    fn constant_product_matches_known_reserves() {
+        // fee = 2_500, 2e9 * 997_500 / (1e9 + 997_500) = 1_993_011.06...
+        assert_eq!(
+            constant_product_out(1_000_000, 1_000_000_000, 2_000_000_000, (25, 10_000)),
+            Some(1_993_011)
+        );
+        assert_eq!(constant_product_out(1_000, 1_000_000, 1_000_000, (0, 1)), Some(999));
+        assert_eq!(constant_product_out(0, 1_000_000, 1_000_000, (25, 10_000)), Some(0));
+    }
+
+    #[test]
+    fn constant_product_rounds_the_fee_up() {
+        // 1 * 25 / 10_000 rounds up to a whole unit of fee, leaving nothing to swap.
+        assert_eq!(constant_product_out(1, 1_000, 1_000, (25, 10_000)), Some(0));
+        // 401 * 25 / 10_000 = 1.0025 -> fee 2, so 399 is swapped rather than 400.
+        assert_eq!(
+            constant_product_out(401, 1_000_000, 1_000_000, (25, 10_000)),
+            constant_product_out(399, 1_000_000, 1_000_000, (0, 1))
+        );
+        // Exactly divisible fees are not rounded.
+        assert_eq!(
+            constant_product_out(400, 1_000_000, 1_000_000, (25, 10_000)),
+            constant_product_out(399, 1_000_000, 1_000_000, (0, 1))
+        );
+    }
+
+    #[test]
+    fn constant_product_rejects_degenerate_pools() {
+        assert_eq!(constant_product_out(1_000, 0, 1_000, (25, 10_000)), None);
+        assert_eq!(constant_product_out(1_000, 1_000, 0, (25, 10_000)), None);
+        assert_eq!(constant_product_out(1_000, 1_000, 1_000, (25, 0)), None);
+        assert_eq!(constant_product_out(1_000, 1_000, 1_000, (2, 1)), None);
+    }
+
+    #[test]
+    fn quotes_in_either_direction() {
+        let pool = amm_pool(6, 1_000_000_000, 2_000_000_000);
+        assert_eq!(quote_exact_in(&pool, &key(1), 1_000_000), Some(1_993_011));
+        assert_eq!(
+            quote_exact_in(&pool, &key(2), 1_000_000),
+            constant_product_out(1_000_000, 2_000_000_000, 1_000_000_000, (25, 10_000))
+        );
+        assert_eq!(quote_exact_in(&pool, &key(7), 1_000_000), None);
+    }
+
+    #[test]
+    fn does_not_quote_pools_that_refuse_swaps() {
+        assert!(quote_exact_in(&amm_pool(1, 1_000, 1_000), &key(1), 10).is_some());
+        assert_eq!(quote_exact_in(&amm_pool(2, 1_000, 1_000), &key(1), 10), None);
+    }
+}
@@ -0,0 +1,104 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{RaydiumAmmPool, RAYDIUM_AMM_PROGRAM, TOKEN_PROGRAM_ID};
+
+/// Raydium AMM v4 pool authority (the same PDA for every pool).
+pub const RAYDIUM_AMM_AUTHORITY: Pubkey =
+    Pubkey::from_str_const("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+
+/// `SwapBaseInV2`: exact-in swap without the OpenBook market accounts.
+const SWAP_BASE_IN_V2: u8 = 16;
+
+/// Pool statuses that accept swaps (Initialized, SwapOnly).
+pub fn raydium_amm_swappable(state: &RaydiumAmmPool) -> bool {
+    state.status == 1 || state.status == 6
+}
+
+pub fn raydium_amm_swap_ix(
+    pool: &Pubkey,
+    state: &RaydiumAmmPool,
+    owner: &Pubkey,
+    user_source: Pubkey,
+    user_destination: Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = Vec::with_capacity(17);
+    data.push(SWAP_BASE_IN_V2);
+    data.extend(amount_in.to_le_bytes());
+    data.extend(minimum_amount_out.to_le_bytes());
+
+    Instruction {
+        program_id: RAYDIUM_AMM_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new_readonly(RAYDIUM_AMM_AUTHORITY, false),
+            AccountMeta::new(state.base_vault, false),
+            AccountMeta::new(state.quote_vault, false),
+            AccountMeta::new(user_source, false),
+            AccountMeta::new(user_destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn pool(status: u64) -> RaydiumAmmPool {
+        RaydiumAmmPool {
+            status,
+            base_need_take_pnl: 0,
+            quote_need_take_pnl: 0,
+            swap_fee_numerator: 25,
+            swap_fee_denominator: 10_000,
+            base_vault: key(3),
+            quote_vault: key(4),
+            base_mint: key(1),
+            quote_mint: key(2),
+            open_orders: key(5),
+        }
+    }
+
+    #[test]
+    fn encodes_swap_base_in_v2() {
+        let ix = raydium_amm_swap_ix(&key(9), &pool(6), &key(8), key(6), key(7), 1_000, 990);
+
+        assert_eq!(ix.program_id, RAYDIUM_AMM_PROGRAM);
+        let mut data = vec![16u8];
+        data.extend(1_000u64.to_le_bytes());
+        data.extend(990u64.to_le_bytes());
+        assert_eq!(ix.data, data);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+                AccountMeta::new(key(9), false),
+                AccountMeta::new_readonly(RAYDIUM_AMM_AUTHORITY, false),
+                AccountMeta::new(key(3), false),
+                AccountMeta::new(key(4), false),
+                AccountMeta::new(key(6), false),
+                AccountMeta::new(key(7), false),
+                AccountMeta::new_readonly(key(8), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn only_initialized_and_swap_only_pools_swap() {
+        assert!(raydium_amm_swappable(&pool(1)));
+        assert!(raydium_amm_swappable(&pool(6)));
+        assert!(!raydium_amm_swappable(&pool(0)));
+        assert!(!raydium_amm_swappable(&pool(2)));
+    }
+}
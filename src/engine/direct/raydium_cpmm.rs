@@ -0,0 +1,158 @@
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::{RaydiumCpmmPool, RAYDIUM_CPMM_PROGRAM};
+
+/// Raydium CPMM vault and LP mint authority (PDA of "vault_and_lp_mint_auth_seed").
+pub const RAYDIUM_CPMM_AUTHORITY: Pubkey =
+    Pubkey::from_str_const("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL");
+
+const SWAP_BASE_INPUT_DISCRIMINATOR: [u8; 8] = [143, 190, 90, 218, 196, 30, 51, 222];
+
+/// Status bit 2 disables swaps.
+pub fn raydium_cpmm_swappable(state: &RaydiumCpmmPool) -> bool {
+    state.status & 0b100 == 0
+}
+
+/// Token program owning `mint` in this pool.
+pub fn raydium_cpmm_token_program(state: &RaydiumCpmmPool, mint: &Pubkey) -> Option<Pubkey> {
+    if *mint == state.token_0_mint {
+        Some(state.token_0_program)
+    } else if *mint == state.token_1_mint {
+        Some(state.token_1_program)
+    } else {
+        None
+    }
+}
+
+/// `swap_base_input` from `input_mint` into the other pool token. None if the mint is not in the pool.
+pub fn raydium_cpmm_swap_ix(
+    pool: &Pubkey,
+    state: &RaydiumCpmmPool,
+    owner: &Pubkey,
+    input_mint: &Pubkey,
+    user_source: Pubkey,
+    user_destination: Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Option<Instruction> {
+    let (input_vault, output_vault, input_program, output_program, output_mint) =
+        if *input_mint == state.token_0_mint {
+            (
+                state.token_0_vault,
+                state.token_1_vault,
+                state.token_0_program,
+                state.token_1_program,
+                state.token_1_mint,
+            )
+        } else if *input_mint == state.token_1_mint {
+            (
+                state.token_1_vault,
+                state.token_0_vault,
+                state.token_1_program,
+                state.token_0_program,
+                state.token_0_mint,
+            )
+        } else {
+            return None;
+        };
+
+    let mut data = Vec::with_capacity(24);
+    data.extend(SWAP_BASE_INPUT_DISCRIMINATOR);
+    data.extend(amount_in.to_le_bytes());
+    data.extend(minimum_amount_out.to_le_bytes());
+
+    Some(Instruction {
+        program_id: RAYDIUM_CPMM_PROGRAM,
+        accounts: vec![
+            AccountMeta::new_readonly(*owner, true),
+            AccountMeta::new_readonly(RAYDIUM_CPMM_AUTHORITY, false),
+            AccountMeta::new_readonly(state.amm_config, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(user_source, false),
+            AccountMeta::new(user_destination, false),
+            AccountMeta::new(input_vault, false),
+            AccountMeta::new(output_vault, false),
+            AccountMeta::new_readonly(input_program, false),
+            AccountMeta::new_readonly(output_program, false),
+            AccountMeta::new_readonly(*input_mint, false),
+            AccountMeta::new_readonly(output_mint, false),
+            AccountMeta::new(state.observation_key, false),
+        ],
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn pool(status: u8) -> RaydiumCpmmPool {
+        RaydiumCpmmPool {
+            amm_config: key(10),
+            token_0_vault: key(3),
+            token_1_vault: key(4),
+            token_0_mint: key(1),
+            token_1_mint: key(2),
+            token_0_program: key(11),
+            token_1_program: key(12),
+            observation_key: key(13),
+            status,
+            protocol_fees_token_0: 0,
+            protocol_fees_token_1: 0,
+            fund_fees_token_0: 0,
+            fund_fees_token_1: 0,
+        }
+    }
+
+    #[test]
+    fn encodes_swap_base_input() {
+        let ix = raydium_cpmm_swap_ix(&key(9), &pool(0), &key(8), &key(2), key(6), key(7), 1_000, 990)
+            .unwrap();
+
+        assert_eq!(ix.program_id, RAYDIUM_CPMM_PROGRAM);
+        let mut data = vec![143, 190, 90, 218, 196, 30, 51, 222];
+        data.extend(1_000u64.to_le_bytes());
+        data.extend(990u64.to_le_bytes());
+        assert_eq!(ix.data, data);
+        // Input is token 1, so vaults, programs and mints are listed input side first.
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new_readonly(key(8), true),
+                AccountMeta::new_readonly(RAYDIUM_CPMM_AUTHORITY, false),
+                AccountMeta::new_readonly(key(10), false),
+                AccountMeta::new(key(9), false),
+                AccountMeta::new(key(6), false),
+                AccountMeta::new(key(7), false),
+                AccountMeta::new(key(4), false),
+                AccountMeta::new(key(3), false),
+                AccountMeta::new_readonly(key(12), false),
+                AccountMeta::new_readonly(key(11), false),
+                AccountMeta::new_readonly(key(2), false),
+                AccountMeta::new_readonly(key(1), false),
+                AccountMeta::new(key(13), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_foreign_input_mint() {
+        assert!(raydium_cpmm_swap_ix(&key(9), &pool(0), &key(8), &key(5), key(6), key(7), 1, 1).is_none());
+        assert_eq!(raydium_cpmm_token_program(&pool(0), &key(1)), Some(key(11)));
+        assert_eq!(raydium_cpmm_token_program(&pool(0), &key(5)), None);
+    }
+
+    #[test]
+    fn swap_disabled_bit_blocks_swaps() {
+        assert!(raydium_cpmm_swappable(&pool(0)));
+        assert!(raydium_cpmm_swappable(&pool(0b011)));
+        assert!(!raydium_cpmm_swappable(&pool(0b100)));
+    }
+}
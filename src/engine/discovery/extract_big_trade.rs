@@ -208,19 +208,16 @@ pub fn extract_big_trade(
                     .iter()
                     .find(|f| c.mint == f.mint && c.delta.abs() > f.threshold)
                     .and_then(|f| {
-                        POPULAR_TOKEN_INFO
-                            .iter()
-                            .find(|t| t.mint == f.mint)
-                            .map(|token_info| {
-                                (
-                                    token_info.mint.to_string(),
-                                    token_info.decimals,
-                                    f.amount_range.clone(),
-                                    f.steps,
-                                    f.min_profit,
-                                    token_info.symbol.to_string(),
-                                )
-                            })
+                        let token_info = instrument_meta(&f.mint)?;
+                        let amount_range = effective_notional_range(f, token_info.decimals)?;
+                        Some((
+                            token_info.mint,
+                            token_info.decimals,
+                            amount_range,
+                            f.steps,
+                            f.min_profit,
+                            token_info.symbol,
+                        ))
                     })
             });
 
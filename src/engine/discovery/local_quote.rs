@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::*;
+
+/// Geometric grid of raw input amounts between `from_f` and `to_f` (UI units), like the
+/// Jupiter sweep in `simulate_amount_in`.
+pub fn amount_grid(from_f: f64, to_f: f64, steps: usize, decimal: u8) -> Vec<u64> {
+    if steps < 2 {
+        return vec![(from_f * 10_f64.powf(decimal as f64)) as u64];
+    }
+    let ratio = (to_f / from_f).powf(1.0 / (steps as f64 - 1.0));
+    (0..steps)
+        .map(|i| (from_f * ratio.powi(i as i32) * 10_f64.powf(decimal as f64)) as u64)
+        .collect()
+}
+
+/// Sweep `amounts` over every ordered pair of distinct, fresh cached pools trading
+/// `base_mint` against a common target, using only local state. Targets must be resolved in
+/// the token registry without unsupported extensions. Returns the plans whose output
+/// exceeds their input (gross; costs are applied by the caller).
+pub fn find_direct_arbs(base_mint: &Pubkey, amounts: &[u64], slippage_bps: u64) -> Vec<DirectArbPlan> {
+    let mut by_target: HashMap<Pubkey, Vec<PoolSnapshot>> = HashMap::new();
+    for pool in pools_with_mint(base_mint).into_iter().filter(|p| !p.stale) {
+        let target = if pool.mint_a == *base_mint {
+            pool.mint_b
+        } else {
+            pool.mint_a
+        };
+        if instrument_meta(&target.to_string()).is_none() {
+            continue;
+        }
+        by_target.entry(target).or_default().push(pool);
+    }
+
+    let mut plans = Vec::new();
+    for (target, pools) in &by_target {
+        for leg1 in pools {
+            for leg2 in pools.iter().filter(|p| p.address != leg1.address) {
+                plans.extend(amounts.iter().filter_map(|&amount_in| {
+                    plan_direct_arb(base_mint, target, leg1, leg2, amount_in, slippage_bps)
+                }));
+            }
+        }
+    }
+    plans
+}
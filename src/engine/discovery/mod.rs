@@ -1,4 +1,6 @@
 pub mod extract_big_trade;
+pub mod local_quote;
 pub mod simulate_amount_in;
 pub use extract_big_trade::*;
+pub use local_quote::*;
 pub use simulate_amount_in::*;
@@ -0,0 +1,111 @@
+//! Turns a pair of Jupiter quotes into the instructions of one arbitrage transaction,
+//! using the route builder selected in `[strategy].route_builder`.
+
+use jupiter_swap_api_client::quote::QuoteResponse;
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct ArbSwap {
+    /// Setup instructions followed by the swap instruction.
+    pub instructions: Vec<Instruction>,
+    pub address_lookup_table_addresses: Vec<Pubkey>,
+    /// Instructions start with their own compute budget (flash-loan funded); submit without
+    /// relayer-added `cu` / priority fee.
+    pub includes_compute_budget: bool,
+}
+
+/// Build the arbitrage instructions for wallet `user`, wrapped in a flash loan when the mother
+/// token has a reserve and ending with a profit guard when one is configured.
+pub async fn build_arb_swap(
+    quote_response_1: QuoteResponse,
+    quote_response_2: QuoteResponse,
+    min_profit_amount: u64,
+    user: &Pubkey,
+) -> Result<ArbSwap, anyhow::Error> {
+    let mother_token = quote_response_1.input_mint;
+    let in_amount = quote_response_1.in_amount;
+    let flash_funded = flash_loan_reserve(&mother_token.to_string()).is_some();
+
+    let mut swap = build_route(
+        quote_response_1,
+        quote_response_2,
+        min_profit_amount,
+        !flash_funded,
+        user,
+    )
+    .await?;
+
+    if flash_funded {
+        let token_program = resolve_token_program(&mother_token).await?;
+        if let Some(wrapped) =
+            wrap_with_flash_loan(swap.instructions.clone(), user, &mother_token, &token_program, in_amount)
+        {
+            swap.instructions = wrapped;
+            swap.includes_compute_budget = true;
+        }
+    }
+
+    if let Some(program_id) = profit_guard_program() {
+        // The swap API path wraps in_amount of native SOL into the WSOL ATA inside the tx
+        let wrapped = if !flash_funded
+            && mother_token == WSOL
+            && CONFIG.strategy.route_builder == RouteBuilder::SwapApi
+        {
+            in_amount
+        } else {
+            0
+        };
+        let guard = build_profit_guard(program_id, user, &mother_token, wrapped + min_profit_amount).await?;
+        swap.instructions.push(guard);
+    }
+
+    Ok(swap)
+}
+
+async fn build_route(
+    quote_response_1: QuoteResponse,
+    quote_response_2: QuoteResponse,
+    min_profit_amount: u64,
+    wrap_and_unwrap_sol: bool,
+    user: &Pubkey,
+) -> Result<ArbSwap, anyhow::Error> {
+    note_route_mints(
+        quote_response_1
+            .route_plan
+            .iter()
+            .chain(&quote_response_2.route_plan)
+            .flat_map(|step| [step.swap_info.input_mint, step.swap_info.output_mint]),
+    );
+    match CONFIG.strategy.route_builder {
+        RouteBuilder::SwapApi => {
+            let ix = get_swap_ix(
+                quote_response_1,
+                quote_response_2,
+                min_profit_amount,
+                wrap_and_unwrap_sol,
+                user,
+            )
+            .await?;
+            let mut instructions = strip_provisioned_ata_creates(ix.setup_instructions);
+            instructions.push(ix.swap_instruction);
+            Ok(ArbSwap {
+                instructions,
+                address_lookup_table_addresses: ix.address_lookup_table_addresses,
+                includes_compute_budget: false,
+            })
+        }
+        RouteBuilder::MergedRoute => {
+            let ix =
+                get_merged_route_ix(quote_response_1, quote_response_2, min_profit_amount, user).await?;
+            let mut instructions = strip_provisioned_ata_creates(ix.setup_instructions);
+            instructions.push(ix.swap_instruction);
+            Ok(ArbSwap {
+                instructions,
+                address_lookup_table_addresses: ix.address_lookup_table_addresses,
+                includes_compute_budget: false,
+            })
+        }
+    }
+}
@@ -0,0 +1,356 @@
+//! Flash-loan funding: wraps the swap instructions of an arbitrage in a borrow/repay pair
+//! from a Kamino-style lending reserve, so trade size is not capped by wallet inventory.
+//!
+//! Each configured reserve account is read at startup for its liquidity supply and fee vaults
+//! and its flash loan fee. The repay instruction names the absolute index of the borrow
+//! instruction; `link_flash_repay` sets it once the submitter knows how many instructions
+//! precede the swap instructions in the final transaction.
+
+use once_cell::sync::OnceCell;
+use solana_sdk::{
+    compute_budget::ComputeBudgetInstruction,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::collections::HashMap;
+use tracing::info;
+
+use crate::*;
+
+const FLASH_BORROW_DISCRIMINATOR: [u8; 8] = [135, 231, 52, 167, 7, 52, 212, 193];
+const FLASH_REPAY_DISCRIMINATOR: [u8; 8] = [185, 117, 0, 203, 96, 245, 180, 186];
+
+/// `Reserve` account layout (after the 8-byte Anchor discriminator): `lending_market`, then
+/// `liquidity.{mint_pubkey, supply_vault, fee_vault}`, then `config.fees.flash_loan_fee_sf`.
+const RESERVE_LENDING_MARKET: usize = 32;
+const RESERVE_LIQUIDITY_MINT: usize = 128;
+const RESERVE_SUPPLY_VAULT: usize = 160;
+const RESERVE_FEE_VAULT: usize = 192;
+const RESERVE_FLASH_LOAN_FEE_SF: usize = 4904;
+/// `flash_loan_fee_sf` is a fraction with 60 fractional bits; this value disables flash loans.
+const FLASH_LOANS_DISABLED_SF: u64 = u64::MAX;
+
+/// A reserve as read from chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FlashReserve {
+    pub address: Pubkey,
+    pub lending_market: Pubkey,
+    pub mint: Pubkey,
+    pub supply_vault: Pubkey,
+    pub fee_vault: Pubkey,
+    pub flash_loan_fee_sf: u64,
+}
+
+impl FlashReserve {
+    /// Fee for borrowing `amount`, rounded up like the lending program.
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.flash_loan_fee_sf as u128).div_ceil(1u128 << 60)) as u64
+    }
+}
+
+struct FlashLoan {
+    program: Pubkey,
+    market: Pubkey,
+    lending_market_authority: Pubkey,
+    /// Base token mint -> reserve.
+    reserves: HashMap<String, FlashReserve>,
+}
+
+static FLASH_LOAN: OnceCell<FlashLoan> = OnceCell::new();
+
+/// Decode the fields of a `Reserve` account that flash loans need.
+pub fn decode_reserve(address: Pubkey, data: &[u8]) -> Option<FlashReserve> {
+    let pubkey_at = |offset: usize| -> Option<Pubkey> {
+        Some(Pubkey::new_from_array(data.get(offset..offset + 32)?.try_into().ok()?))
+    };
+    Some(FlashReserve {
+        address,
+        lending_market: pubkey_at(RESERVE_LENDING_MARKET)?,
+        mint: pubkey_at(RESERVE_LIQUIDITY_MINT)?,
+        supply_vault: pubkey_at(RESERVE_SUPPLY_VAULT)?,
+        fee_vault: pubkey_at(RESERVE_FEE_VAULT)?,
+        flash_loan_fee_sf: u64::from_le_bytes(
+            data.get(RESERVE_FLASH_LOAN_FEE_SF..RESERVE_FLASH_LOAN_FEE_SF + 8)?.try_into().ok()?,
+        ),
+    })
+}
+
+/// Parse `[flash_loan]` and read every reserve account. Called once at startup; any reserve
+/// that is missing, belongs to another market or mint, or has flash loans disabled is an error.
+pub async fn init_flash_loan() -> Result<usize, anyhow::Error> {
+    if !CONFIG.flash_loan.enabled {
+        return Ok(0);
+    }
+    let ids = parse_flash_loan_ids(&CONFIG.flash_loan)?;
+    let addresses: Vec<Pubkey> = ids.reserves.iter().map(|(_, reserve)| *reserve).collect();
+    let accounts = RPC_CLIENT.get_multiple_accounts(&addresses).await?;
+
+    let mut reserves = HashMap::new();
+    for ((mint, address), account) in ids.reserves.iter().zip(accounts) {
+        let account = account.ok_or_else(|| anyhow::anyhow!("flash loan reserve {} not found", address))?;
+        let reserve = decode_reserve(*address, &account.data)
+            .ok_or_else(|| anyhow::anyhow!("flash loan reserve {} is not a reserve account", address))?;
+        if reserve.lending_market != ids.market || reserve.mint.to_string() != *mint {
+            return Err(anyhow::anyhow!(
+                "flash loan reserve {} lends {} in market {}, configured for {} in {}",
+                address,
+                reserve.mint,
+                reserve.lending_market,
+                mint,
+                ids.market
+            ));
+        }
+        if reserve.flash_loan_fee_sf == FLASH_LOANS_DISABLED_SF {
+            return Err(anyhow::anyhow!("flash loans are disabled on reserve {}", address));
+        }
+        info!(%mint, reserve = %address, fee_bps = reserve.fee_for(10_000_000) as f64 / 1_000.0, "Flash loan reserve loaded");
+        reserves.insert(mint.clone(), reserve);
+    }
+
+    let (lending_market_authority, _) = Pubkey::find_program_address(&[b"lma", ids.market.as_ref()], &ids.program);
+    let count = reserves.len();
+    let _ = FLASH_LOAN.set(FlashLoan {
+        program: ids.program,
+        market: ids.market,
+        lending_market_authority,
+        reserves,
+    });
+    Ok(count)
+}
+
+/// Reserve to flash-borrow `mint` from, when flash loans are enabled for it.
+pub fn flash_loan_reserve(mint: &str) -> Option<&'static FlashReserve> {
+    FLASH_LOAN.get()?.reserves.get(mint)
+}
+
+/// Account list shared by borrow and repay: the reserve supply is the borrow source and the
+/// repay destination, `user_liquidity` (the wallet's token account) the opposite side.
+fn flash_accounts(
+    program: Pubkey,
+    market: Pubkey,
+    lending_market_authority: Pubkey,
+    reserve: &FlashReserve,
+    user: &Pubkey,
+    token_program: &Pubkey,
+    user_liquidity: Pubkey,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new_readonly(*user, true),
+        AccountMeta::new_readonly(lending_market_authority, false),
+        AccountMeta::new_readonly(market, false),
+        AccountMeta::new(reserve.address, false),
+        AccountMeta::new_readonly(reserve.mint, false),
+        AccountMeta::new(reserve.supply_vault, false),
+        AccountMeta::new(user_liquidity, false),
+        AccountMeta::new(reserve.fee_vault, false),
+        // Optional referrer accounts are passed as the program id
+        AccountMeta::new_readonly(program, false),
+        AccountMeta::new_readonly(program, false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(*token_program, false),
+    ]
+}
+
+/// Wrap `swap_ixs` in compute budget + flash borrow/repay of `amount` of `mint` for `user`. None when
+/// no reserve is configured for the mint (the trade then uses wallet inventory).
+pub fn wrap_with_flash_loan(
+    swap_ixs: Vec<Instruction>,
+    user: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Option<Vec<Instruction>> {
+    let loan = FLASH_LOAN.get()?;
+    let reserve = loan.reserves.get(&mint.to_string())?;
+    let accounts = flash_accounts(
+        loan.program,
+        loan.market,
+        loan.lending_market_authority,
+        reserve,
+        user,
+        token_program,
+        get_associated_token_address_with_program_id(user, mint, token_program),
+    );
+    Some(flash_loan_ixs(
+        loan.program,
+        accounts,
+        swap_ixs,
+        amount,
+        FEES.compute_units as u32,
+        FEES.priority_lamports,
+    ))
+}
+
+fn flash_loan_ixs(
+    program: Pubkey,
+    accounts: Vec<AccountMeta>,
+    swap_ixs: Vec<Instruction>,
+    amount: u64,
+    compute_units: u32,
+    priority_micro_lamports: u64,
+) -> Vec<Instruction> {
+    let mut borrow_data = FLASH_BORROW_DISCRIMINATOR.to_vec();
+    borrow_data.extend(amount.to_le_bytes());
+    let borrow = Instruction {
+        program_id: program,
+        accounts: accounts.clone(),
+        data: borrow_data,
+    };
+
+    // The borrow index is filled in by `link_flash_repay`
+    let mut repay_data = FLASH_REPAY_DISCRIMINATOR.to_vec();
+    repay_data.extend(amount.to_le_bytes());
+    repay_data.push(0);
+    let repay = Instruction {
+        program_id: program,
+        accounts,
+        data: repay_data,
+    };
+
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+        ComputeBudgetInstruction::set_compute_unit_price(priority_micro_lamports),
+        borrow,
+    ];
+    instructions.extend(swap_ixs);
+    instructions.push(repay);
+    instructions
+}
+
+/// Point each flash repay in `ixs` at the absolute index of the borrow before it, given
+/// that the submitter places `prefix_len` instructions (nonce advance, compute budget)
+/// ahead of `ixs`. No-op for transactions without a flash loan.
+pub fn link_flash_repay(ixs: &mut [Instruction], prefix_len: usize) -> Result<(), anyhow::Error> {
+    let mut borrow_index: Option<usize> = None;
+    for i in 0..ixs.len() {
+        if ixs[i].data.starts_with(&FLASH_BORROW_DISCRIMINATOR) {
+            borrow_index = Some(prefix_len + i);
+        } else if ixs[i].data.starts_with(&FLASH_REPAY_DISCRIMINATOR) {
+            let index = borrow_index
+                .take()
+                .ok_or_else(|| anyhow::anyhow!("flash repay without a preceding borrow"))?;
+            let index = u8::try_from(index).map_err(|_| anyhow::anyhow!("flash borrow at index {}", index))?;
+            *ixs[i].data.last_mut().expect("repay data holds the borrow index") = index;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reserve() -> FlashReserve {
+        FlashReserve {
+            address: Pubkey::new_unique(),
+            lending_market: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            supply_vault: Pubkey::new_unique(),
+            fee_vault: Pubkey::new_unique(),
+            // 0.09%
+            flash_loan_fee_sf: ((9u128 << 60) / 10_000) as u64,
+        }
+    }
+
+    fn noop(byte: u8) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: Vec::new(),
+            data: vec![byte],
+        }
+    }
+
+    #[test]
+    fn decodes_reserve_layout() {
+        let expected = reserve();
+        let mut data = vec![0u8; 8624];
+        data[RESERVE_LENDING_MARKET..][..32].copy_from_slice(expected.lending_market.as_ref());
+        data[RESERVE_LIQUIDITY_MINT..][..32].copy_from_slice(expected.mint.as_ref());
+        data[RESERVE_SUPPLY_VAULT..][..32].copy_from_slice(expected.supply_vault.as_ref());
+        data[RESERVE_FEE_VAULT..][..32].copy_from_slice(expected.fee_vault.as_ref());
+        data[RESERVE_FLASH_LOAN_FEE_SF..][..8].copy_from_slice(&expected.flash_loan_fee_sf.to_le_bytes());
+
+        assert_eq!(decode_reserve(expected.address, &data), Some(expected.clone()));
+        assert_eq!(decode_reserve(expected.address, &data[..RESERVE_FLASH_LOAN_FEE_SF + 7]), None);
+    }
+
+    #[test]
+    fn fee_rounds_up() {
+        let reserve = reserve();
+        assert_eq!(reserve.fee_for(1_000_000), 900);
+        assert_eq!(reserve.fee_for(1_001), 1);
+        assert_eq!(reserve.fee_for(0), 0);
+        let free = FlashReserve { flash_loan_fee_sf: 0, ..reserve };
+        assert_eq!(free.fee_for(1_000_000), 0);
+    }
+
+    #[test]
+    fn orders_borrow_and_repay_accounts() {
+        let (program, market, authority) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let (user, user_liquidity) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let reserve = reserve();
+        let accounts = flash_accounts(program, market, authority, &reserve, &user, &spl_token::ID, user_liquidity);
+
+        let keys: Vec<Pubkey> = accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                user,
+                authority,
+                market,
+                reserve.address,
+                reserve.mint,
+                reserve.supply_vault,
+                user_liquidity,
+                reserve.fee_vault,
+                program,
+                program,
+                sysvar::instructions::id(),
+                spl_token::ID,
+            ]
+        );
+        let writable: Vec<usize> = (0..accounts.len()).filter(|&i| accounts[i].is_writable).collect();
+        assert_eq!(writable, vec![3, 5, 6, 7]);
+        assert!(accounts[0].is_signer);
+    }
+
+    #[test]
+    fn wraps_swap_between_borrow_and_repay() {
+        let program = Pubkey::new_unique();
+        let ixs = flash_loan_ixs(program, Vec::new(), vec![noop(1), noop(2)], 5_000, 400_000, 10);
+        assert_eq!(ixs.len(), 6);
+        assert_eq!(ixs[2].data[..8], FLASH_BORROW_DISCRIMINATOR);
+        assert_eq!(ixs[2].data[8..], 5_000u64.to_le_bytes());
+        assert_eq!((ixs[3].data.as_slice(), ixs[4].data.as_slice()), (&[1u8][..], &[2u8][..]));
+        assert_eq!(ixs[5].data[..8], FLASH_REPAY_DISCRIMINATOR);
+        assert_eq!(ixs[5].data[8..16], 5_000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn links_repay_to_final_borrow_index() {
+        let program = Pubkey::new_unique();
+        let mut ixs = flash_loan_ixs(program, Vec::new(), vec![noop(1)], 5_000, 400_000, 10);
+        ixs.push(noop(3));
+
+        // Nonce advance only: borrow is instruction 1 + 2
+        link_flash_repay(&mut ixs, 1).unwrap();
+        assert_eq!(*ixs[4].data.last().unwrap(), 3);
+        // Nonce advance plus relayer compute budget
+        link_flash_repay(&mut ixs, 3).unwrap();
+        assert_eq!(*ixs[4].data.last().unwrap(), 5);
+    }
+
+    #[test]
+    fn link_ignores_plain_swaps_and_rejects_orphan_repay() {
+        let mut plain = vec![noop(1), noop(2)];
+        link_flash_repay(&mut plain, 1).unwrap();
+        assert_eq!(plain[1].data, vec![2]);
+
+        let program = Pubkey::new_unique();
+        let mut ixs = flash_loan_ixs(program, Vec::new(), Vec::new(), 1, 1, 1);
+        ixs.remove(2);
+        assert!(link_flash_repay(&mut ixs, 1).is_err());
+    }
+}
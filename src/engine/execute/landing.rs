@@ -0,0 +1,150 @@
+//! Outcome feedback for Jupiter-routed trades, feeding the venue blacklist: an optional RPC
+//! preflight simulation before submission, and a landing tracker that watches the leased
+//! nonce and the base-token balance after it.
+//!
+//! The relayer adapter signs and sends transactions itself, so landings are detected by the
+//! wallet's nonce advancing rather than by signature.
+
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget::ComputeBudgetInstruction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, AddressLookupTableAccount, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::VersionedTransaction,
+};
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::*;
+
+const LANDING_TIMEOUT: Duration = Duration::from_secs(30);
+const LANDING_POLL: Duration = Duration::from_millis(500);
+
+/// Simulate the arbitrage instructions when `simulate_before_submit` is on. A failing
+/// simulation counts against `venues` and returns false; RPC errors let the trade through.
+pub async fn passes_preflight(
+    payer: &Pubkey,
+    swap_ixs: &[Instruction],
+    includes_compute_budget: bool,
+    alts: &[AddressLookupTableAccount],
+    venues: &[String],
+) -> bool {
+    if !CONFIG.venues.simulate_before_submit {
+        return true;
+    }
+    let mut ixs = Vec::with_capacity(swap_ixs.len() + 1);
+    if !includes_compute_budget {
+        ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(FEES.compute_units as u32));
+    }
+    ixs.extend_from_slice(swap_ixs);
+
+    // Unsigned: the RPC node skips signature checks and substitutes a recent blockhash
+    let message = match v0::Message::try_compile(payer, &ixs, alts, Hash::default()) {
+        Ok(message) => message,
+        Err(e) => {
+            warn!(error = %e, "Failed to compile preflight transaction");
+            return true;
+        }
+    };
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header.num_required_signatures as usize],
+        message: VersionedMessage::V0(message),
+    };
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(CommitmentConfig::processed()),
+        ..Default::default()
+    };
+    match RPC_CLIENT.simulate_transaction_with_config(&tx, config).await {
+        Ok(response) => match response.value.err {
+            None => true,
+            Some(err) => {
+                info!(error = %err, venues = %venues.join(","), "Preflight simulation failed; trade dropped");
+                LANDINGS.with_label_values(&["preflight_failed"]).inc();
+                record_venue_failure(venues, "simulation failed");
+                false
+            }
+        },
+        Err(e) => {
+            warn!(error = %e, "Preflight simulation request failed");
+            true
+        }
+    }
+}
+
+/// Watch a submitted trade: once the lease's nonce advances, re-read the wallet's balance of
+/// `mint` and report the route's venues as failed unless it grew. Trades that never land are
+/// not counted. The outcome is also written to the opportunity database. No-op unless
+/// `track_landings` is on.
+pub fn track_landing(lease: &WalletLease, mint: &str, venues: Vec<String>, opportunity_id: OpportunityId) {
+    if !CONFIG.venues.track_landings || venues.is_empty() {
+        return;
+    }
+    let payer = lease.pubkey();
+    let nonce_account = lease.wallet.nonce_account;
+    let leased_nonce = lease.blockhash();
+    let mint = mint.to_string();
+    let Some(balance_before) = spendable_balance(&payer, &mint) else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        let deadline = Instant::now() + LANDING_TIMEOUT;
+        while get_nonce_of(&nonce_account).blockhash() == leased_nonce {
+            if Instant::now() >= deadline {
+                LANDINGS.with_label_values(&["not_landed"]).inc();
+                note_submission_failed("not landed");
+                mark_opportunity_landed(opportunity_id, None);
+                note_landing(opportunity_id, None);
+                note_scheduled_landing(opportunity_id, false);
+                debug!(wallet = %payer, venues = %venues.join(","), "Trade did not land");
+                return;
+            }
+            sleep(LANDING_POLL).await;
+        }
+
+        if let Err(e) = refresh_inventory().await {
+            warn!(error = %e, "Inventory refresh after landing failed");
+            return;
+        }
+        let Some(balance_after) = spendable_balance(&payer, &mint) else {
+            return;
+        };
+        note_submission_landed();
+        let balance_change = balance_after as i64 - balance_before as i64;
+        mark_opportunity_landed(opportunity_id, Some(balance_change));
+        note_landing(opportunity_id, Some(balance_change));
+        note_scheduled_landing(opportunity_id, true);
+        let profitable = balance_after > balance_before;
+        emit(Event::Landed {
+            wallet: payer.to_string(),
+            mint: mint.clone(),
+            profitable,
+            balance_change: balance_after as i64 - balance_before as i64,
+            venues: venues.join(","),
+        });
+        if profitable {
+            debug!(wallet = %payer, gain = balance_after - balance_before, "Trade landed with profit");
+            LANDINGS.with_label_values(&["profit"]).inc();
+            record_venue_success(&venues);
+            record_trade_outcome(true);
+            note_landed_profit(&payer, &mint, balance_after - balance_before);
+        } else {
+            info!(
+                wallet = %payer,
+                %mint,
+                loss = balance_before - balance_after,
+                venues = %venues.join(","),
+                "Trade landed without profit"
+            );
+            LANDINGS.with_label_values(&["loss"]).inc();
+            record_venue_failure(&venues, "unprofitable landing");
+            record_trade_outcome(false);
+        }
+    });
+}
@@ -0,0 +1,104 @@
+//! Trade ledger: one JSON line per submitted transaction in `trades.jsonl`, arbitrage and
+//! non-arbitrage (rebalance) alike. Also remembers when the last arbitrage went out so
+//! maintenance work can stay out of hot trading windows.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use tracing::warn;
+
+pub const LEDGER_PATH: &str = "trades.jsonl";
+
+static LEDGER_MUTEX: Mutex<()> = Mutex::new(());
+/// Unix millis of the last arbitrage submission; 0 when none yet.
+static LAST_ARBITRAGE_MS: AtomicI64 = AtomicI64::new(0);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeKind {
+    Arbitrage,
+    Rebalance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub timestamp: String,
+    pub kind: TradeKind,
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Raw input amount.
+    pub in_amount: u64,
+    /// Raw output amount expected at submission (quote or local estimate).
+    pub expected_out: u64,
+    /// Transaction signature when known before submission.
+    pub signature: Option<String>,
+    /// Trading wallet that signed the transaction.
+    pub wallet: Option<String>,
+}
+
+impl LedgerEntry {
+    pub fn new(kind: TradeKind, input_mint: String, output_mint: String, in_amount: u64, expected_out: u64) -> Self {
+        Self {
+            timestamp: Utc::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+            kind,
+            input_mint,
+            output_mint,
+            in_amount,
+            expected_out,
+            signature: None,
+            wallet: None,
+        }
+    }
+
+    pub fn with_signature(mut self, signature: String) -> Self {
+        self.signature = Some(signature);
+        self
+    }
+
+    pub fn with_wallet(mut self, wallet: String) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+}
+
+/// Append `entry` to the ledger.
+pub fn record_trade(entry: &LedgerEntry) {
+    if entry.kind == TradeKind::Arbitrage {
+        LAST_ARBITRAGE_MS.store(Utc::now().timestamp_millis(), Ordering::Relaxed);
+    }
+    let line = match serde_json::to_string(entry) {
+        Ok(line) => line,
+        Err(e) => {
+            warn!(error = %e, "Failed to serialize ledger entry");
+            return;
+        }
+    };
+    let _guard = LEDGER_MUTEX.lock().unwrap();
+    match OpenOptions::new().create(true).append(true).open(LEDGER_PATH) {
+        Ok(mut file) => {
+            if let Err(e) = writeln!(file, "{}", line) {
+                warn!(error = %e, "Failed to write trade ledger");
+            }
+        }
+        Err(e) => warn!(error = %e, path = LEDGER_PATH, "Failed to open trade ledger"),
+    }
+}
+
+/// Every readable entry in the ledger; unparsable lines are skipped.
+pub fn read_ledger() -> Result<Vec<LedgerEntry>, anyhow::Error> {
+    let content = match std::fs::read_to_string(LEDGER_PATH) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+}
+
+/// Seconds since the last arbitrage submission, None when there has been none.
+pub fn secs_since_last_arbitrage() -> Option<u64> {
+    let last = LAST_ARBITRAGE_MS.load(Ordering::Relaxed);
+    (last > 0).then(|| ((Utc::now().timestamp_millis() - last).max(0) / 1000) as u64)
+}
@@ -1,3 +1,25 @@
+mod build_arb;
+mod flash_loan;
+mod landing;
+mod ledger;
+mod opportunity_db;
 mod process_update;
+mod profit_guard;
+mod rebalance;
+mod report;
+mod risk;
+mod scheduler;
+mod wallet_pool;
 
+pub use build_arb::*;
+pub use flash_loan::*;
+pub use landing::*;
+pub use ledger::*;
+pub use opportunity_db::*;
 pub use process_update::*;
+pub use profit_guard::*;
+pub use rebalance::*;
+pub use report::*;
+pub use risk::*;
+pub use scheduler::*;
+pub use wallet_pool::*;
@@ -0,0 +1,373 @@
+//! Opportunity database: every evaluated quote round trip goes to SQLite next to the trade
+//! ledger, with its costs, whether it was submitted and why not. Rows are written by a
+//! dedicated thread so quoting never waits on disk; the schema is versioned with
+//! `PRAGMA user_version`.
+//!
+//! A row is inserted when the quote is evaluated and updated once the trading loop decides on
+//! it, so candidates that cleared min profit carry `submitted` or a `reject_reason` later.
+//! Direct mode re-quotes cached pools every tick and records only the plans clearing min profit.
+
+use chrono::Utc;
+use once_cell::sync::{Lazy, OnceCell};
+use rusqlite::{params, Connection};
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use tracing::{info, warn};
+
+use crate::*;
+
+const QUEUE_CAPACITY: usize = 65_536;
+/// Operations written per transaction at most.
+const WRITE_BATCH: usize = 1_024;
+
+/// Schema migrations, applied in order; `user_version` is the number applied so far.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE opportunities (
+        id INTEGER PRIMARY KEY,
+        ts_ms INTEGER NOT NULL,
+        mode TEXT NOT NULL,
+        instrument_mint TEXT NOT NULL,
+        instrument TEXT NOT NULL,
+        target_mint TEXT NOT NULL,
+        target TEXT NOT NULL,
+        in_amount INTEGER NOT NULL,
+        quoted_out INTEGER,
+        gross_profit INTEGER,
+        tx_cost INTEGER,
+        tip_sol REAL,
+        net_profit INTEGER,
+        min_profit INTEGER NOT NULL,
+        submitted INTEGER NOT NULL DEFAULT 0,
+        wallet TEXT,
+        reject_reason TEXT,
+        detail TEXT
+    );
+    CREATE INDEX opportunities_pair ON opportunities (instrument, target, ts_ms);
+    CREATE INDEX opportunities_ts ON opportunities (ts_ms);",
+    "CREATE VIEW opportunity_hours AS
+        SELECT instrument, target, CAST(strftime('%H', ts_ms / 1000, 'unixepoch') AS INTEGER) AS hour_utc,
+            COUNT(*) AS evaluated,
+            SUM(net_profit > min_profit) AS profitable,
+            SUM(submitted) AS submitted,
+            AVG(in_amount) AS avg_in_amount,
+            MAX(net_profit) AS best_net_profit
+        FROM opportunities
+        WHERE quoted_out IS NOT NULL
+        GROUP BY instrument, target, hour_utc;",
+    "ALTER TABLE opportunities ADD COLUMN service TEXT;
+    ALTER TABLE opportunities ADD COLUMN landed INTEGER;
+    ALTER TABLE opportunities ADD COLUMN realized_profit INTEGER;
+    CREATE INDEX opportunities_submitted ON opportunities (submitted, ts_ms);",
+];
+
+/// Rejection reasons written by the trading loops.
+pub const REJECT_QUOTE_FAILED: &str = "quote_failed";
+pub const REJECT_BELOW_MIN_PROFIT: &str = "below_min_profit";
+pub const REJECT_NOT_BEST: &str = "not_best";
+pub const REJECT_DRY_RUN: &str = "dry_run";
+pub const REJECT_CIRCUIT_BREAKER: &str = "circuit_breaker";
+pub const REJECT_NO_WALLET: &str = "no_wallet";
+pub const REJECT_BUILD_FAILED: &str = "build_failed";
+pub const REJECT_PREFLIGHT_FAILED: &str = "preflight_failed";
+pub const REJECT_SEND_FAILED: &str = "send_failed";
+pub const REJECT_EXPIRED: &str = "expired";
+pub const REJECT_DUPLICATE: &str = "duplicate";
+pub const REJECT_QUEUE_FULL: &str = "queue_full";
+
+static DB_QUEUE: OnceCell<SyncSender<DbOp>> = OnceCell::new();
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+/// Ids are microseconds since the epoch at startup plus a counter, so they stay unique across
+/// restarts without a round trip to the database.
+static NEXT_ID: Lazy<AtomicI64> = Lazy::new(|| AtomicI64::new(Utc::now().timestamp_micros()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OpportunityId(pub(crate) i64);
+
+/// One evaluated quote round trip. Amounts and costs are raw base-token units.
+#[derive(Debug, Clone)]
+pub struct Opportunity {
+    /// `polling`, `big_trade` or `direct`.
+    pub mode: &'static str,
+    pub instrument_mint: String,
+    pub target_mint: String,
+    pub in_amount: u64,
+    /// None when the quote failed.
+    pub quoted_out: Option<u64>,
+    /// Fee model output: network fee, tip and transfer fees.
+    pub tx_cost: Option<i64>,
+    /// Tip the fee model would attach, in SOL.
+    pub tip_sol: Option<f64>,
+    pub min_profit: u64,
+    pub reject_reason: Option<&'static str>,
+    pub detail: Option<String>,
+}
+
+enum DbOp {
+    Insert(OpportunityId, i64, Opportunity),
+    Submitted(OpportunityId, String, String),
+    Landed(OpportunityId, Option<i64>),
+    Rejected(OpportunityId, &'static str, Option<String>),
+}
+
+/// Open the database, apply pending migrations and start the writer thread. No-op unless
+/// `[opportunity_db]` is enabled.
+pub fn init_opportunity_db() -> Result<(), anyhow::Error> {
+    let config = &CONFIG.opportunity_db;
+    if !config.enabled {
+        return Ok(());
+    }
+    let mut conn = Connection::open(&config.path)?;
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+    let applied = migrate(&mut conn)?;
+    info!(path = %config.path, migrations_applied = applied, "Opportunity database opened");
+
+    let (tx, rx) = sync_channel(QUEUE_CAPACITY);
+    std::thread::Builder::new()
+        .name("opportunity-db".to_string())
+        .spawn(move || write_loop(conn, rx))?;
+    let _ = DB_QUEUE.set(tx);
+    Ok(())
+}
+
+/// Apply the migrations above `user_version`, each in its own transaction. Returns how many ran.
+fn migrate(conn: &mut Connection) -> Result<usize, rusqlite::Error> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", i + 1)?;
+        tx.commit()?;
+    }
+    Ok(MIGRATIONS.len().saturating_sub(version))
+}
+
+fn write_loop(mut conn: Connection, rx: Receiver<DbOp>) {
+    while let Ok(first) = rx.recv() {
+        let batch: Vec<DbOp> = std::iter::once(first).chain(rx.try_iter().take(WRITE_BATCH - 1)).collect();
+        if let Err(e) = write_batch(&mut conn, batch) {
+            warn!(error = %e, "Failed to commit opportunities");
+        }
+        let dropped = DROPPED.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!(dropped, "Opportunity queue full; rows dropped");
+        }
+    }
+}
+
+/// Write `batch` in one transaction. An operation that fails is logged and skipped, so one bad
+/// row does not cost the rest of the batch.
+fn write_batch(conn: &mut Connection, batch: Vec<DbOp>) -> Result<(), rusqlite::Error> {
+    let tx = conn.transaction()?;
+    let mut failed = 0;
+    let mut last_error = None;
+    for op in batch {
+        if let Err(e) = write_op(&tx, op) {
+            failed += 1;
+            last_error = Some(e);
+        }
+    }
+    if let Some(e) = last_error {
+        warn!(failed, error = %e, "Failed to write opportunities; rows skipped");
+    }
+    tx.commit()
+}
+
+fn write_op(tx: &rusqlite::Transaction, op: DbOp) -> Result<(), rusqlite::Error> {
+    match op {
+        DbOp::Insert(id, ts_ms, o) => {
+            let gross_profit = o.quoted_out.map(|out| out as i64 - o.in_amount as i64);
+            let net_profit = gross_profit.zip(o.tx_cost).map(|(gross, cost)| gross - cost);
+            tx.prepare_cached(
+                "INSERT INTO opportunities (id, ts_ms, mode, instrument_mint, instrument, target_mint, target,
+                    in_amount, quoted_out, gross_profit, tx_cost, tip_sol, net_profit, min_profit,
+                    reject_reason, detail)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+            )?
+            .execute(params![
+                id.0,
+                ts_ms,
+                o.mode,
+                o.instrument_mint,
+                token_symbol(&o.instrument_mint),
+                o.target_mint,
+                token_symbol(&o.target_mint),
+                o.in_amount as i64,
+                o.quoted_out.map(|out| out as i64),
+                gross_profit,
+                o.tx_cost,
+                o.tip_sol,
+                net_profit,
+                o.min_profit as i64,
+                o.reject_reason,
+                o.detail,
+            ])?;
+        }
+        DbOp::Submitted(id, wallet, service) => {
+            tx.prepare_cached(
+                "UPDATE opportunities SET submitted = 1, wallet = ?2, service = ?3, reject_reason = NULL
+                 WHERE id = ?1",
+            )?
+            .execute(params![id.0, wallet, service])?;
+        }
+        DbOp::Landed(id, balance_change) => {
+            tx.prepare_cached("UPDATE opportunities SET landed = ?2, realized_profit = ?3 WHERE id = ?1")?
+                .execute(params![id.0, balance_change.is_some(), balance_change])?;
+        }
+        DbOp::Rejected(id, reason, detail) => {
+            tx.prepare_cached(
+                "UPDATE opportunities SET submitted = 0, reject_reason = ?2, detail = COALESCE(?3, detail)
+                 WHERE id = ?1",
+            )?
+            .execute(params![id.0, reason, detail])?;
+        }
+    }
+    Ok(())
+}
+
+fn enqueue(op: DbOp) {
+    let Some(queue) = DB_QUEUE.get() else {
+        return;
+    };
+    if let Err(TrySendError::Full(_)) = queue.try_send(op) {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Queue an evaluated opportunity. Never blocks; the returned id is valid even while the
+/// database is disabled.
+pub fn record_opportunity(opportunity: Opportunity) -> OpportunityId {
+    let id = OpportunityId(NEXT_ID.fetch_add(1, Ordering::Relaxed));
+    enqueue(DbOp::Insert(id, Utc::now().timestamp_millis(), opportunity));
+    id
+}
+
+/// `service` is the relay path the transaction went out on (see `submission_label`).
+pub fn mark_opportunity_submitted(id: OpportunityId, wallet: String, service: String) {
+    enqueue(DbOp::Submitted(id, wallet, service));
+}
+
+/// Outcome of a tracked submission: the base-token balance change once it landed, or None
+/// when it never did. Untracked submissions keep `landed` NULL.
+pub fn mark_opportunity_landed(id: OpportunityId, balance_change: Option<i64>) {
+    enqueue(DbOp::Landed(id, balance_change));
+}
+
+pub fn mark_opportunity_rejected(id: OpportunityId, reason: &'static str) {
+    enqueue(DbOp::Rejected(id, reason, None));
+}
+
+/// Reject with the error that caused it (build or preflight failure).
+pub fn mark_opportunity_failed(id: OpportunityId, reason: &'static str, detail: String) {
+    enqueue(DbOp::Rejected(id, reason, Some(detail)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn opportunity(in_amount: u64, quoted_out: Option<u64>) -> Opportunity {
+        Opportunity {
+            mode: "polling",
+            instrument_mint: USDC_MINT.to_string(),
+            target_mint: WSOL.to_string(),
+            in_amount,
+            quoted_out,
+            tx_cost: Some(5_000),
+            tip_sol: Some(0.0001),
+            min_profit: 10_000,
+            reject_reason: None,
+            detail: None,
+        }
+    }
+
+    fn migrated() -> Connection {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        conn
+    }
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn migrates_fresh_database_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(migrate(&mut conn).unwrap(), MIGRATIONS.len());
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        assert_eq!(migrate(&mut conn).unwrap(), 0);
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn migrates_from_an_older_version() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO opportunities (id, ts_ms, mode, instrument_mint, instrument, target_mint, target, in_amount, min_profit)
+             VALUES (1, 0, 'polling', 'm', 'USDC', 't', 'SOL', 100, 1)",
+            [],
+        )
+        .unwrap();
+
+        assert_eq!(migrate(&mut conn).unwrap(), MIGRATIONS.len() - 1);
+        assert_eq!(user_version(&conn), MIGRATIONS.len());
+        // The old row survives and the later columns exist
+        let (service, landed): (Option<String>, Option<bool>) = conn
+            .query_row("SELECT service, landed FROM opportunities WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((service, landed), (None, None));
+    }
+
+    #[test]
+    fn writes_and_updates_rows() {
+        let mut conn = migrated();
+        let (id, ts_ms) = (OpportunityId(7), 1_700_000_000_000);
+        write_batch(
+            &mut conn,
+            vec![
+                DbOp::Insert(id, ts_ms, opportunity(1_000_000, Some(1_020_000))),
+                DbOp::Submitted(id, "wallet".to_string(), "rpc".to_string()),
+                DbOp::Landed(id, Some(14_000)),
+            ],
+        )
+        .unwrap();
+
+        let row: (i64, i64, bool, String, bool, i64) = conn
+            .query_row(
+                "SELECT gross_profit, net_profit, submitted, service, landed, realized_profit FROM opportunities WHERE id = 7",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?, row.get(5)?)),
+            )
+            .unwrap();
+        assert_eq!(row, (20_000, 15_000, true, "rpc".to_string(), true, 14_000));
+    }
+
+    #[test]
+    fn bad_row_does_not_roll_back_the_batch() {
+        let mut conn = migrated();
+        write_batch(
+            &mut conn,
+            vec![
+                DbOp::Insert(OpportunityId(1), 0, opportunity(100, None)),
+                // Duplicate primary key
+                DbOp::Insert(OpportunityId(1), 0, opportunity(200, None)),
+                DbOp::Insert(OpportunityId(2), 0, opportunity(300, None)),
+                DbOp::Rejected(OpportunityId(2), REJECT_QUOTE_FAILED, Some("timeout".to_string())),
+            ],
+        )
+        .unwrap();
+
+        let rows: Vec<(i64, i64, Option<String>)> = conn
+            .prepare("SELECT id, in_amount, reject_reason FROM opportunities ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(rows, vec![(1, 100, None), (2, 300, Some(REJECT_QUOTE_FAILED.to_string()))]);
+    }
+}
@@ -1,10 +1,8 @@
-use chrono::Utc;
-use futures::{Stream, future::join_all};
+use futures::{FutureExt, Stream};
 // Temporarily disabled: use helius_laserstream::SubscribeUpdate;
 use solana_relayer_adapter_rust::Tips;
 use crate::submit_with_services;
 use solana_sdk::{
-    system_instruction::advance_nonce_account,
     transaction::Transaction,
     signature::Signer,
     bs58,
@@ -12,29 +10,10 @@ use solana_sdk::{
 use yellowstone_grpc_proto::prelude::SubscribeUpdate;
 
 use std::time::Instant;
-use std::fs::OpenOptions;
-use std::io::Write;
-use std::sync::Mutex;
+use tracing::{error, info};
 
 use crate::*;
 
-// Mutex to ensure thread-safe file writes for big trades and submissions
-static BIG_TRADE_LOG_MUTEX: Mutex<()> = Mutex::new(());
-
-/// Write a log message to big_trades.txt (thread-safe, opens file in append mode for each write)
-fn write_big_trade_log(message: &str) {
-    let _guard = BIG_TRADE_LOG_MUTEX.lock().unwrap();
-    if let Ok(mut file) = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("big_trades.txt")
-    {
-        if writeln!(file, "{}", message).is_ok() {
-            let _ = file.flush();
-        }
-    }
-}
-
 /// Process a single trade update asynchronously Lil Jit
 /// Temporarily disabled - needs to be refactored for Yellowstone gRPC
 // async fn process_single_trade(sub_update: SubscribeUpdate) {
@@ -57,35 +36,28 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
             },
         };
 
-    // Log big trade discovery details (file + console), easy to read
-    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
     let mother_symbol = &mother_token.5;
     let mother_mint = &mother_token.0;
-    let min_profit = mother_token.4;
-    let change_lines: Vec<String> = changes
+    // Costs and profits are converted at the instrument's own price; none means no trade
+    let Some(token_price_sol) = token_price_sol(mother_mint).await else {
+        return;
+    };
+    let min_profit = min_profit_units(mother_mint, mother_token.4).await;
+    let change_list: Vec<String> = changes
         .iter()
         .map(|c| {
-            let sym = POPULAR_TOKEN_INFO
-                .iter()
-                .find(|t| t.mint == c.mint)
-                .map(|t| t.symbol)
-                .unwrap_or("?");
-            format!("    {}  delta: {:+.6}  pre: {:.6}  post: {:.6}", sym, c.delta, c.pre_balance, c.post_balance)
+            let sym = token_meta(&c.mint).map(|t| t.symbol).unwrap_or_else(|| "?".to_string());
+            format!("{}:{:+.6}", sym, c.delta)
         })
         .collect();
-    let programs_short = program_names.join(", ");
-    let log_block = format!(
-        "[{}] [BIG_TRADE_DISCOVERED]\n  tx_id:     {}\n  mother:    {} ({})\n  min_profit: {:.6}\n  changes:\n{}\n  programs:  {}",
-        timestamp,
-        tx_id,
-        mother_symbol,
-        mother_mint,
+    emit(Event::BigTradeDetected {
+        tx_id: tx_id.clone(),
+        instrument: mother_symbol.clone(),
+        mint: mother_mint.clone(),
         min_profit,
-        change_lines.join("\n"),
-        programs_short
-    );
-    write_big_trade_log(&log_block);
-    // println!("{}\n", log_block);
+        changes: change_list.join(","),
+        programs: program_names.join(","),
+    });
 
     let simulate_start = Instant::now();
 
@@ -102,8 +74,12 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
         .reduce(f64::max)
         .unwrap_or(0.0);
 
-    // Use only tokens that appeared in the big trade (no POPULAR_TOKEN_INFO) to reduce quote count
-    let target_tokens: Vec<String> = unique_tokens.clone();
+    // The instrument's configured target_mints if set; otherwise only tokens that appeared
+    // in the big trade (no POPULAR_TOKEN_INFO) to reduce quote count
+    let target_tokens: Vec<String> = match base_token_config(&mother_token.0) {
+        Some(config) if !config.target_mints.is_empty() => config.target_mints.clone(),
+        _ => unique_tokens.clone(),
+    };
 
     // Run simulation with original input amount range (big trade mode)
     let mut quote_data = simulate_amount_in(
@@ -114,7 +90,7 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
         min_range,
         max_range,
         mother_token.3 as usize,
-        mother_token.4,
+        min_profit,
         false, // is_polling_mode = false for big trade mode
     )
     .await;
@@ -129,7 +105,7 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
             min_range,
             max_range,
             mother_token.3 as usize,
-            mother_token.4,
+            min_profit,
             false, // is_polling_mode = false for big trade mode
         )
         .await;
@@ -149,31 +125,35 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
     }
 
     let sol_price = crate::engine::runtime::get_sol_price_usdc(FEES.sol_usd).await;
-    let token_is_sol = mother_token.0 == "So11111111111111111111111111111111111111112";
 
     // Per-trade tx cost (and tip for submission) when using profit-based third-party fee
-    let with_costs: Vec<(u64, u64, _, _, u128, String, i64, f64)> = quote_data
+    let mut with_costs: Vec<(u64, u64, _, _, u128, String, i64, f64, OpportunityId)> = quote_data
         .into_iter()
-        .map(|(in_amount, out_amount, in_res, out_res, elapsed, target_token)| {
+        .map(|(in_amount, out_amount, in_res, out_res, elapsed, target_token, id)| {
             let gross_profit = out_amount as i64 - in_amount as i64;
             let (total_tx_cost, tip_sol) =
-                crate::engine::runtime::calculate_tx_cost_for_trade_with_sol_price(
+                crate::engine::runtime::calculate_tx_cost_for_trade_with_price(
                     &FEES,
                     gross_profit,
-                    token_is_sol,
                     mother_token.1,
-                    sol_price,
+                    token_price_sol,
+                    &mother_token.0,
+                    in_amount,
                 );
-            (in_amount, out_amount, in_res, out_res, elapsed, target_token, total_tx_cost, tip_sol)
+            let total_tx_cost = total_tx_cost
+                + transfer_fee_cost_raw(&mother_token.0, &target_token, in_amount, in_res.out_amount, out_amount);
+            (in_amount, out_amount, in_res, out_res, elapsed, target_token, total_tx_cost, tip_sol, id)
         })
         .collect();
 
-    let best = with_costs
-        .into_iter()
-        .max_by_key(|(in_amount, out_amount, _, _, _, _, total_tx_cost, _)| {
-            *out_amount as i64 - *in_amount as i64 - *total_tx_cost
-        });
-    let (in_amount, out_amount, in_res, out_res, _elapsed, _target_token, total_tx_cost, tip_sol_for_submit) =
+    with_costs.sort_by_key(|(in_amount, out_amount, _, _, _, _, total_tx_cost, _, _)| {
+        std::cmp::Reverse(*out_amount as i64 - *in_amount as i64 - *total_tx_cost)
+    });
+    for (_, _, _, _, _, _, _, _, id) in with_costs.iter().skip(1) {
+        mark_opportunity_rejected(*id, REJECT_NOT_BEST);
+    }
+    let best = with_costs.into_iter().next();
+    let (in_amount, out_amount, in_res, out_res, _elapsed, target_token, total_tx_cost, tip_sol_for_submit, opportunity_id) =
         match best {
             Some(t) => t,
             None => return,
@@ -183,94 +163,82 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
     let net_profit = gross_profit - total_tx_cost;
     let pow_dec = 10_f64.powf(mother_token.1 as f64);
     let total_tx_cost_usdc =
-        (total_tx_cost as f64 / pow_dec) * if token_is_sol { sol_price } else { 1.0 };
+        total_tx_cost as f64 / pow_dec * token_price_sol * sol_price;
     
-    // Log big trade with profitable opportunity found
-    let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
     let token_symbol = mother_token.5.clone();
-    let token_mint = mother_token.0.clone();
-    
-    // Calculate total delta for the mother token
-    let total_delta: f64 = changes.iter()
-        .filter(|c| c.mint == token_mint)
-        .map(|c| c.delta.abs())
-        .sum();
-    
-    // Format programs and unique_tokens as comma-separated strings
-    let programs_str = program_names.join(", ");
-    let unique_tokens_str = unique_tokens.join(", ");
-    
-    let big_trade_log = format!(
-        "[{}] [BIG_TRADE] Profitable opportunity found: token={} ({}), tx_id={}, delta={:.6}, programs=[{}], unique_tokens=[{}], in_amount={}, out_amount={}, gross_profit={}, net_profit={}",
-        timestamp,
-        token_symbol,
-        token_mint,
-        tx_id,
-        total_delta,
-        programs_str,
-        unique_tokens_str,
-        in_amount,
-        out_amount,
-        gross_profit,
-        net_profit
+    let pow = 10_f64.powf(mother_token.1 as f64);
+    info!(
+        instrument = %token_symbol,
+        %tx_id,
+        in_amount = in_amount as f64 / pow,
+        out_amount = out_amount as f64 / pow,
+        net_profit = net_profit as f64 / pow,
+        tx_cost_usd = total_tx_cost_usdc,
+        "Submitting most profitable big-trade arbitrage"
     );
-    println!("{}", big_trade_log);
-    write_big_trade_log(&big_trade_log);
-
-    // Human-readable submit log (token units + USD tx cost from SOL price)
-    let dec = mother_token.1;
-    let pow = 10_f64.powf(dec as f64);
-    let in_human = in_amount as f64 / pow;
-    let out_human = out_amount as f64 / pow;
-    let gross_human = gross_profit as f64 / pow;
-    let net_human = net_profit as f64 / pow;
-    let tx_cost_human = total_tx_cost as f64 / pow;
-    println!(
-        "[{}] [SUBMIT] 🚀 Submitting most profitable trade:\n  in={:.6} {}  out={:.6} {}  gross_profit={:.6} {}  net_profit={:.6} {}  tx_cost={:.6} {} (≈ ${:.4} USD)",
-        timestamp,
-        in_human, token_symbol,
-        out_human, token_symbol,
-        gross_human, token_symbol,
-        net_human, token_symbol,
-        tx_cost_human, token_symbol,
-        total_tx_cost_usdc
-    );
-    
+
     // Capture values for logging
     let log_in_amount = in_amount;
     let log_out_amount = out_amount;
-    let log_total_tx_cost = total_tx_cost;
     let log_tx_id = tx_id.clone();
     let log_mother_token_symbol = mother_token.5.clone();
-    
-    let tip_sol_submit = tip_sol_for_submit;
-    let tasks = std::iter::once((in_amount, out_amount, in_res, out_res, _elapsed, _target_token))
-        .map(|(_in_amount, _out_amount, in_res, out_res, _elapsed, _target_token)| {
-            let log_in_amount = log_in_amount;
-            let log_out_amount = log_out_amount;
-            let log_total_tx_cost = log_total_tx_cost;
-            let log_tx_id = log_tx_id.clone();
-            let log_mother_token_symbol = log_mother_token_symbol.clone();
-            let tip_sol_amount = tip_sol_submit;
 
-            tokio::spawn(async move {
-                let instr_advance_nonce_account = advance_nonce_account(&NONCE_ADDR, &PUBKEY);
-                let ix = get_swap_ix(
+    if risk_tripped().is_some() {
+        info!(instrument = %token_symbol, %tx_id, "Big-trade submission skipped: circuit breaker tripped");
+        mark_opportunity_rejected(opportunity_id, REJECT_CIRCUIT_BREAKER);
+        return;
+    }
+    let log_mother_mint = mother_token.0.clone();
+    let tip_sol_amount = tip_sol_for_submit;
+    let decimals = mother_token.1;
+    schedule(Candidate {
+        opportunity_id,
+        source: "big_trade",
+        instrument_mint: mother_token.0.clone(),
+        target_mint: target_token,
+        pools: route_pools(&[&in_res, &out_res]),
+        in_amount,
+        net_profit,
+        quoted_at: simulate_start,
+        dispatch: Box::new(move |lease| {
+            async move {
+                let payer = lease.pubkey();
+                let instr_advance_nonce_account = lease.advance_nonce_ix();
+                let venues = route_venues(&[&in_res, &out_res]);
+                let swap = match build_arb_swap(
                     in_res,
                     out_res,
-                    (mother_token.4 * 10_f64.powf(mother_token.1 as f64)) as u64,
+                    (min_profit * 10_f64.powf(decimals as f64)) as u64,
+                    &payer,
                 )
                 .await
-                .unwrap();
+                {
+                    Ok(swap) => swap,
+                    Err(e) => {
+                        lease.release();
+                        mark_opportunity_failed(opportunity_id, REJECT_BUILD_FAILED, e.to_string());
+                        error!(
+                            instrument = %log_mother_token_symbol,
+                            tx_id = %log_tx_id,
+                            error = %e,
+                            "Failed to build big-trade swap"
+                        );
+                        return;
+                    }
+                };
 
-                let mut raw_swap_ixs = Vec::new();
-                raw_swap_ixs.extend(ix.setup_instructions);
-                raw_swap_ixs.push(ix.swap_instruction);
+                let raw_swap_ixs = swap.instructions;
+                let includes_compute_budget = swap.includes_compute_budget;
 
-                let nonce_data = get_nonce();
-                let recent_blockhash = nonce_data.blockhash();
+                let recent_blockhash = lease.blockhash();
 
-                let alts = fetch_alt(ix.address_lookup_table_addresses).await;
+                let alts = fetch_alt(swap.address_lookup_table_addresses).await;
+
+                if !passes_preflight(&payer, &raw_swap_ixs, includes_compute_budget, &alts, &venues).await {
+                    mark_opportunity_rejected(opportunity_id, REJECT_PREFLIGHT_FAILED);
+                    lease.release();
+                    return;
+                }
 
                 // Build transaction to get signature before submitting
                 // Note: This builds a simplified version for signature calculation
@@ -278,62 +246,83 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
                 let mut all_instructions = vec![instr_advance_nonce_account.clone()];
                 all_instructions.extend(raw_swap_ixs.clone());
                 
-                let mut tx = Transaction::new_with_payer(&all_instructions, Some(&PUBKEY));
-                tx.sign(&**SIGNERS, recent_blockhash);
+                let mut tx = Transaction::new_with_payer(&all_instructions, Some(&payer));
+                if let Err(e) = tx.try_sign(&[lease.wallet.signer], recent_blockhash) {
+                    lease.release();
+                    mark_opportunity_failed(opportunity_id, REJECT_BUILD_FAILED, e.to_string());
+                    error!(
+                        instrument = %log_mother_token_symbol,
+                        tx_id = %log_tx_id,
+                        error = %e,
+                        "Failed to sign big-trade transaction"
+                    );
+                    return;
+                }
                 let submitted_tx_signature = bs58::encode(tx.signatures[0]).into_string();
 
-                let service_name = if crate::use_low_latency_submission() {
-                    "low-latency"
-                } else {
-                    "RPC"
-                };
+                let service = crate::submission_label(lease.signers());
 
-                submit_with_services(
+                let sent = submit_with_services(
                     Tips {
                         tip_sol_amount,
                         tip_addr_idx: 0,
-                        cu: Some(FEES.compute_units),
-                        priority_fee_micro_lamport: Some(FEES.priority_lamports),
-                        payer: *PUBKEY,
+                        cu: (!includes_compute_budget).then_some(FEES.compute_units),
+                        priority_fee_micro_lamport: (!includes_compute_budget)
+                            .then_some(FEES.priority_lamports),
+                        payer,
                         pure_ix: raw_swap_ixs,
                     },
-                    &*SIGNERS,
+                    lease.signers(),
                     recent_blockhash,
                     instr_advance_nonce_account,
                     alts,
                     1,
                 )
                 .await;
-                
-                let timestamp = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
-                let submit_msg = format!(
-                    "[{}] [SUBMIT] ✅ Transaction submission completed via {}",
-                    timestamp,
-                    service_name
-                );
-                println!("{}", submit_msg);
-                
-                // Log successful submission with details including submitted tx signature
-                let gross_profit = log_out_amount as i64 - log_in_amount as i64;
-                let net_profit = gross_profit - log_total_tx_cost;
-                let submission_log = format!(
-                    "[{}] [SUBMIT_SUCCESS] token={}, in_amount={}, out_amount={}, gross_profit={}, net_profit={}, tx_cost={}, service={}, original_tx_id={}, submitted_tx_signature={}",
-                    timestamp,
-                    log_mother_token_symbol,
+                if !sent {
+                    lease.release();
+                    mark_opportunity_failed(opportunity_id, REJECT_SEND_FAILED, "transaction not sent".to_string());
+                    return;
+                }
+
+                emit(Event::Submitted {
+                    source: "big_trade",
+                    instrument: log_mother_token_symbol.clone(),
+                    wallet: payer.to_string(),
+                    in_amount: log_in_amount,
+                    expected_out: log_out_amount,
+                    service: service.clone(),
+                    signature: Some(submitted_tx_signature.clone()),
+                });
+                OPPORTUNITIES_SUBMITTED
+                    .with_label_values(&[log_mother_token_symbol.as_str()])
+                    .inc();
+                mark_opportunity_submitted(opportunity_id, payer.to_string(), service);
+                note_submitted(
+                    opportunity_id,
+                    "big_trade",
+                    &log_mother_mint,
+                    payer.to_string(),
                     log_in_amount,
                     log_out_amount,
-                    gross_profit,
-                    net_profit,
-                    log_total_tx_cost,
-                    service_name,
-                    log_tx_id,
-                    submitted_tx_signature
                 );
-                write_big_trade_log(&submission_log);
-            })
-        });
-
-    join_all(tasks).await;
+                track_landing(&lease, &log_mother_mint, venues, opportunity_id);
+                record_trade(
+                    &LedgerEntry::new(
+                        TradeKind::Arbitrage,
+                        log_mother_mint.clone(),
+                        log_mother_mint,
+                        log_in_amount,
+                        log_out_amount,
+                    )
+                    .with_signature(submitted_tx_signature)
+                    .with_wallet(payer.to_string()),
+                );
+            }
+            .boxed()
+        }),
+    })
+    .await;
     
     /* Original Helius Laserstream code (for reference):
     let (mother_token, _changes, _program_names, unique_tokens, _tx_id) =
@@ -450,6 +439,7 @@ pub async fn process_single_trade_yellowstone(sub_update: yellowstone_grpc_proto
                     in_res,
                     out_res,
                     (mother_token.4 * 10_f64.powf(mother_token.1 as f64)) as u64,
+                    true,
                 )
                 .await
                 .unwrap();
@@ -0,0 +1,89 @@
+//! Client for the companion profit-guard program. Its single instruction fails the whole
+//! transaction unless a token account holds at least `min_amount`, so an arbitrage that
+//! fills worse than quoted reverts instead of landing at a loss.
+//!
+//! The pre-swap balance comes from the inventory snapshot and is baked into the instruction
+//! as `pre_balance + min_profit`.
+
+use borsh::{to_vec, BorshDeserialize, BorshSerialize};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+use crate::*;
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub enum ProfitGuardInstruction {
+    /// Accounts: `[token_account]` (read-only). Fails unless its amount is at least `min_amount`.
+    AssertMinBalance { min_amount: u64 },
+}
+
+pub fn profit_guard_ix(program_id: Pubkey, token_account: Pubkey, min_amount: u64) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![AccountMeta::new_readonly(token_account, false)],
+        data: to_vec(&ProfitGuardInstruction::AssertMinBalance { min_amount }).unwrap(),
+    }
+}
+
+/// Guard for `user`'s `mother_token` ATA: balance after the swap must be at least the
+/// current balance plus `extra_amount` (min profit, plus anything wrapped into the ATA
+/// during the transaction). The caller holds `user`'s wallet lease, so none of our other
+/// trades moves the snapshot balance before this transaction lands.
+///
+/// The current balance is the cached inventory snapshot (`token_balance`), not a fresh read
+/// of the ATA. A deposit the snapshot has not seen yet loosens the guard by that amount; a
+/// withdrawal it has not seen makes the guard revert a trade that did profit.
+pub async fn build_profit_guard(
+    program_id: Pubkey,
+    user: &Pubkey,
+    mother_token: &Pubkey,
+    extra_amount: u64,
+) -> Result<Instruction, anyhow::Error> {
+    let token_program = resolve_token_program(mother_token).await?;
+    let ata = get_associated_token_address_with_program_id(user, mother_token, &token_program);
+    let pre_balance = token_balance(user, &mother_token.to_string())
+        .ok_or_else(|| anyhow::anyhow!("{} balance of {} not in inventory", mother_token, user))?;
+    let min_amount = guard_min_amount(pre_balance, extra_amount)
+        .ok_or_else(|| anyhow::anyhow!("profit guard minimum overflows: {} + {}", pre_balance, extra_amount))?;
+
+    Ok(profit_guard_ix(program_id, ata, min_amount))
+}
+
+/// Balance the guarded account must hold after the swap. None on overflow.
+fn guard_min_amount(pre_balance: u64, extra_amount: u64) -> Option<u64> {
+    pre_balance.checked_add(extra_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    #[test]
+    fn encodes_assert_min_balance() {
+        let ix = profit_guard_ix(key(1), key(2), 1_234_567);
+
+        assert_eq!(ix.program_id, key(1));
+        assert_eq!(ix.accounts, vec![AccountMeta::new_readonly(key(2), false)]);
+        let mut data = vec![0u8];
+        data.extend(1_234_567u64.to_le_bytes());
+        assert_eq!(ix.data, data);
+        assert!(matches!(
+            ProfitGuardInstruction::try_from_slice(&ix.data).unwrap(),
+            ProfitGuardInstruction::AssertMinBalance { min_amount: 1_234_567 }
+        ));
+    }
+
+    #[test]
+    fn minimum_is_snapshot_balance_plus_extra() {
+        assert_eq!(guard_min_amount(5_000_000, 25_000), Some(5_025_000));
+        assert_eq!(guard_min_amount(0, 25_000), Some(25_000));
+        assert_eq!(guard_min_amount(u64::MAX, 1), None);
+    }
+}
@@ -0,0 +1,261 @@
+//! Inventory rebalancer: periodically values each base token's balance in USD, compares the
+//! shares against `[rebalance].target_weights` and, outside hot trading windows, swaps the
+//! largest surplus into the largest deficit through Jupiter with a slippage cap. Balances are
+//! those of wallet 0 (the `[connection]` wallet). SOL is only swapped out down to
+//! `[inventory].min_sol_reserve`.
+
+use jupiter_swap_api_client::{
+    quote::QuoteRequest,
+    swap::SwapRequest,
+    transaction_config::TransactionConfig,
+};
+use solana_relayer_adapter_rust::Tips;
+use solana_sdk::pubkey::Pubkey;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::app::config::RebalanceConfig;
+use crate::*;
+
+struct Holding {
+    mint: Pubkey,
+    raw: u64,
+    usd: f64,
+    /// Normalized target share of the total value.
+    target: f64,
+    /// Most that may be swapped out, raw units. For WSOL the swap wraps native SOL, so only
+    /// the SOL above the fee reserve.
+    max_spend: u64,
+}
+
+pub async fn start_rebalancer(interval_secs: u64) {
+    info!(interval_secs, "Starting inventory rebalancer");
+    let mut ticker = interval(Duration::from_secs(interval_secs));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        if let Err(e) = rebalance_once().await {
+            warn!(error = %e, "Rebalance failed");
+        }
+    }
+}
+
+/// One rebalance check; submits at most one swap.
+pub async fn rebalance_once() -> Result<(), anyhow::Error> {
+    let config = &CONFIG.rebalance;
+    if secs_since_last_arbitrage().is_some_and(|secs| secs < config.quiet_period_secs) {
+        debug!("Rebalance skipped: arbitrage submitted recently");
+        return Ok(());
+    }
+
+    let total_weight: f64 = config.target_weights.iter().map(|w| w.weight).sum();
+    if total_weight <= 0.0 {
+        return Ok(());
+    }
+
+    let owner = wallets()[0].pubkey;
+    let mut holdings = Vec::new();
+    for weight in &config.target_weights {
+        let (Ok(mint), Some(raw)) = (weight.mint.parse::<Pubkey>(), spendable_balance(&owner, &weight.mint)) else {
+            return Err(anyhow::anyhow!("Balance of {} not loaded", weight.mint));
+        };
+        let usd = token_value_usd(&weight.mint, raw)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("No price for {}", weight.mint))?;
+        let max_spend = if mint == WSOL {
+            sol_balance(&owner).unwrap_or(0).saturating_sub(fee_reserve_lamports())
+        } else {
+            raw
+        };
+        holdings.push(Holding { mint, raw, usd, target: weight.weight / total_weight, max_spend });
+    }
+
+    let Some((from, to, amount)) = plan_rebalance(&holdings, config) else {
+        return Ok(());
+    };
+    submit_rebalance(from, to, amount, config.max_slippage_bps).await?;
+    info!(%from, %to, amount, "Rebalance submitted");
+    Ok(())
+}
+
+/// Swap moving the largest surplus into the largest deficit: (from, to, raw amount). None while
+/// every share is within `drift_threshold` of its target or the move is under `min_trade_usd`.
+fn plan_rebalance(holdings: &[Holding], config: &RebalanceConfig) -> Option<(Pubkey, Pubkey, u64)> {
+    let total_usd: f64 = holdings.iter().map(|h| h.usd).sum();
+    if total_usd <= 0.0 {
+        return None;
+    }
+    // Signed drift in USD: positive = surplus, negative = deficit
+    let drift = |h: &Holding| h.usd - h.target * total_usd;
+    let surplus = holdings.iter().max_by(|a, b| drift(a).total_cmp(&drift(b)))?;
+    let deficit = holdings.iter().min_by(|a, b| drift(a).total_cmp(&drift(b)))?;
+
+    let max_share_drift = holdings
+        .iter()
+        .map(|h| (h.usd / total_usd - h.target).abs())
+        .fold(0.0, f64::max);
+    if max_share_drift < config.drift_threshold || surplus.mint == deficit.mint {
+        return None;
+    }
+
+    let move_usd = drift(surplus).min(-drift(deficit));
+    if move_usd < config.min_trade_usd || surplus.usd <= 0.0 {
+        return None;
+    }
+    let amount = ((surplus.raw as f64 * move_usd / surplus.usd) as u64).min(surplus.max_spend);
+    (amount > 0).then_some((surplus.mint, deficit.mint, amount))
+}
+
+async fn submit_rebalance(
+    input_mint: Pubkey,
+    output_mint: Pubkey,
+    amount: u64,
+    slippage_bps: u16,
+) -> Result<(), anyhow::Error> {
+    let excluded = excluded_venues();
+    let request = QuoteRequest {
+        amount,
+        input_mint,
+        output_mint,
+        slippage_bps,
+        exclude_dexes: (!excluded.is_empty()).then(|| excluded.join(",").into()),
+        ..QuoteRequest::default()
+    };
+    let quote = observe_jupiter("quote", JUPITER_CLIENT.quote(&request)).await?;
+    let expected_out = quote.out_amount;
+
+    if !CONFIG.strategy.live_trading {
+        info!(%input_mint, %output_mint, amount, expected_out, "Rebalance not submitted while execution is disabled");
+        return Ok(());
+    }
+
+    let lease = lease_primary_wallet().ok_or_else(|| anyhow::anyhow!("Wallet nonce is busy"))?;
+    let payer = lease.pubkey();
+    let request = SwapRequest {
+        user_public_key: payer,
+        quote_response: quote,
+        config: TransactionConfig {
+            wrap_and_unwrap_sol: true,
+            skip_user_accounts_rpc_calls: true,
+            ..Default::default()
+        },
+    };
+    let ix = match observe_jupiter("swap_instructions", JUPITER_CLIENT.swap_instructions(&request)).await {
+        Ok(ix) => ix,
+        Err(e) => {
+            lease.release();
+            return Err(e.into());
+        }
+    };
+
+    // Setup + swap only: SOL received stays wrapped in the WSOL ATA, like arbitrage output
+    let mut instructions = strip_provisioned_ata_creates(ix.setup_instructions);
+    instructions.push(ix.swap_instruction);
+    let alts = fetch_alt(ix.address_lookup_table_addresses).await;
+
+    let sent = submit_with_services(
+        Tips {
+            tip_sol_amount: FEES.tip_sol,
+            tip_addr_idx: 0,
+            cu: Some(FEES.compute_units),
+            priority_fee_micro_lamport: Some(FEES.priority_lamports),
+            payer,
+            pure_ix: instructions,
+        },
+        lease.signers(),
+        lease.blockhash(),
+        lease.advance_nonce_ix(),
+        alts,
+        1,
+    )
+    .await;
+    if !sent {
+        lease.release();
+        return Err(anyhow::anyhow!("Rebalance transaction not sent"));
+    }
+
+    record_trade(
+        &LedgerEntry::new(
+            TradeKind::Rebalance,
+            input_mint.to_string(),
+            output_mint.to_string(),
+            amount,
+            expected_out,
+        )
+        .with_wallet(payer.to_string()),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn holding(byte: u8, usd: f64, target: f64) -> Holding {
+        // 1 raw unit = $0.000001
+        let raw = (usd * 1e6) as u64;
+        Holding { mint: key(byte), raw, usd, target, max_spend: raw }
+    }
+
+    fn config(drift_threshold: f64, min_trade_usd: f64) -> RebalanceConfig {
+        RebalanceConfig { drift_threshold, min_trade_usd, ..RebalanceConfig::default() }
+    }
+
+    #[test]
+    fn moves_the_surplus_into_the_deficit() {
+        let holdings = [holding(1, 700.0, 0.5), holding(2, 300.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), Some((key(1), key(2), 200_000_000)));
+    }
+
+    #[test]
+    fn moves_only_what_the_deficit_lacks() {
+        // Token 1 is $300 over its target, token 3 only $200 under
+        let holdings = [holding(1, 600.0, 0.3), holding(2, 300.0, 0.4), holding(3, 100.0, 0.3)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), Some((key(1), key(3), 200_000_000)));
+    }
+
+    #[test]
+    fn holds_within_the_drift_threshold() {
+        let holdings = [holding(1, 540.0, 0.5), holding(2, 460.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), None);
+        assert!(plan_rebalance(&holdings, &config(0.03, 10.0)).is_some());
+    }
+
+    #[test]
+    fn skips_moves_below_min_trade_usd() {
+        let holdings = [holding(1, 70.0, 0.5), holding(2, 30.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 25.0)), None);
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 20.0)), Some((key(1), key(2), 20_000_000)));
+    }
+
+    #[test]
+    fn never_swaps_a_mint_into_itself() {
+        let holdings = [holding(1, 700.0, 1.0)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.0, 0.0)), None);
+    }
+
+    #[test]
+    fn holds_without_inventory_value() {
+        let holdings = [holding(1, 0.0, 0.5), holding(2, 0.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), None);
+        assert_eq!(plan_rebalance(&[], &config(0.1, 10.0)), None);
+    }
+
+    #[test]
+    fn caps_the_amount_at_what_may_be_spent() {
+        let mut sol = holding(1, 700.0, 0.5);
+        sol.max_spend = 150_000_000;
+        let holdings = [sol, holding(2, 300.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), Some((key(1), key(2), 150_000_000)));
+
+        let mut sol = holding(1, 700.0, 0.5);
+        sol.max_spend = 0;
+        let holdings = [sol, holding(2, 300.0, 0.5)];
+        assert_eq!(plan_rebalance(&holdings, &config(0.1, 10.0)), None);
+    }
+}
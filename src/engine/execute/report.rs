@@ -0,0 +1,609 @@
+//! `report` subcommand: daily or weekly summaries over the opportunity database and the trade
+//! ledger, printed as aligned tables, CSV or JSON.
+//!
+//! The covered range starts at a UTC midnight, a Monday when weekly, so the first period is
+//! complete; only the current one is partial.
+//!
+//! Amounts are shown in token units using the cached token registry. Realized PnL is the
+//! base-token balance change of tracked landings (`venues.track_landings`), so network fees
+//! and tips paid in SOL only show up in it for SOL-denominated instruments.
+
+use chrono::{Datelike, Days, NaiveDate, Utc};
+use rusqlite::{Connection, OpenFlags};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::*;
+
+pub const REPORT_USAGE: &str =
+    "Usage: report [--period daily|weekly] [--format table|csv|json] [--days N] [--top N]";
+
+const TOP_PAIRS: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportPeriod {
+    Daily,
+    Weekly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub period: ReportPeriod,
+    pub format: ReportFormat,
+    /// Days of history covered, counted back from today (widened to a week boundary when weekly).
+    pub days: u32,
+    pub top: usize,
+}
+
+impl ReportOptions {
+    /// Parse the arguments after `report`. Defaults: daily, table, 7 days (28 when weekly).
+    pub fn parse(args: &[String]) -> Result<Self, anyhow::Error> {
+        let mut period = ReportPeriod::Daily;
+        let mut format = ReportFormat::Table;
+        let mut days = None;
+        let mut top = TOP_PAIRS;
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("{} needs a value\n{}", flag, REPORT_USAGE))
+            };
+            match flag.as_str() {
+                "--period" => {
+                    period = match value()?.as_str() {
+                        "daily" | "day" => ReportPeriod::Daily,
+                        "weekly" | "week" => ReportPeriod::Weekly,
+                        other => return Err(anyhow::anyhow!("unknown period \"{}\"\n{}", other, REPORT_USAGE)),
+                    }
+                }
+                "--format" => {
+                    format = match value()?.as_str() {
+                        "table" => ReportFormat::Table,
+                        "csv" => ReportFormat::Csv,
+                        "json" => ReportFormat::Json,
+                        other => return Err(anyhow::anyhow!("unknown format \"{}\"\n{}", other, REPORT_USAGE)),
+                    }
+                }
+                "--days" => days = Some(value()?.parse()?),
+                "--top" => top = value()?.parse()?,
+                other => return Err(anyhow::anyhow!("unknown argument \"{}\"\n{}", other, REPORT_USAGE)),
+            }
+        }
+        let days = days.unwrap_or(match period {
+            ReportPeriod::Daily => 7,
+            ReportPeriod::Weekly => 28,
+        });
+        Ok(Self { period, format, days, top })
+    }
+
+    /// First day covered when the report runs on `today` (UTC): `days` back including today,
+    /// moved back to that week's Monday when weekly.
+    fn first_day(&self, today: NaiveDate) -> NaiveDate {
+        let first = today - Days::new(self.days.saturating_sub(1) as u64);
+        match self.period {
+            ReportPeriod::Daily => first,
+            ReportPeriod::Weekly => first - Days::new(first.weekday().num_days_from_monday() as u64),
+        }
+    }
+
+    /// Period label of a `YYYY-MM-DD` day: the day itself, or its ISO week (`2026-W42`).
+    fn period_of(&self, day: &str) -> String {
+        match (self.period, NaiveDate::parse_from_str(day, "%Y-%m-%d")) {
+            (ReportPeriod::Weekly, Ok(date)) => {
+                let week = date.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            _ => day.to_string(),
+        }
+    }
+}
+
+/// One section of the report.
+#[derive(Debug, Clone)]
+pub struct ReportTable {
+    pub name: &'static str,
+    pub headers: Vec<&'static str>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Per period and instrument; additive so days can be folded into weeks.
+#[derive(Debug, Default)]
+struct Summary {
+    evaluated: i64,
+    profitable: i64,
+    submitted: i64,
+    tracked: i64,
+    landed: i64,
+    realized: i64,
+    tx_cost: i64,
+    tip_sol: f64,
+}
+
+/// Parse `args`, build the report from the database at `db_path` and the ledger, and print it.
+pub fn run_report(db_path: &str, args: &[String]) -> Result<(), anyhow::Error> {
+    let options = ReportOptions::parse(args)?;
+    let tables = build_report(db_path, &options)?;
+    print!("{}", render_report(&tables, options.format));
+    Ok(())
+}
+
+pub fn build_report(db_path: &str, options: &ReportOptions) -> Result<Vec<ReportTable>, anyhow::Error> {
+    let conn = Connection::open_with_flags(db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| anyhow::anyhow!("cannot open opportunity database {}: {}", db_path, e))?;
+    let since_ms = options
+        .first_day(Utc::now().date_naive())
+        .and_hms_opt(0, 0, 0)
+        .map(|start| start.and_utc().timestamp_millis())
+        .unwrap_or_default();
+
+    Ok(vec![
+        summary_table(&conn, options, since_ms)?,
+        ledger_table(options, since_ms)?,
+        services_table(&conn, since_ms)?,
+        top_pairs_table(&conn, options, since_ms)?,
+        notional_table(&conn, since_ms)?,
+        slippage_table(&conn, since_ms)?,
+    ])
+}
+
+fn summary_table(conn: &Connection, options: &ReportOptions, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT strftime('%Y-%m-%d', ts_ms / 1000, 'unixepoch') AS day, instrument_mint,
+            COUNT(*),
+            COALESCE(SUM(net_profit > min_profit), 0),
+            COALESCE(SUM(submitted), 0),
+            COALESCE(SUM(submitted = 1 AND landed IS NOT NULL), 0),
+            COALESCE(SUM(landed = 1), 0),
+            COALESCE(SUM(CASE WHEN landed = 1 THEN realized_profit END), 0),
+            COALESCE(SUM(CASE WHEN submitted = 1 THEN tx_cost END), 0),
+            COALESCE(SUM(CASE WHEN submitted = 1 THEN tip_sol END), 0.0)
+         FROM opportunities WHERE ts_ms >= ?1
+         GROUP BY day, instrument_mint",
+    )?;
+    let mut summaries: BTreeMap<(String, String), Summary> = BTreeMap::new();
+    let mut rows = stmt.query([since_ms])?;
+    while let Some(row) = rows.next()? {
+        let day: String = row.get(0)?;
+        let s = summaries.entry((options.period_of(&day), row.get(1)?)).or_default();
+        s.evaluated += row.get::<_, i64>(2)?;
+        s.profitable += row.get::<_, i64>(3)?;
+        s.submitted += row.get::<_, i64>(4)?;
+        s.tracked += row.get::<_, i64>(5)?;
+        s.landed += row.get::<_, i64>(6)?;
+        s.realized += row.get::<_, i64>(7)?;
+        s.tx_cost += row.get::<_, i64>(8)?;
+        s.tip_sol += row.get::<_, f64>(9)?;
+    }
+
+    Ok(ReportTable {
+        name: "summary",
+        headers: vec![
+            "period", "instrument", "evaluated", "profitable", "submitted", "landed", "landing_rate",
+            "realized_pnl", "tx_cost", "tip_sol",
+        ],
+        rows: summaries
+            .into_iter()
+            .map(|((period, mint), s)| {
+                vec![
+                    period,
+                    token_symbol(&mint),
+                    s.evaluated.to_string(),
+                    s.profitable.to_string(),
+                    s.submitted.to_string(),
+                    s.landed.to_string(),
+                    rate(s.landed, s.tracked),
+                    token_amount(&mint, s.realized as f64),
+                    token_amount(&mint, s.tx_cost as f64),
+                    format!("{:.6}", s.tip_sol),
+                ]
+            })
+            .collect(),
+    })
+}
+
+fn ledger_table(options: &ReportOptions, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let since_day = chrono::DateTime::from_timestamp_millis(since_ms)
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    let mut counts: BTreeMap<(String, &'static str), i64> = BTreeMap::new();
+    for entry in read_ledger()? {
+        let Some(day) = entry.timestamp.get(..10) else {
+            continue;
+        };
+        if day < since_day.as_str() {
+            continue;
+        }
+        let kind = match entry.kind {
+            TradeKind::Arbitrage => "arbitrage",
+            TradeKind::Rebalance => "rebalance",
+        };
+        *counts.entry((options.period_of(day), kind)).or_default() += 1;
+    }
+    Ok(ReportTable {
+        name: "ledger",
+        headers: vec!["period", "kind", "trades"],
+        rows: counts
+            .into_iter()
+            .map(|((period, kind), trades)| vec![period, kind.to_string(), trades.to_string()])
+            .collect(),
+    })
+}
+
+fn services_table(conn: &Connection, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT COALESCE(service, 'unknown'), COUNT(*),
+            COALESCE(SUM(landed IS NOT NULL), 0), COALESCE(SUM(landed = 1), 0)
+         FROM opportunities WHERE submitted = 1 AND ts_ms >= ?1
+         GROUP BY 1 ORDER BY 2 DESC",
+    )?;
+    let rows = stmt
+        .query_map([since_ms], |row| {
+            let (submitted, tracked, landed): (i64, i64, i64) = (row.get(1)?, row.get(2)?, row.get(3)?);
+            Ok(vec![
+                row.get::<_, String>(0)?,
+                submitted.to_string(),
+                tracked.to_string(),
+                landed.to_string(),
+                rate(landed, tracked),
+            ])
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(ReportTable {
+        name: "services",
+        headers: vec!["service", "submitted", "tracked", "landed", "landing_rate"],
+        rows,
+    })
+}
+
+fn top_pairs_table(conn: &Connection, options: &ReportOptions, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT instrument_mint, target_mint, COUNT(*),
+            COALESCE(SUM(net_profit > min_profit), 0), COALESCE(SUM(submitted), 0),
+            COALESCE(SUM(CASE WHEN landed = 1 THEN realized_profit END), 0), MAX(net_profit)
+         FROM opportunities WHERE ts_ms >= ?1 AND quoted_out IS NOT NULL
+         GROUP BY 1, 2 ORDER BY 4 DESC, 3 DESC LIMIT ?2",
+    )?;
+    let rows = stmt
+        .query_map(rusqlite::params![since_ms, options.top as i64], |row| {
+            let mint: String = row.get(0)?;
+            let (evaluated, profitable): (i64, i64) = (row.get(2)?, row.get(3)?);
+            Ok(vec![
+                token_symbol(&mint),
+                token_symbol(&row.get::<_, String>(1)?),
+                evaluated.to_string(),
+                profitable.to_string(),
+                rate(profitable, evaluated),
+                row.get::<_, i64>(4)?.to_string(),
+                token_amount(&mint, row.get::<_, i64>(5)? as f64),
+                row.get::<_, Option<i64>>(6)?
+                    .map(|best| token_amount(&mint, best as f64))
+                    .unwrap_or_default(),
+            ])
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(ReportTable {
+        name: "top_pairs",
+        headers: vec![
+            "instrument", "target", "evaluated", "profitable", "hit_rate", "submitted", "realized_pnl",
+            "best_net_profit",
+        ],
+        rows,
+    })
+}
+
+/// Best notional bucket per instrument. Buckets are one significant digit of the raw input
+/// amount (`[2, 3) x 10^k`), grouped in SQL so large databases stay cheap to scan.
+fn notional_table(conn: &Connection, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT instrument_mint, length(in_amount), substr(in_amount, 1, 1), COUNT(*),
+            COALESCE(SUM(net_profit > min_profit), 0), AVG(net_profit)
+         FROM opportunities WHERE ts_ms >= ?1 AND quoted_out IS NOT NULL AND in_amount > 0
+         GROUP BY 1, 2, 3",
+    )?;
+    // instrument -> (lower, upper, evaluated, profitable, avg net profit), best bucket only
+    let mut best: BTreeMap<String, (f64, f64, i64, i64, f64)> = BTreeMap::new();
+    let mut rows = stmt.query([since_ms])?;
+    while let Some(row) = rows.next()? {
+        let mint: String = row.get(0)?;
+        let digits: i64 = row.get(1)?;
+        let lead: f64 = row.get::<_, String>(2)?.parse().unwrap_or(0.0);
+        let scale = 10_f64.powi(digits as i32 - 1);
+        let bucket: (f64, f64, i64, i64, f64) = (
+            lead * scale,
+            (lead + 1.0) * scale,
+            row.get(3)?,
+            row.get(4)?,
+            row.get::<_, Option<f64>>(5)?.unwrap_or(0.0),
+        );
+        let better = match best.get(&mint) {
+            Some(current) => (bucket.3, bucket.4) > (current.3, current.4),
+            None => true,
+        };
+        if better {
+            best.insert(mint, bucket);
+        }
+    }
+    Ok(ReportTable {
+        name: "best_notional",
+        headers: vec!["instrument", "from", "to", "evaluated", "profitable", "hit_rate", "avg_net_profit"],
+        rows: best
+            .into_iter()
+            .map(|(mint, (lower, upper, evaluated, profitable, avg_net))| {
+                vec![
+                    token_symbol(&mint),
+                    token_amount(&mint, lower),
+                    token_amount(&mint, upper),
+                    evaluated.to_string(),
+                    profitable.to_string(),
+                    rate(profitable, evaluated),
+                    token_amount(&mint, avg_net),
+                ]
+            })
+            .collect(),
+    })
+}
+
+/// Expected gross profit at submission against the realized balance change of landings.
+fn slippage_table(conn: &Connection, since_ms: i64) -> Result<ReportTable, anyhow::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT instrument_mint, COUNT(*), COALESCE(SUM(gross_profit), 0), COALESCE(SUM(realized_profit), 0),
+            AVG((gross_profit - realized_profit) * 10000.0 / in_amount)
+         FROM opportunities WHERE landed = 1 AND in_amount > 0 AND ts_ms >= ?1
+         GROUP BY 1",
+    )?;
+    let rows = stmt
+        .query_map([since_ms], |row| {
+            let mint: String = row.get(0)?;
+            Ok(vec![
+                token_symbol(&mint),
+                row.get::<_, i64>(1)?.to_string(),
+                token_amount(&mint, row.get::<_, i64>(2)? as f64),
+                token_amount(&mint, row.get::<_, i64>(3)? as f64),
+                format!("{:.1}", row.get::<_, Option<f64>>(4)?.unwrap_or(0.0)),
+            ])
+        })?
+        .collect::<Result<_, _>>()?;
+    Ok(ReportTable {
+        name: "slippage",
+        headers: vec!["instrument", "landed", "expected_gross", "realized", "avg_slippage_bps"],
+        rows,
+    })
+}
+
+/// Raw amount of `mint` in token units; raw when its decimals are not cached.
+fn token_amount(mint: &str, raw: f64) -> String {
+    match token_meta(mint) {
+        Some(meta) => format!("{:.6}", raw / 10_f64.powi(meta.decimals as i32)),
+        None => format!("{:.0}", raw),
+    }
+}
+
+fn rate(part: i64, whole: i64) -> String {
+    if whole == 0 {
+        return "-".to_string();
+    }
+    format!("{:.1}%", part as f64 * 100.0 / whole as f64)
+}
+
+pub fn render_report(tables: &[ReportTable], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Table => render_text(tables),
+        ReportFormat::Csv => render_csv(tables),
+        ReportFormat::Json => render_json(tables),
+    }
+}
+
+fn render_text(tables: &[ReportTable]) -> String {
+    let mut out = String::new();
+    for table in tables {
+        let _ = writeln!(out, "== {} ==", table.name);
+        if table.rows.is_empty() {
+            out.push_str("(no data)\n\n");
+            continue;
+        }
+        let widths: Vec<usize> = table
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| table.rows.iter().map(|r| r[i].len()).chain([h.len()]).max().unwrap_or(0))
+            .collect();
+        let line = |cells: Vec<&str>| -> String {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = *width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let _ = writeln!(out, "{}", line(table.headers.clone()));
+        for row in &table.rows {
+            let _ = writeln!(out, "{}", line(row.iter().map(String::as_str).collect()));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn render_csv(tables: &[ReportTable]) -> String {
+    let field = |value: &str| {
+        if value.contains([',', '"', '\n']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    };
+    let mut out = String::new();
+    for (i, table) in tables.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "# {}", table.name);
+        let _ = writeln!(out, "{}", table.headers.join(","));
+        for row in &table.rows {
+            let _ = writeln!(out, "{}", row.iter().map(|v| field(v)).collect::<Vec<_>>().join(","));
+        }
+    }
+    out
+}
+
+/// `{"<section>": [{"<header>": value, ...}, ...], ...}`; numeric cells become JSON numbers.
+fn render_json(tables: &[ReportTable]) -> String {
+    let mut report = Map::new();
+    for table in tables {
+        let rows: Vec<Value> = table
+            .rows
+            .iter()
+            .map(|row| {
+                let cells = table.headers.iter().zip(row).map(|(header, cell)| {
+                    let value = match cell.parse::<f64>() {
+                        Ok(n) if n.is_finite() => serde_json::json!(n),
+                        _ => Value::String(cell.clone()),
+                    };
+                    (header.to_string(), value)
+                });
+                Value::Object(cells.collect())
+            })
+            .collect();
+        report.insert(table.name.to_string(), Value::Array(rows));
+    }
+    let mut json = serde_json::to_string_pretty(&Value::Object(report)).unwrap_or_default();
+    json.push('\n');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn date(day: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").unwrap()
+    }
+
+    fn tables() -> Vec<ReportTable> {
+        vec![
+            ReportTable {
+                name: "summary",
+                headers: vec!["period", "instrument", "evaluated", "landing_rate"],
+                rows: vec![
+                    vec!["2026-10-12".to_string(), "USDC".to_string(), "1200".to_string(), "50.0%".to_string()],
+                    vec!["2026-10-13".to_string(), "SOL, wrapped".to_string(), "7".to_string(), "-".to_string()],
+                ],
+            },
+            ReportTable { name: "ledger", headers: vec!["period", "kind", "trades"], rows: vec![] },
+        ]
+    }
+
+    #[test]
+    fn parses_defaults() {
+        let options = ReportOptions::parse(&[]).unwrap();
+        assert_eq!(options.period, ReportPeriod::Daily);
+        assert_eq!(options.format, ReportFormat::Table);
+        assert_eq!((options.days, options.top), (7, TOP_PAIRS));
+
+        let weekly = ReportOptions::parse(&args("--period week")).unwrap();
+        assert_eq!((weekly.period, weekly.days), (ReportPeriod::Weekly, 28));
+    }
+
+    #[test]
+    fn parses_every_flag() {
+        let options = ReportOptions::parse(&args("--format json --period weekly --days 90 --top 3")).unwrap();
+        assert_eq!(options.period, ReportPeriod::Weekly);
+        assert_eq!(options.format, ReportFormat::Json);
+        assert_eq!((options.days, options.top), (90, 3));
+        assert_eq!(ReportOptions::parse(&args("--format csv")).unwrap().format, ReportFormat::Csv);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        for bad in ["--days", "--days ten", "--period monthly", "--format xml", "--verbose", "--top -1"] {
+            assert!(ReportOptions::parse(&args(bad)).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn daily_range_starts_at_midnight_days_back() {
+        let options = ReportOptions::parse(&args("--days 7")).unwrap();
+        // 2026-10-19 is a Monday
+        assert_eq!(options.first_day(date("2026-10-19")), date("2026-10-13"));
+        let options = ReportOptions::parse(&args("--days 1")).unwrap();
+        assert_eq!(options.first_day(date("2026-10-19")), date("2026-10-19"));
+    }
+
+    #[test]
+    fn weekly_range_starts_on_a_monday() {
+        let options = ReportOptions::parse(&args("--period weekly --days 14")).unwrap();
+        // 14 days back from Wednesday 2026-10-21 is Thursday 2026-10-08, in the week of 10-05
+        let first = options.first_day(date("2026-10-21"));
+        assert_eq!(first, date("2026-10-05"));
+        assert_eq!(options.period_of("2026-10-05"), options.period_of("2026-10-11"));
+        assert_eq!(options.period_of("2026-10-05"), "2026-W41");
+        assert_eq!(options.period_of("2026-10-12"), "2026-W42");
+        // Already a Monday
+        assert_eq!(options.first_day(date("2026-10-25")), date("2026-10-12"));
+    }
+
+    #[test]
+    fn daily_period_is_the_day() {
+        let options = ReportOptions::parse(&[]).unwrap();
+        assert_eq!(options.period_of("2026-10-19"), "2026-10-19");
+        // ISO week years differ from calendar years around new year
+        let weekly = ReportOptions::parse(&args("--period weekly")).unwrap();
+        assert_eq!(weekly.period_of("2027-01-01"), "2026-W53");
+    }
+
+    #[test]
+    fn renders_aligned_table() {
+        let text = render_report(&tables(), ReportFormat::Table);
+        let expected = "\
+== summary ==
+period      instrument    evaluated  landing_rate
+2026-10-12  USDC          1200       50.0%
+2026-10-13  SOL, wrapped  7          -
+
+== ledger ==
+(no data)
+
+";
+        assert_eq!(text, expected);
+    }
+
+    #[test]
+    fn renders_csv_with_quoting() {
+        let csv = render_report(&tables(), ReportFormat::Csv);
+        let expected = "\
+# summary
+period,instrument,evaluated,landing_rate
+2026-10-12,USDC,1200,50.0%
+2026-10-13,\"SOL, wrapped\",7,-
+
+# ledger
+period,kind,trades
+";
+        assert_eq!(csv, expected);
+    }
+
+    #[test]
+    fn renders_json_with_numbers() {
+        let json: Value = serde_json::from_str(&render_report(&tables(), ReportFormat::Json)).unwrap();
+        let summary = json["summary"].as_array().unwrap();
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0]["evaluated"], 1200.0);
+        assert_eq!(summary[0]["landing_rate"], "50.0%");
+        assert_eq!(summary[1]["instrument"], "SOL, wrapped");
+        assert_eq!(json["ledger"], Value::Array(vec![]));
+    }
+}
@@ -0,0 +1,122 @@
+//! Circuit breaker: halts arbitrage submission for a cooldown after a streak of trades that
+//! landed without profit. Trading loops keep quoting while tripped; only submission stops.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::app::config::RiskConfig;
+use crate::{notify, AlertKind, CONFIG};
+
+static BREAKER: Mutex<Breaker> = Mutex::new(Breaker { losses: 0, tripped_until: None });
+
+#[derive(Debug, Default)]
+struct Breaker {
+    /// Unprofitable landings since the last profitable one or trip.
+    losses: u32,
+    tripped_until: Option<Instant>,
+}
+
+impl Breaker {
+    /// Count an outcome. Returns the loss streak when it trips the breaker.
+    fn record(&mut self, profitable: bool, config: &RiskConfig, now: Instant) -> Option<u32> {
+        if profitable {
+            self.losses = 0;
+            return None;
+        }
+        self.losses += 1;
+        if config.max_consecutive_losses == 0 || self.losses < config.max_consecutive_losses {
+            return None;
+        }
+        let losses = std::mem::take(&mut self.losses);
+        self.tripped_until = Some(now + Duration::from_secs(config.cooldown_secs));
+        Some(losses)
+    }
+
+    /// Remaining halt time; clears a trip whose cooldown has passed.
+    fn remaining(&mut self, now: Instant) -> Option<Duration> {
+        let until = self.tripped_until?;
+        if until > now {
+            return Some(until - now);
+        }
+        self.tripped_until = None;
+        None
+    }
+}
+
+/// Feed a landed trade's outcome into the breaker.
+pub fn record_trade_outcome(profitable: bool) {
+    let config = &CONFIG.risk;
+    let Some(losses) = BREAKER.lock().unwrap().record(profitable, config, Instant::now()) else {
+        return;
+    };
+    warn!(losses, cooldown_secs = config.cooldown_secs, "Circuit breaker tripped; submission halted");
+    notify(
+        AlertKind::CircuitBreakerTripped,
+        format!("{} unprofitable landings in a row; submission halted for {}s", losses, config.cooldown_secs),
+    );
+}
+
+/// Remaining halt time while the breaker is tripped.
+pub fn risk_tripped() -> Option<Duration> {
+    let mut breaker = BREAKER.lock().unwrap();
+    let was_tripped = breaker.tripped_until.is_some();
+    let remaining = breaker.remaining(Instant::now());
+    if was_tripped && remaining.is_none() {
+        info!("Circuit breaker reset; submission resumed");
+    }
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_consecutive_losses: u32) -> RiskConfig {
+        RiskConfig { max_consecutive_losses, cooldown_secs: 900 }
+    }
+
+    #[test]
+    fn trips_after_the_loss_streak() {
+        let mut breaker = Breaker::default();
+        let now = Instant::now();
+        assert_eq!(breaker.record(false, &config(3), now), None);
+        assert_eq!(breaker.record(false, &config(3), now), None);
+        assert_eq!(breaker.record(false, &config(3), now), Some(3));
+        assert_eq!(breaker.remaining(now), Some(Duration::from_secs(900)));
+        // The streak restarts once tripped
+        assert_eq!(breaker.losses, 0);
+    }
+
+    #[test]
+    fn a_profit_resets_the_streak() {
+        let mut breaker = Breaker::default();
+        let now = Instant::now();
+        breaker.record(false, &config(3), now);
+        breaker.record(false, &config(3), now);
+        breaker.record(true, &config(3), now);
+        assert_eq!(breaker.record(false, &config(3), now), None);
+        assert_eq!(breaker.record(false, &config(3), now), None);
+        assert_eq!(breaker.remaining(now), None);
+    }
+
+    #[test]
+    fn expires_after_the_cooldown() {
+        let mut breaker = Breaker::default();
+        let now = Instant::now();
+        assert_eq!(breaker.record(false, &config(1), now), Some(1));
+        assert_eq!(breaker.remaining(now + Duration::from_secs(899)), Some(Duration::from_secs(1)));
+        assert_eq!(breaker.remaining(now + Duration::from_secs(900)), None);
+        assert!(breaker.tripped_until.is_none());
+    }
+
+    #[test]
+    fn disabled_breaker_never_trips() {
+        let mut breaker = Breaker::default();
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(breaker.record(false, &config(0), now), None);
+        }
+        assert_eq!(breaker.remaining(now), None);
+    }
+}
@@ -0,0 +1,429 @@
+//! Opportunity scheduler: polling, big-trade and direct discovery hand their best candidate
+//! here instead of submitting on their own. A single dispatcher keeps waiting candidates in a
+//! max-heap by expected net profit in USD × landing probability and offers them a wallet best
+//! first. A candidate leaves the heap when it is dispatched, when its quotes pass `max_age_ms`
+//! (its deadline) or when it overlaps a recent dispatch (same instrument/target pair or a
+//! shared pool). One that finds no free wallet waits for the next round until its deadline.
+//! Wallets are allocated one candidate at a time, so two modes never race for the same nonce
+//! or inventory.
+//!
+//! A source's landing probability starts at `landing_prior` and follows its tracked landings.
+
+use futures::future::BoxFuture;
+use once_cell::sync::{Lazy, OnceCell};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, info, warn};
+
+use crate::*;
+
+/// Dispatched ids are remembered this long for their landing outcome.
+const LANDING_MEMORY: Duration = Duration::from_secs(600);
+/// How soon candidates waiting for a wallet are offered one again.
+const WALLET_RETRY: Duration = Duration::from_millis(20);
+
+static SCHEDULER_QUEUE: OnceCell<mpsc::Sender<Ranked>> = OnceCell::new();
+/// Source -> (landed, tracked) submissions.
+static LANDING_STATS: Lazy<Mutex<HashMap<&'static str, (u64, u64)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Dispatched opportunity -> source and dispatch time, until its landing is known.
+static DISPATCHED: Lazy<Mutex<HashMap<OpportunityId, (&'static str, Instant)>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Builds and submits the trade with the wallet the scheduler allocated.
+pub type Dispatch = Box<dyn FnOnce(WalletLease) -> BoxFuture<'static, ()> + Send>;
+
+/// A trade ready to submit.
+pub struct Candidate {
+    pub opportunity_id: OpportunityId,
+    /// `polling`, `big_trade` or `direct`.
+    pub source: &'static str,
+    pub instrument_mint: String,
+    pub target_mint: String,
+    /// Pool accounts of both legs.
+    pub pools: Vec<String>,
+    pub in_amount: u64,
+    /// Expected net profit after costs, raw instrument units.
+    pub net_profit: i64,
+    /// When the quotes were taken; the candidate expires `max_age_ms` later.
+    pub quoted_at: Instant,
+    pub dispatch: Dispatch,
+}
+
+impl Candidate {
+    fn overlaps(&self, other: &Dispatched) -> bool {
+        (self.instrument_mint == other.instrument_mint && self.target_mint == other.target_mint)
+            || self.pools.iter().any(|pool| other.pools.contains(pool))
+    }
+}
+
+struct Ranked {
+    candidate: Candidate,
+    /// Expected net profit in USD × landing probability.
+    score: f64,
+    /// Set once the candidate found no free wallet.
+    waited_for_wallet: bool,
+}
+
+/// Heap order: higher score first, then the older quote, which expires sooner.
+impl Ord for Ranked {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.candidate.quoted_at.cmp(&self.candidate.quoted_at))
+    }
+}
+
+impl PartialOrd for Ranked {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Ranked {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Ranked {}
+
+struct Dispatched {
+    at: Instant,
+    instrument_mint: String,
+    target_mint: String,
+    pools: Vec<String>,
+}
+
+/// Waiting candidates and the dispatches they are deduplicated against.
+struct Queue {
+    heap: BinaryHeap<Ranked>,
+    recent: VecDeque<Dispatched>,
+    max_age: Duration,
+}
+
+impl Queue {
+    fn new(max_age: Duration) -> Self {
+        Self { heap: BinaryHeap::new(), recent: VecDeque::new(), max_age }
+    }
+
+    fn push(&mut self, ranked: Ranked) {
+        self.heap.push(ranked);
+    }
+
+    /// Offer every waiting candidate to `start`, best first. Candidates past their deadline or
+    /// overlapping a dispatch of the last `max_age` are returned with the reason they were
+    /// rejected; ones `start` hands back (no free wallet) stay queued for the next round.
+    fn run(
+        &mut self,
+        now: Instant,
+        mut start: impl FnMut(Candidate) -> Result<(), Candidate>,
+    ) -> Vec<(Ranked, &'static str)> {
+        while self.recent.front().is_some_and(|d| now.duration_since(d.at) >= self.max_age) {
+            self.recent.pop_front();
+        }
+        let mut rejected = Vec::new();
+        let mut waiting = Vec::new();
+        while let Some(ranked) = self.heap.pop() {
+            if now.duration_since(ranked.candidate.quoted_at) > self.max_age {
+                let reason = if ranked.waited_for_wallet { REJECT_NO_WALLET } else { REJECT_EXPIRED };
+                rejected.push((ranked, reason));
+                continue;
+            }
+            if self.recent.iter().any(|d| ranked.candidate.overlaps(d)) {
+                rejected.push((ranked, REJECT_DUPLICATE));
+                continue;
+            }
+            let dispatched = Dispatched {
+                at: now,
+                instrument_mint: ranked.candidate.instrument_mint.clone(),
+                target_mint: ranked.candidate.target_mint.clone(),
+                pools: ranked.candidate.pools.clone(),
+            };
+            let Ranked { candidate, score, .. } = ranked;
+            match start(candidate) {
+                Ok(()) => self.recent.push_back(dispatched),
+                Err(candidate) => waiting.push(Ranked { candidate, score, waited_for_wallet: true }),
+            }
+        }
+        self.heap.extend(waiting);
+        rejected
+    }
+
+    /// When the next round is due without a new arrival: the retry for candidates waiting on a
+    /// wallet, or the earliest deadline if sooner. None while nothing waits.
+    fn next_round(&self, now: Instant) -> Option<Instant> {
+        let earliest_deadline = self.heap.iter().map(|r| r.candidate.quoted_at + self.max_age).min()?;
+        Some(earliest_deadline.min(now + WALLET_RETRY))
+    }
+}
+
+/// Hand a candidate to the scheduler. Without it (disabled, or not started yet) the
+/// candidate is dispatched right away. Candidates not expected to make a profit are rejected.
+pub async fn schedule(candidate: Candidate) {
+    if candidate.net_profit <= 0 {
+        debug!(source = candidate.source, net_profit = candidate.net_profit, "Unprofitable candidate dropped");
+        SCHEDULED.with_label_values(&[candidate.source, REJECT_BELOW_MIN_PROFIT]).inc();
+        mark_opportunity_rejected(candidate.opportunity_id, REJECT_BELOW_MIN_PROFIT);
+        return;
+    }
+    let Some(queue) = SCHEDULER_QUEUE.get() else {
+        dispatch(candidate);
+        return;
+    };
+    let score = score(&candidate).await;
+    if let Err(e) = queue.try_send(Ranked { candidate, score, waited_for_wallet: false }) {
+        let candidate = match e {
+            mpsc::error::TrySendError::Full(ranked) | mpsc::error::TrySendError::Closed(ranked) => ranked.candidate,
+        };
+        warn!(source = candidate.source, instrument = %token_symbol(&candidate.instrument_mint), "Scheduler queue full; candidate dropped");
+        SCHEDULED.with_label_values(&[candidate.source, "queue_full"]).inc();
+        mark_opportunity_rejected(candidate.opportunity_id, REJECT_QUEUE_FULL);
+    }
+}
+
+async fn score(candidate: &Candidate) -> f64 {
+    let profit_usd = token_value_usd(&candidate.instrument_mint, candidate.net_profit as u64)
+        .await
+        .unwrap_or(0.0);
+    profit_usd * landing_probability(candidate.source)
+}
+
+/// Share of `source`'s tracked submissions that landed, smoothed towards `landing_prior`.
+pub fn landing_probability(source: &str) -> f64 {
+    let config = &CONFIG.scheduler;
+    let (landed, tracked) = LANDING_STATS.lock().unwrap().get(source).copied().unwrap_or_default();
+    smoothed_landing_rate(landed, tracked, config.landing_prior, config.landing_prior_weight)
+}
+
+/// `prior` counted as `prior_weight` submissions on top of the tracked ones.
+fn smoothed_landing_rate(landed: u64, tracked: u64, prior: f64, prior_weight: f64) -> f64 {
+    (landed as f64 + prior * prior_weight) / (tracked as f64 + prior_weight).max(f64::MIN_POSITIVE)
+}
+
+/// Landing outcome of a tracked submission; updates its source's landing probability.
+pub fn note_scheduled_landing(id: OpportunityId, landed: bool) {
+    let Some((source, _)) = DISPATCHED.lock().unwrap().remove(&id) else {
+        return;
+    };
+    let mut stats = LANDING_STATS.lock().unwrap();
+    let entry = stats.entry(source).or_default();
+    entry.0 += landed as u64;
+    entry.1 += 1;
+}
+
+/// Allocate a wallet and start the submission, or reject the candidate when none can take it.
+fn dispatch(candidate: Candidate) {
+    if let Err(candidate) = try_dispatch(candidate) {
+        warn!(
+            source = candidate.source,
+            instrument = %token_symbol(&candidate.instrument_mint),
+            in_amount = candidate.in_amount,
+            "No wallet with balance and a free nonce; trade skipped"
+        );
+        SCHEDULED.with_label_values(&[candidate.source, "no_wallet"]).inc();
+        mark_opportunity_rejected(candidate.opportunity_id, REJECT_NO_WALLET);
+    }
+}
+
+/// Allocate a wallet and start the submission; hands the candidate back when no wallet can
+/// take the trade.
+fn try_dispatch(candidate: Candidate) -> Result<(), Candidate> {
+    let Some(lease) = allocate_wallet(&candidate.instrument_mint, candidate.in_amount) else {
+        return Err(candidate);
+    };
+    let source = candidate.source;
+    {
+        let mut dispatched = DISPATCHED.lock().unwrap();
+        dispatched.retain(|_, (_, at)| at.elapsed() < LANDING_MEMORY);
+        dispatched.insert(candidate.opportunity_id, (source, Instant::now()));
+    }
+    SCHEDULED.with_label_values(&[source, "dispatched"]).inc();
+    tokio::spawn((candidate.dispatch)(lease));
+    Ok(())
+}
+
+/// Run the dispatcher until the process exits. No-op unless `[scheduler]` is enabled.
+pub async fn start_scheduler() {
+    let config = &CONFIG.scheduler;
+    if !config.enabled {
+        return;
+    }
+    let (tx, mut rx) = mpsc::channel(config.queue_capacity);
+    if SCHEDULER_QUEUE.set(tx).is_err() {
+        return;
+    }
+    info!(max_age_ms = config.max_age_ms, "Opportunity scheduler started");
+
+    let mut queue = Queue::new(Duration::from_millis(config.max_age_ms));
+    loop {
+        let next_round = queue.next_round(Instant::now());
+        tokio::select! {
+            received = rx.recv() => match received {
+                Some(ranked) => queue.push(ranked),
+                None => return,
+            },
+            _ = tokio::time::sleep_until(next_round.unwrap_or_else(Instant::now).into()), if next_round.is_some() => {}
+        }
+        // Everything that arrived meanwhile competes in this round
+        while let Ok(ranked) = rx.try_recv() {
+            queue.push(ranked);
+        }
+
+        let rejected = queue.run(Instant::now(), |candidate| {
+            debug!(source = candidate.source, "Dispatching candidate");
+            try_dispatch(candidate)
+        });
+        for (Ranked { candidate, score, .. }, reason) in rejected {
+            let source = candidate.source;
+            debug!(source, score, reason, age_ms = candidate.quoted_at.elapsed().as_millis(), "Candidate dropped");
+            SCHEDULED.with_label_values(&[source, reason]).inc();
+            mark_opportunity_rejected(candidate.opportunity_id, reason);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    const MAX_AGE: Duration = Duration::from_millis(1_000);
+
+    fn candidate(id: i64, pair: (&str, &str), pools: &[&str], quoted_at: Instant) -> Candidate {
+        Candidate {
+            opportunity_id: OpportunityId(id),
+            source: "polling",
+            instrument_mint: pair.0.to_string(),
+            target_mint: pair.1.to_string(),
+            pools: pools.iter().map(|p| p.to_string()).collect(),
+            in_amount: 1_000_000,
+            net_profit: 1_000,
+            quoted_at,
+            dispatch: Box::new(|_: WalletLease| async {}.boxed()),
+        }
+    }
+
+    fn ranked(candidate: Candidate, score: f64) -> Ranked {
+        Ranked { candidate, score, waited_for_wallet: false }
+    }
+
+    fn dispatched(pair: (&str, &str), pools: &[&str]) -> Dispatched {
+        Dispatched {
+            at: Instant::now(),
+            instrument_mint: pair.0.to_string(),
+            target_mint: pair.1.to_string(),
+            pools: pools.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    /// Run one round, starting every candidate whose id is in `wallet_free`; returns the ids
+    /// started, in order, and the rejected ids with their reason.
+    fn round(queue: &mut Queue, now: Instant, wallet_free: &[i64]) -> (Vec<i64>, Vec<(i64, &'static str)>) {
+        let mut started = Vec::new();
+        let rejected = queue.run(now, |candidate| {
+            if wallet_free.contains(&candidate.opportunity_id.0) {
+                started.push(candidate.opportunity_id.0);
+                Ok(())
+            } else {
+                Err(candidate)
+            }
+        });
+        let rejected = rejected.into_iter().map(|(r, reason)| (r.candidate.opportunity_id.0, reason)).collect();
+        (started, rejected)
+    }
+
+    #[test]
+    fn overlaps_on_pair_or_pool() {
+        let now = Instant::now();
+        let recent = dispatched(("USDC", "SOL"), &["pool_a", "pool_b"]);
+        assert!(candidate(1, ("USDC", "SOL"), &["pool_c"], now).overlaps(&recent));
+        assert!(candidate(2, ("USDT", "JUP"), &["pool_b"], now).overlaps(&recent));
+        // Reverse direction of the same pair is a different trade
+        assert!(!candidate(3, ("SOL", "USDC"), &["pool_c"], now).overlaps(&recent));
+        assert!(!candidate(4, ("USDC", "JUP"), &[], now).overlaps(&recent));
+    }
+
+    #[test]
+    fn landing_rate_starts_at_prior_and_follows_landings() {
+        assert_eq!(smoothed_landing_rate(0, 0, 0.5, 10.0), 0.5);
+        // 5 + 9 landed of 10 + 10
+        assert_eq!(smoothed_landing_rate(9, 10, 0.5, 10.0), 0.7);
+        assert_eq!(smoothed_landing_rate(3, 4, 0.5, 0.0), 0.75);
+        assert_eq!(smoothed_landing_rate(0, 0, 0.5, 0.0), 0.0);
+    }
+
+    #[test]
+    fn dispatches_best_score_first() {
+        let now = Instant::now();
+        let mut queue = Queue::new(MAX_AGE);
+        queue.push(ranked(candidate(1, ("USDC", "SOL"), &["a"], now), 1.0));
+        queue.push(ranked(candidate(2, ("USDC", "JUP"), &["b"], now), 3.0));
+        queue.push(ranked(candidate(3, ("USDT", "SOL"), &["c"], now), 2.0));
+        let (started, rejected) = round(&mut queue, now, &[1, 2, 3]);
+        assert_eq!(started, vec![2, 3, 1]);
+        assert!(rejected.is_empty());
+        assert!(queue.heap.is_empty());
+        assert_eq!(queue.next_round(now), None);
+    }
+
+    #[test]
+    fn drops_candidates_past_their_deadline() {
+        let quoted_at = Instant::now();
+        let mut queue = Queue::new(MAX_AGE);
+        queue.push(ranked(candidate(1, ("USDC", "SOL"), &["a"], quoted_at), 1.0));
+        let (started, rejected) = round(&mut queue, quoted_at + MAX_AGE + Duration::from_millis(1), &[1]);
+        assert!(started.is_empty());
+        assert_eq!(rejected, vec![(1, REJECT_EXPIRED)]);
+    }
+
+    #[test]
+    fn drops_overlapping_candidates_within_max_age() {
+        let now = Instant::now();
+        let mut queue = Queue::new(MAX_AGE);
+        queue.push(ranked(candidate(1, ("USDC", "SOL"), &["a"], now), 2.0));
+        queue.push(ranked(candidate(2, ("USDC", "SOL"), &["b"], now), 1.0));
+        let (started, rejected) = round(&mut queue, now, &[1, 2]);
+        assert_eq!(started, vec![1]);
+        assert_eq!(rejected, vec![(2, REJECT_DUPLICATE)]);
+
+        // Still blocked in a later round, free once the dispatch is max_age old
+        let later = now + Duration::from_millis(500);
+        queue.push(ranked(candidate(3, ("USDT", "JUP"), &["a"], later), 1.0));
+        assert_eq!(round(&mut queue, later, &[3]), (vec![], vec![(3, REJECT_DUPLICATE)]));
+        queue.push(ranked(candidate(4, ("USDT", "JUP"), &["a"], later), 1.0));
+        assert_eq!(round(&mut queue, now + MAX_AGE, &[4]), (vec![4], vec![]));
+    }
+
+    #[test]
+    fn waits_for_a_wallet_until_the_deadline() {
+        let now = Instant::now();
+        let mut queue = Queue::new(MAX_AGE);
+        queue.push(ranked(candidate(1, ("USDC", "SOL"), &["a"], now), 2.0));
+        queue.push(ranked(candidate(2, ("USDC", "JUP"), &["b"], now), 1.0));
+
+        // No wallet for the best one; the next still gets one
+        assert_eq!(round(&mut queue, now, &[2]), (vec![2], vec![]));
+        assert_eq!(queue.next_round(now), Some(now + WALLET_RETRY));
+        assert_eq!(round(&mut queue, now, &[]), (vec![], vec![]));
+        // Expired while waiting
+        let late = now + MAX_AGE + Duration::from_millis(1);
+        assert_eq!(round(&mut queue, late, &[1]), (vec![], vec![(1, REJECT_NO_WALLET)]));
+
+        // A wallet frees up in time
+        queue.push(ranked(candidate(3, ("USDT", "SOL"), &["c"], late), 1.0));
+        assert_eq!(round(&mut queue, late, &[]), (vec![], vec![]));
+        assert_eq!(round(&mut queue, late + WALLET_RETRY, &[3]), (vec![3], vec![]));
+    }
+
+    #[test]
+    fn next_round_is_the_earliest_deadline_when_sooner() {
+        let quoted_at = Instant::now();
+        let now = quoted_at + MAX_AGE - Duration::from_millis(5);
+        let mut queue = Queue::new(MAX_AGE);
+        queue.push(ranked(candidate(1, ("USDC", "SOL"), &["a"], quoted_at), 1.0));
+        assert_eq!(queue.next_round(now), Some(quoted_at + MAX_AGE));
+    }
+}
@@ -0,0 +1,146 @@
+//! Wallet allocator: assigns each opportunity to a trading wallet that has the balance for it
+//! and a free durable nonce, so independent trades go out in parallel without contending for
+//! one payer's account locks.
+//!
+//! A leased nonce stays busy until its on-chain value advances (the transaction landed) or
+//! the lease times out (the transaction was dropped and the nonce is still usable).
+
+use once_cell::sync::Lazy;
+use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signer::keypair::Keypair,
+    system_instruction::advance_nonce_account,
+};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::*;
+
+const NONCE_LEASE_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Nonce account -> (nonce value it was leased at, lease time).
+static NONCE_LEASES: Lazy<Mutex<HashMap<Pubkey, (Hash, Instant)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_WALLET: AtomicUsize = AtomicUsize::new(0);
+
+/// A wallet reserved for one transaction, with the nonce value to sign against.
+pub struct WalletLease {
+    pub wallet: &'static TradingWallet,
+    blockhash: Hash,
+}
+
+impl WalletLease {
+    pub fn pubkey(&self) -> Pubkey {
+        self.wallet.pubkey
+    }
+
+    /// Keypairs for the relayer adapter; empty for a remote signer.
+    pub fn signers(&self) -> &'static Vec<&'static Keypair> {
+        &self.wallet.signers
+    }
+
+    /// The leased nonce value, used as the transaction's recent blockhash.
+    pub fn blockhash(&self) -> Hash {
+        self.blockhash
+    }
+
+    pub fn advance_nonce_ix(&self) -> Instruction {
+        advance_nonce_account(&self.wallet.nonce_account, &self.wallet.pubkey)
+    }
+
+    /// Return the nonce without submitting (e.g. building the transaction failed).
+    pub fn release(self) {
+        NONCE_LEASES.lock().unwrap().remove(&self.wallet.nonce_account);
+    }
+}
+
+fn try_lease(wallet: &'static TradingWallet) -> Option<WalletLease> {
+    let blockhash = get_nonce_of(&wallet.nonce_account).blockhash();
+    if blockhash == Hash::default() {
+        return None;
+    }
+    let now = Instant::now();
+    let mut leases = NONCE_LEASES.lock().unwrap();
+    if !nonce_free(leases.get(&wallet.nonce_account), blockhash, now) {
+        return None;
+    }
+    leases.insert(wallet.nonce_account, (blockhash, now));
+    Some(WalletLease { wallet, blockhash })
+}
+
+/// A nonce is free unless it is leased at its current value for less than the lease timeout.
+fn nonce_free(lease: Option<&(Hash, Instant)>, blockhash: Hash, now: Instant) -> bool {
+    lease.is_none_or(|(leased_at, since)| {
+        *leased_at != blockhash || now.duration_since(*since) >= NONCE_LEASE_TIMEOUT
+    })
+}
+
+/// Wallet indexes in allocation order: all `len` of them, starting at `start` and wrapping.
+fn round_robin(start: usize, len: usize) -> impl Iterator<Item = usize> {
+    (0..len).map(move |i| (start + i) % len)
+}
+
+/// Lease a wallet for a trade spending `amount` raw units of `mint`: round-robin over wallets
+/// with SOL above the fee reserve, enough spendable `mint` (unless flash-funded) and a free
+/// nonce. None when every wallet is busy or short.
+pub fn allocate_wallet(mint: &str, amount: u64) -> Option<WalletLease> {
+    let all = wallets();
+    let flash_funded = flash_loan_reserve(mint).is_some();
+    let start = NEXT_WALLET.fetch_add(1, Ordering::Relaxed);
+    round_robin(start, all.len())
+        .map(|i| &all[i])
+        .filter(|w| {
+            has_fee_reserve(&w.pubkey)
+                && (flash_funded || spendable_balance(&w.pubkey, mint).is_some_and(|b| b >= amount))
+        })
+        .find_map(try_lease)
+}
+
+/// Lease wallet 0 (the `[connection]` wallet), for maintenance trades such as rebalancing.
+pub fn lease_primary_wallet() -> Option<WalletLease> {
+    try_lease(&wallets()[0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> Hash {
+        Hash::new_from_array([byte; 32])
+    }
+
+    #[test]
+    fn round_robin_starts_at_the_cursor_and_wraps() {
+        assert_eq!(round_robin(0, 3).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(round_robin(4, 3).collect::<Vec<_>>(), vec![1, 2, 0]);
+        assert_eq!(round_robin(7, 1).collect::<Vec<_>>(), vec![0]);
+        assert_eq!(round_robin(3, 0).count(), 0);
+    }
+
+    #[test]
+    fn nonce_is_busy_until_it_advances_or_the_lease_times_out() {
+        let now = Instant::now();
+        let lease = (hash(1), now);
+        assert!(nonce_free(None, hash(1), now));
+        assert!(!nonce_free(Some(&lease), hash(1), now + Duration::from_secs(19)));
+        // Landed: the nonce advanced
+        assert!(nonce_free(Some(&lease), hash(2), now + Duration::from_secs(1)));
+        // Dropped: the lease timed out
+        assert!(nonce_free(Some(&lease), hash(1), now + NONCE_LEASE_TIMEOUT));
+        assert_eq!(NONCE_LEASE_TIMEOUT, Duration::from_secs(20));
+    }
+
+    #[test]
+    fn allocation_skips_busy_nonces() {
+        let now = Instant::now();
+        let leases = [Some((hash(1), now)), Some((hash(1), now)), None, Some((hash(1), now))];
+        let free = |i: usize| nonce_free(leases[i].as_ref(), hash(1), now + Duration::from_secs(5));
+        assert_eq!(round_robin(0, 4).find(|&i| free(i)), Some(2));
+        assert_eq!(round_robin(3, 4).find(|&i| free(i)), Some(2));
+        let later = |i: usize| nonce_free(leases[i].as_ref(), hash(1), now + Duration::from_secs(20));
+        assert_eq!(round_robin(3, 4).find(|&i| later(i)), Some(3));
+    }
+}
@@ -1,29 +1,34 @@
 use crate::{
-    JUPITER_EVENT_AUTH, JUPITER_PROGRAM_ADDR, PUBKEY, ROUTE_DISCRIMINATOR, RouteArgs,
-    TOKEN_PROGRAM_ID,
+    JUPITER_EVENT_AUTH, JUPITER_PROGRAM_ADDR, ROUTE_DISCRIMINATOR, RouteArgs,
 };
 use borsh::to_vec;
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
-use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 
+/// Build a Jupiter `route` instruction that starts and ends in `user`'s `mother_token`
+/// ATA. `token_program` is the mint's owner program (SPL Token or Token-2022).
 pub fn build_swap_ix(
     route_args: RouteArgs,
     remaining_accounts: Vec<AccountMeta>,
+    user: &Pubkey,
     mother_token: Pubkey,
+    token_program: Pubkey,
 ) -> Instruction {
-    let user_source_token_account = get_associated_token_address(&PUBKEY.clone(), &mother_token);
+    let user_source_token_account =
+        get_associated_token_address_with_program_id(user, &mother_token, &token_program);
 
     let mut data: Vec<u8> = Vec::new();
 
     data.extend(ROUTE_DISCRIMINATOR);
     data.extend(to_vec(&route_args).unwrap());
 
+    // Optional accounts (destination_token_account, platform_fee_account) are passed as the program id
     let mut accounts = vec![
-        AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-        AccountMeta::new(PUBKEY.clone(), true),
+        AccountMeta::new_readonly(token_program, false),
+        AccountMeta::new(*user, true),
         AccountMeta::new(user_source_token_account, false),
         AccountMeta::new(user_source_token_account, false),
         AccountMeta::new_readonly(JUPITER_PROGRAM_ADDR, false),
@@ -41,3 +46,76 @@ pub fn build_swap_ix(
         data,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{RoutePlanStep, Swap};
+    use borsh::BorshDeserialize;
+
+    fn route_args() -> RouteArgs {
+        RouteArgs {
+            route_plan: vec![
+                RoutePlanStep { swap: Swap::Raydium, percent: 100, input_index: 0, output_index: 1 },
+                RoutePlanStep { swap: Swap::Whirlpool { a_to_b: true }, percent: 100, input_index: 1, output_index: 2 },
+            ],
+            in_amount: 1_000_000,
+            quoted_out_amount: 1_000_500,
+            slippage_bps: 50,
+            platform_fee_bps: 0,
+        }
+    }
+
+    #[test]
+    fn encodes_route_layout() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ix = build_swap_ix(route_args(), Vec::new(), &user, mint, spl_token::ID);
+
+        let expected: Vec<u8> = [
+            &ROUTE_DISCRIMINATOR[..],
+            &[2, 0, 0, 0],                            // route_plan length
+            &[7, 100, 0, 1],                          // Raydium, 100%, 0 -> 1
+            &[17, 1, 100, 1, 2],                      // Whirlpool { a_to_b: true }, 100%, 1 -> 2
+            &[0x40, 0x42, 0x0f, 0, 0, 0, 0, 0],       // in_amount 1_000_000
+            &[0x34, 0x44, 0x0f, 0, 0, 0, 0, 0],       // quoted_out_amount 1_000_500
+            &[50, 0],                                 // slippage_bps
+            &[0],                                     // platform_fee_bps
+        ]
+        .concat();
+        assert_eq!(ix.data, expected);
+
+        let decoded = RouteArgs::try_from_slice(&ix.data[8..]).unwrap();
+        assert_eq!(decoded.route_plan.len(), 2);
+        assert_eq!((decoded.in_amount, decoded.quoted_out_amount), (1_000_000, 1_000_500));
+        assert!(matches!(decoded.route_plan[1].swap, Swap::Whirlpool { a_to_b: true }));
+    }
+
+    #[test]
+    fn orders_fixed_accounts_before_swap_accounts() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let pool = AccountMeta::new(Pubkey::new_unique(), false);
+        let ix = build_swap_ix(route_args(), vec![pool.clone()], &user, mint, spl_token::ID);
+
+        let ata = get_associated_token_address_with_program_id(&user, &mint, &spl_token::ID);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|a| a.pubkey).collect();
+        assert_eq!(
+            keys,
+            vec![
+                spl_token::ID,
+                user,
+                ata,
+                ata,
+                JUPITER_PROGRAM_ADDR,
+                mint,
+                JUPITER_PROGRAM_ADDR,
+                JUPITER_EVENT_AUTH,
+                JUPITER_PROGRAM_ADDR,
+                pool.pubkey,
+            ]
+        );
+        assert!(ix.accounts[1].is_signer);
+        assert!(ix.accounts[2].is_writable && ix.accounts[3].is_writable);
+    }
+}
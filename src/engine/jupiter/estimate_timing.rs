@@ -1,4 +1,4 @@
-//! Estimate Jupiter API latency: quote (2 calls) and build swap tx (swap_instructions).
+//! Estimate Jupiter API latency: quote (2 calls) and build swap tx (configured route builder).
 
 use std::time::Instant;
 
@@ -9,16 +9,16 @@ use crate::*;
 pub struct JupiterTimingEstimate {
     /// Time for one full quote (mother -> target -> mother), i.e. 2 Jupiter quote API calls, in ms.
     pub quote_ms: u64,
-    /// Time for Jupiter swap_instructions (build swap tx) in ms.
+    /// Time to build the swap instructions (one or two swap_instructions calls) in ms.
     pub swap_build_ms: u64,
     /// Total for quote + swap_build (sequential).
     pub total_ms: u64,
 }
 
-/// Run one quote (2 Jupiter API calls) and one swap_instructions call using config's first
+/// Run one quote (2 Jupiter API calls) and one swap build using config's first
 /// base token and a single target, then return estimated timings in ms.
 ///
-/// Uses: first base token from config, one target (USDC if base is SOL, else quote_mint),
+/// Uses: first base token from config, its first target mint (see `instrument_targets`),
 /// amount = geometric midpoint of amount_range, and min_profit for swap build.
 pub async fn estimate_jupiter_timing() -> Result<JupiterTimingEstimate, anyhow::Error> {
     let base_config = BASE_TOKENS
@@ -26,23 +26,14 @@ pub async fn estimate_jupiter_timing() -> Result<JupiterTimingEstimate, anyhow::
         .ok_or_else(|| anyhow::anyhow!("No base token in config"))?;
     let mother_token = base_config.mint.as_str();
 
-    let (decimal, symbol) = POPULAR_TOKEN_INFO
-        .iter()
-        .find(|t| t.mint == mother_token)
-        .map(|t| (t.decimals, t.symbol))
-        .unwrap_or_else(|| {
-            if mother_token == "So11111111111111111111111111111111111111112" {
-                (9, "SOL")
-            } else {
-                (6, "UNKNOWN")
-            }
-        });
+    let meta = instrument_meta(mother_token)
+        .ok_or_else(|| anyhow::anyhow!("{} is not a tradable instrument", mother_token))?;
+    let (decimal, symbol) = (meta.decimals, meta.symbol.as_str());
 
-    let target_token = if symbol == "SOL" || symbol == "WSOL" || mother_token == "So11111111111111111111111111111111111111112" {
-        "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v" // USDC
-    } else {
-        TARGET_TOKEN.as_str()
-    };
+    let targets = instrument_targets(base_config);
+    let target_token = targets
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("{} has no target mints", symbol))?;
 
     let [min_f, max_f] = base_config.amount_range;
     let mid_f = (min_f * max_f).sqrt();
@@ -50,13 +41,13 @@ pub async fn estimate_jupiter_timing() -> Result<JupiterTimingEstimate, anyhow::
 
     // Time: one full quote (2 Jupiter quote API calls)
     let quote_start = Instant::now();
-    let (_, _, in_res, out_res) = get_quote_polling(amount, mother_token, target_token).await?;
+    let (_, _, in_res, out_res) = get_quote_polling(amount, mother_token, target_token, &RouteConstraints::for_instrument(mother_token)).await?;
     let quote_ms = quote_start.elapsed().as_millis() as u64;
 
-    // Time: build swap instructions (1 Jupiter swap_instructions API call)
+    // Time: build swap instructions with the configured route builder
     let min_profit_raw = (base_config.min_profit * 10_f64.powf(decimal as f64)) as u64;
     let swap_start = Instant::now();
-    let _ = get_swap_ix(in_res, out_res, min_profit_raw).await?;
+    let _ = build_arb_swap(in_res, out_res, min_profit_raw, &PUBKEY).await?;
     let swap_build_ms = swap_start.elapsed().as_millis() as u64;
 
     Ok(JupiterTimingEstimate {
@@ -0,0 +1,83 @@
+//! Merged-route path: each leg is built by Jupiter `/swap-instructions`, then both route plans
+//! are combined into one `route` instruction whose quoted output is `in_amount + min_profit`,
+//! so the Jupiter program itself rejects an unprofitable fill.
+
+use jupiter_swap_api_client::{
+    quote::QuoteResponse,
+    swap::SwapRequest,
+    transaction_config::TransactionConfig,
+};
+use solana_sdk::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub struct MergedRouteIx {
+    pub setup_instructions: Vec<Instruction>,
+    pub swap_instruction: Instruction,
+    pub address_lookup_table_addresses: Vec<Pubkey>,
+}
+
+/// Per-leg request. Shared accounts must stay off so every leg has the 9 fixed `route`
+/// accounts, and SOL is not wrapped per leg (the merged route uses the WSOL ATA directly).
+fn leg_request(quote_response: QuoteResponse, user: &Pubkey) -> SwapRequest {
+    SwapRequest {
+        quote_response,
+        config: TransactionConfig {
+            use_shared_accounts: Some(false),
+            wrap_and_unwrap_sol: false,
+            skip_user_accounts_rpc_calls: true,
+            ..Default::default()
+        },
+        user_public_key: *user,
+    }
+}
+
+pub async fn get_merged_route_ix(
+    quote_response_1: QuoteResponse,
+    quote_response_2: QuoteResponse,
+    min_profit_amount: u64,
+    user: &Pubkey,
+) -> Result<MergedRouteIx, anyhow::Error> {
+    let mother_token = quote_response_1.input_mint;
+    let in_amount = quote_response_1.in_amount;
+    let quoted_out_amount = in_amount + min_profit_amount;
+
+    let leg_1 = leg_request(quote_response_1.clone(), user);
+    let leg_2 = leg_request(quote_response_2.clone(), user);
+    let (mut ix1, mut ix2) = tokio::try_join!(
+        observe_jupiter("swap_instructions", JUPITER_CLIENT.swap_instructions(&leg_1)),
+        observe_jupiter("swap_instructions", JUPITER_CLIENT.swap_instructions(&leg_2)),
+    )?;
+
+    // Both legs usually create the same intermediate ATA; keep one copy of each setup ix
+    let mut setup_instructions: Vec<Instruction> = Vec::new();
+    for ix in std::mem::take(&mut ix1.setup_instructions)
+        .into_iter()
+        .chain(std::mem::take(&mut ix2.setup_instructions))
+    {
+        if !setup_instructions.contains(&ix) {
+            setup_instructions.push(ix);
+        }
+    }
+
+    let (route_plan, remaining_accounts, _token_addresses, address_lookup_table_addresses) =
+        get_swap_infos(ix1, ix2, quote_response_1, quote_response_2)?;
+
+    let route_args = RouteArgs {
+        route_plan,
+        in_amount,
+        quoted_out_amount,
+        slippage_bps: CONFIG.strategy.route_slippage_bps,
+        platform_fee_bps: 0,
+    };
+
+    let token_program = resolve_token_program(&mother_token).await?;
+    let swap_instruction = build_swap_ix(route_args, remaining_accounts, user, mother_token, token_program);
+
+    Ok(MergedRouteIx {
+        setup_instructions,
+        swap_instruction,
+        address_lookup_table_addresses,
+    })
+}
@@ -1,25 +1,103 @@
 use jupiter_swap_api_client::quote::{QuoteRequest, QuoteResponse};
 use solana_sdk::pubkey::Pubkey;
 
-use crate::JUPITER_CLIENT;
+use crate::{base_token_config, excluded_venues, merge_labels, observe_jupiter, JUPITER_CLIENT};
+
+/// Per-instrument routing limits applied to both legs of every quote.
+#[derive(Debug, Clone, Default)]
+pub struct RouteConstraints {
+    /// Jupiter `dexes` (allow list); empty allows all.
+    pub dexes: Vec<String>,
+    /// Jupiter `excludeDexes`: the instrument's list plus the venue denylist and blacklist.
+    pub exclude_dexes: Vec<String>,
+    pub max_route_hops: Option<usize>,
+    /// Overrides the mode default (multi-hop for polling, direct for big trades).
+    pub only_direct_routes: Option<bool>,
+}
+
+impl RouteConstraints {
+    /// Constraints of the instrument `mother_token`; only the venue policy for other mints.
+    pub fn for_instrument(mother_token: &str) -> Self {
+        let mut constraints = base_token_config(mother_token)
+            .map(|t| Self {
+                dexes: t.dexes.clone(),
+                exclude_dexes: t.exclude_dexes.clone(),
+                max_route_hops: t.max_route_hops,
+                only_direct_routes: t.only_direct_routes,
+            })
+            .unwrap_or_default();
+        constraints.exclude_dexes = merge_labels(constraints.exclude_dexes, excluded_venues());
+        constraints
+    }
+
+    fn dexes(&self) -> Option<String> {
+        (!self.dexes.is_empty()).then(|| self.dexes.join(","))
+    }
+
+    /// The instrument's excluded DEXes plus `extra` (comma-separated labels).
+    fn exclude_dexes(&self, extra: Option<&str>) -> Option<String> {
+        let labels: Vec<&str> = self
+            .exclude_dexes
+            .iter()
+            .map(String::as_str)
+            .chain(extra.into_iter().flat_map(|e| e.split(',')))
+            .filter(|label| !label.is_empty())
+            .collect();
+        (!labels.is_empty()).then(|| labels.join(","))
+    }
+
+    /// `onlyDirectRoutes` of the request: `max_route_hops = 1` makes Jupiter quote direct routes
+    /// only; otherwise the instrument's setting, else `mode_default`.
+    fn only_direct_routes(&self, mode_default: Option<bool>) -> Option<bool> {
+        if self.max_route_hops == Some(1) {
+            Some(true)
+        } else {
+            self.only_direct_routes.or(mode_default)
+        }
+    }
+
+    /// The quote API has no hop limit besides direct routes, so limits above 1 are checked on
+    /// the returned plan.
+    fn check_hops(&self, quote: &QuoteResponse) -> Result<(), anyhow::Error> {
+        self.check_route_hops(quote.route_plan.iter().map(|step| step.swap_info.input_mint))
+    }
+
+    /// Hops of a route given the input mint of each plan step. Split steps of one hop share
+    /// their input mint and count once.
+    fn check_route_hops(&self, input_mints: impl IntoIterator<Item = Pubkey>) -> Result<(), anyhow::Error> {
+        let Some(max) = self.max_route_hops.filter(|max| *max > 1) else {
+            return Ok(());
+        };
+        let hops = input_mints.into_iter().collect::<std::collections::HashSet<_>>().len();
+        if hops > max {
+            return Err(anyhow::anyhow!("route has {} hops, max_route_hops is {}", hops, max));
+        }
+        Ok(())
+    }
+}
 
 /// Get quote for big trade mode (with only_direct_routes and exclude_dexes)
 pub async fn get_quote_big_trade(
     input_amount: u64,
     mother_token: &str,
     arb_token: &str,
+    constraints: &RouteConstraints,
 ) -> Result<(u64, u64, QuoteResponse, QuoteResponse), anyhow::Error> {
+    let only_direct_routes = constraints.only_direct_routes(Some(true));
     let quote_request_1 = QuoteRequest {
         amount: input_amount,
         input_mint: Pubkey::from_str_const(mother_token),
         output_mint: Pubkey::from_str_const(arb_token),
-        only_direct_routes: Some(true),
+        dexes: constraints.dexes().map(Into::into),
+        exclude_dexes: constraints.exclude_dexes(None).map(Into::into),
+        only_direct_routes,
         restrict_intermediate_tokens : Some(true),
         slippage_bps: 0,
         ..QuoteRequest::default()
     };
 
-    let quote_response_1 = JUPITER_CLIENT.quote(&quote_request_1).await?;
+    let quote_response_1 = observe_jupiter("quote", JUPITER_CLIENT.quote(&quote_request_1)).await?;
+    constraints.check_hops(&quote_response_1)?;
 
     let dexes_used_in_req_1 = quote_response_1
         .route_plan
@@ -32,14 +110,16 @@ pub async fn get_quote_big_trade(
         amount: quote_response_1.out_amount,
         input_mint: Pubkey::from_str_const(arb_token),
         output_mint: Pubkey::from_str_const(mother_token),
-        exclude_dexes: Some(dexes_used_in_req_1.clone().into()),
-        only_direct_routes: Some(true),
+        dexes: constraints.dexes().map(Into::into),
+        exclude_dexes: constraints.exclude_dexes(Some(&dexes_used_in_req_1)).map(Into::into),
+        only_direct_routes,
         restrict_intermediate_tokens : Some(true),
         slippage_bps: 0,
         ..QuoteRequest::default()
     };
 
-    let quote_response_2 = JUPITER_CLIENT.quote(&quote_request_2).await?;
+    let quote_response_2 = observe_jupiter("quote", JUPITER_CLIENT.quote(&quote_request_2)).await?;
+    constraints.check_hops(&quote_response_2)?;
 
     Ok((
         input_amount,
@@ -53,12 +133,14 @@ pub async fn get_quote_big_trade(
 /// Based on Jupiter API documentation: https://dev.jup.ag/api-reference/swap/quote
 /// - No only_direct_routes: Allows multi-hop routes for better opportunities (default: false)
 /// - No exclude_dexes: Allows all DEXes to be considered
+/// - The instrument's `constraints` override both
 /// - Uses default restrict_intermediate_tokens (true): Reduces exposure to high slippage routes
 /// - slippage_bps: 0 for precise arbitrage calculations
 pub async fn get_quote_polling(
     input_amount: u64,
     mother_token: &str,
     arb_token: &str,
+    constraints: &RouteConstraints,
 ) -> Result<(u64, u64, QuoteResponse, QuoteResponse), anyhow::Error> {
     // Request 1: mother_token -> arb_token
     // No only_direct_routes: allows multi-hop routes (default: false)
@@ -68,11 +150,15 @@ pub async fn get_quote_polling(
         amount: input_amount,
         input_mint: Pubkey::from_str_const(mother_token),
         output_mint: Pubkey::from_str_const(arb_token),
+        dexes: constraints.dexes().map(Into::into),
+        exclude_dexes: constraints.exclude_dexes(None).map(Into::into),
+        only_direct_routes: constraints.only_direct_routes(None),
         slippage_bps: 0,
         ..QuoteRequest::default()
     };
 
-    let quote_response_1 = JUPITER_CLIENT.quote(&quote_request_1).await?;
+    let quote_response_1 = observe_jupiter("quote", JUPITER_CLIENT.quote(&quote_request_1)).await?;
+    constraints.check_hops(&quote_response_1)?;
 
     // Request 2: arb_token -> mother_token
     // No exclude_dexes: allows all DEXes (no restrictions)
@@ -83,11 +169,15 @@ pub async fn get_quote_polling(
         amount: quote_response_1.out_amount,
         input_mint: Pubkey::from_str_const(arb_token),
         output_mint: Pubkey::from_str_const(mother_token),
+        dexes: constraints.dexes().map(Into::into),
+        exclude_dexes: constraints.exclude_dexes(None).map(Into::into),
+        only_direct_routes: constraints.only_direct_routes(None),
         slippage_bps: 0,
         ..QuoteRequest::default()
     };
 
-    let quote_response_2 = JUPITER_CLIENT.quote(&quote_request_2).await?;
+    let quote_response_2 = observe_jupiter("quote", JUPITER_CLIENT.quote(&quote_request_2)).await?;
+    constraints.check_hops(&quote_response_2)?;
 
     Ok((
         input_amount,
@@ -103,5 +193,83 @@ pub async fn get_quote(
     mother_token: &str,
     arb_token: &str,
 ) -> Result<(u64, u64, QuoteResponse, QuoteResponse), anyhow::Error> {
-    get_quote_big_trade(input_amount, mother_token, arb_token).await
+    get_quote_big_trade(input_amount, mother_token, arb_token, &RouteConstraints::for_instrument(mother_token)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn limited(max_route_hops: Option<usize>, only_direct_routes: Option<bool>) -> RouteConstraints {
+        RouteConstraints { max_route_hops, only_direct_routes, ..RouteConstraints::default() }
+    }
+
+    #[test]
+    fn checks_hops_against_the_limit() {
+        // (max_route_hops, input mints of the plan steps, allowed)
+        let cases: &[(Option<usize>, &[u8], bool)] = &[
+            (None, &[1, 2, 3, 4], true),
+            (Some(1), &[1, 2, 3], true),
+            (Some(2), &[1], true),
+            (Some(2), &[1, 2], true),
+            (Some(2), &[1, 2, 3], false),
+            // Split steps out of the same mint are one hop
+            (Some(2), &[1, 1, 2, 2], true),
+            (Some(2), &[1, 1, 2, 3], false),
+            (Some(3), &[1, 2, 3], true),
+            (Some(3), &[1, 2, 3, 4], false),
+        ];
+        for (max_route_hops, mints, allowed) in cases {
+            let result = limited(*max_route_hops, None).check_route_hops(mints.iter().map(|b| key(*b)));
+            assert_eq!(result.is_ok(), *allowed, "max {:?}, mints {:?}", max_route_hops, mints);
+        }
+    }
+
+    #[test]
+    fn single_hop_limit_requests_direct_routes() {
+        // (max_route_hops, only_direct_routes, mode default, requested)
+        let cases = [
+            (Some(1), None, None, Some(true)),
+            (Some(1), Some(false), Some(false), Some(true)),
+            (Some(2), None, Some(true), Some(true)),
+            (Some(2), Some(false), Some(true), Some(false)),
+            (None, Some(true), None, Some(true)),
+            (None, None, Some(false), Some(false)),
+            (None, None, None, None),
+        ];
+        for (max_route_hops, only_direct_routes, mode_default, requested) in cases {
+            assert_eq!(
+                limited(max_route_hops, only_direct_routes).only_direct_routes(mode_default),
+                requested,
+                "max {:?}, only direct {:?}, default {:?}",
+                max_route_hops,
+                only_direct_routes,
+                mode_default
+            );
+        }
+    }
+
+    #[test]
+    fn joins_dex_lists_for_the_request() {
+        let constraints = RouteConstraints {
+            dexes: vec!["Raydium".to_string(), "Whirlpool".to_string()],
+            exclude_dexes: vec!["Lifinity V2".to_string()],
+            ..RouteConstraints::default()
+        };
+        assert_eq!(constraints.dexes().as_deref(), Some("Raydium,Whirlpool"));
+        assert_eq!(constraints.exclude_dexes(None).as_deref(), Some("Lifinity V2"));
+        assert_eq!(
+            constraints.exclude_dexes(Some("Meteora,,Phoenix")).as_deref(),
+            Some("Lifinity V2,Meteora,Phoenix")
+        );
+
+        let open = RouteConstraints::default();
+        assert_eq!(open.dexes(), None);
+        assert_eq!(open.exclude_dexes(None), None);
+        assert_eq!(open.exclude_dexes(Some("")), None);
+    }
 }
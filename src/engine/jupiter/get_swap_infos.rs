@@ -9,6 +9,13 @@ use solana_sdk::{
 
 use crate::{RouteArgs, RoutePlanStep};
 
+/// Fixed accounts of a non-shared `route` instruction; swap accounts follow them.
+pub const ROUTE_FIXED_ACCOUNTS: usize = 9;
+
+/// Merge the `route` instructions of two legs (mother -> arb, arb -> mother) into one plan.
+///
+/// Step indexes address token ledger slots: leg 1 runs from slot 0 to its last output slot,
+/// and leg 2's slots are shifted past it, so split routes keep their `percent` values.
 pub fn get_swap_infos(
     ix1: SwapInstructionsResponse,
     ix2: SwapInstructionsResponse,
@@ -29,42 +36,29 @@ pub fn get_swap_infos(
         .into_iter()
         .collect();
 
-    println!("{:#?}", token_addresses);
+    for ix in [&ix1, &ix2] {
+        if ix.swap_instruction.data.len() < 8 || ix.swap_instruction.accounts.len() < ROUTE_FIXED_ACCOUNTS {
+            return Err(anyhow::anyhow!(
+                "Unexpected swap instruction shape (data {} bytes, {} accounts); shared accounts must be disabled",
+                ix.swap_instruction.data.len(),
+                ix.swap_instruction.accounts.len()
+            ));
+        }
+    }
 
     // Deserialize route args using Borsh deserialization
     // The instruction data starts with an 8-byte discriminator, followed by the RouteArgs
-    let mut route_1 = RouteArgs::try_from_slice(&ix1.swap_instruction.data[8..])
+    let route_1 = RouteArgs::try_from_slice(&ix1.swap_instruction.data[8..])
         .map_err(|e| anyhow::anyhow!("Failed to deserialize route_1: {}", e))?;
-    let mut route_2 = RouteArgs::try_from_slice(&ix2.swap_instruction.data[8..])
+    let route_2 = RouteArgs::try_from_slice(&ix2.swap_instruction.data[8..])
         .map_err(|e| anyhow::anyhow!("Failed to deserialize route_2: {}", e))?;
 
-    // Combine routes
-    let combined_route: Vec<_> = route_1
-        .route_plan
-        .drain(..)
-        .chain(route_2.route_plan.drain(..))
-        .collect();
-
-    // Build refined plan
-    let refined_plan: Vec<RoutePlanStep> = combined_route
-        .iter()
-        .enumerate()
-        .map(|(idx, ele)| RoutePlanStep {
-            swap: ele.swap.clone(),
-            percent: 100, // adjust if needed
-            input_index: idx as u8,
-            output_index: if idx == combined_route.len() - 1 {
-                0
-            } else {
-                (idx + 1) as u8
-            },
-        })
-        .collect();
+    let refined_plan = merge_route_plans(route_1.route_plan, route_2.route_plan)?;
 
     // Merge remaining accounts
-    let remaining_accounts: Vec<AccountMeta> = ix1.swap_instruction.accounts[9..]
+    let remaining_accounts: Vec<AccountMeta> = ix1.swap_instruction.accounts[ROUTE_FIXED_ACCOUNTS..]
         .iter()
-        .chain(&ix2.swap_instruction.accounts[9..])
+        .chain(&ix2.swap_instruction.accounts[ROUTE_FIXED_ACCOUNTS..])
         .cloned()
         .collect();
 
@@ -84,3 +78,60 @@ pub fn get_swap_infos(
         combined_alt,
     ))
 }
+
+/// Leg 1's steps followed by leg 2's, shifted past the last ledger slot of leg 1. Errors when
+/// a shifted index no longer fits the `u8` slot field.
+pub fn merge_route_plans(
+    leg_1: Vec<RoutePlanStep>,
+    leg_2: Vec<RoutePlanStep>,
+) -> Result<Vec<RoutePlanStep>, anyhow::Error> {
+    let leg_1_last = leg_1.iter().map(|step| step.output_index).max().unwrap_or(0);
+    let shift = |index: u8| {
+        index
+            .checked_add(leg_1_last)
+            .ok_or_else(|| anyhow::anyhow!("Merged route needs more than 256 ledger slots"))
+    };
+
+    let mut plan = leg_1;
+    for step in leg_2 {
+        plan.push(RoutePlanStep {
+            input_index: shift(step.input_index)?,
+            output_index: shift(step.output_index)?,
+            ..step
+        });
+    }
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Swap;
+
+    fn step(input_index: u8, output_index: u8) -> RoutePlanStep {
+        RoutePlanStep {
+            swap: Swap::Raydium,
+            percent: 100,
+            input_index,
+            output_index,
+        }
+    }
+
+    fn indexes(plan: &[RoutePlanStep]) -> Vec<(u8, u8)> {
+        plan.iter().map(|s| (s.input_index, s.output_index)).collect()
+    }
+
+    #[test]
+    fn shifts_second_leg_past_first() {
+        // Leg 1 splits 0 -> 1 and 0 -> 1, then 1 -> 2; leg 2 is 0 -> 1
+        let leg_1 = vec![step(0, 1), step(0, 1), step(1, 2)];
+        let plan = merge_route_plans(leg_1, vec![step(0, 1)]).unwrap();
+        assert_eq!(indexes(&plan), vec![(0, 1), (0, 1), (1, 2), (2, 3)]);
+    }
+
+    #[test]
+    fn rejects_index_overflow() {
+        assert!(merge_route_plans(vec![step(0, 200)], vec![step(0, 100)]).is_err());
+        assert!(merge_route_plans(vec![step(0, 255)], vec![step(0, 0)]).is_ok());
+    }
+}
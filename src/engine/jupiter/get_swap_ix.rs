@@ -4,6 +4,8 @@ use jupiter_swap_api_client::{
     transaction_config::{ComputeUnitPriceMicroLamports, TransactionConfig},
 };
 
+use solana_sdk::pubkey::Pubkey;
+
 use crate::*;
 
 fn rpc_transaction_config() -> TransactionConfig {
@@ -22,6 +24,7 @@ pub async fn get_swap_tx(
     quote_response_1: QuoteResponse,
     quote_response_2: QuoteResponse,
     min_profit_amount: u64,
+    user: &Pubkey,
 ) -> Result<SwapResponse, anyhow::Error> {
     let mut combined_route_plan = Vec::new();
     combined_route_plan.extend(quote_response_1.clone().route_plan);
@@ -45,18 +48,21 @@ pub async fn get_swap_tx(
             time_taken: quote_response_2.time_taken,
         },
         config: rpc_transaction_config(),
-        user_public_key: PUBKEY.clone(),
+        user_public_key: *user,
     };
 
-    let swap_tx = JUPITER_CLIENT.swap(&combined_request, None).await.unwrap();
+    let swap_tx = observe_jupiter("swap", JUPITER_CLIENT.swap(&combined_request, None)).await?;
 
     Ok(swap_tx)
 }
 
+/// Swap instructions for wallet `user`. `wrap_and_unwrap_sol` is off for flash-funded trades, where the WSOL comes from the loan.
 pub async fn get_swap_ix(
     quote_response_1: QuoteResponse,
     quote_response_2: QuoteResponse,
     min_profit_amount: u64,
+    wrap_and_unwrap_sol: bool,
+    user: &Pubkey,
 ) -> Result<SwapInstructionsResponse, anyhow::Error> {
     let mut combined_route_plan = Vec::new();
     combined_route_plan.extend(quote_response_1.clone().route_plan);
@@ -81,17 +87,14 @@ pub async fn get_swap_ix(
         },
         config: TransactionConfig {
             use_shared_accounts: Some(false),
-            wrap_and_unwrap_sol: true,
+            wrap_and_unwrap_sol,
             skip_user_accounts_rpc_calls: true,
             ..Default::default()
         },
-        user_public_key: PUBKEY.clone(),
+        user_public_key: *user,
     };
 
-    let swap_ix = JUPITER_CLIENT
-        .swap_instructions(&combined_request)
-        .await
-        .unwrap();
+    let swap_ix = observe_jupiter("swap_instructions", JUPITER_CLIENT.swap_instructions(&combined_request)).await?;
 
     Ok(swap_ix)
 }
@@ -1,12 +1,16 @@
 pub mod build_swap_ix;
 pub mod estimate_timing;
+pub mod get_merged_route_ix;
 pub mod get_quote;
 pub mod get_swap_infos;
 pub mod get_swap_ix;
 pub mod ix_data;
+pub mod venue_policy;
 pub use build_swap_ix::*;
 pub use estimate_timing::*;
+pub use get_merged_route_ix::*;
 pub use get_quote::*;
 pub use get_swap_infos::*;
 pub use get_swap_ix::*;
 pub use ix_data::*;
+pub use venue_policy::*;
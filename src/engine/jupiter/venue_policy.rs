@@ -0,0 +1,223 @@
+//! Venue policy: DEX labels excluded from every quote. Combines the configured denylist with
+//! labels blacklisted for a while after repeated failures of routes through them (failed
+//! preflight simulations, or trades that landed without profit). The blacklist is served on
+//! the control API as `GET /venues`.
+
+use axum::{routing::get, Json, Router};
+use jupiter_swap_api_client::quote::QuoteResponse;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::app::config::VenueConfig;
+use crate::CONFIG;
+
+#[derive(Debug, Default)]
+struct VenueState {
+    /// Recent failure times, oldest first, within the failure window.
+    failures: VecDeque<Instant>,
+    blacklisted_until: Option<Instant>,
+}
+
+static VENUES: Lazy<Mutex<HashMap<String, VenueState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// A DEX label currently excluded after repeated failures.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BlacklistedVenue {
+    pub label: String,
+    pub remaining_secs: u64,
+}
+
+/// Distinct DEX labels the quotes route through.
+pub fn route_venues(quotes: &[&QuoteResponse]) -> Vec<String> {
+    let mut labels: Vec<String> = Vec::new();
+    for quote in quotes {
+        for step in &quote.route_plan {
+            if !labels.contains(&step.swap_info.label) {
+                labels.push(step.swap_info.label.clone());
+            }
+        }
+    }
+    labels
+}
+
+/// Pool accounts of every route step of `quotes`, deduplicated.
+pub fn route_pools(quotes: &[&QuoteResponse]) -> Vec<String> {
+    let mut pools: Vec<String> = Vec::new();
+    for quote in quotes {
+        for step in &quote.route_plan {
+            let pool = step.swap_info.amm_key.to_string();
+            if !pools.contains(&pool) {
+                pools.push(pool);
+            }
+        }
+    }
+    pools
+}
+
+/// Count a failure against each venue; blacklists those reaching `blacklist_after_failures`
+/// within the failure window.
+pub fn record_venue_failure(labels: &[String], reason: &str) {
+    note_failures(&mut VENUES.lock().unwrap(), labels, reason, &CONFIG.venues, Instant::now());
+}
+
+fn note_failures(
+    venues: &mut HashMap<String, VenueState>,
+    labels: &[String],
+    reason: &str,
+    policy: &VenueConfig,
+    now: Instant,
+) {
+    if policy.blacklist_after_failures == 0 {
+        return;
+    }
+    let window = Duration::from_secs(policy.failure_window_secs);
+    for label in labels {
+        let state = venues.entry(label.clone()).or_default();
+        while state.failures.front().is_some_and(|t| now.duration_since(*t) > window) {
+            state.failures.pop_front();
+        }
+        state.failures.push_back(now);
+        let failures = state.failures.len();
+        if failures >= policy.blacklist_after_failures as usize {
+            state.failures.clear();
+            state.blacklisted_until = Some(now + Duration::from_secs(policy.blacklist_secs));
+            warn!(venue = %label, failures, reason, secs = policy.blacklist_secs, "Venue blacklisted");
+        }
+    }
+}
+
+/// A profitable landing clears the venues' failure counts.
+pub fn record_venue_success(labels: &[String]) {
+    let mut venues = VENUES.lock().unwrap();
+    for label in labels {
+        if let Some(state) = venues.get_mut(label) {
+            state.failures.clear();
+        }
+    }
+}
+
+/// Venues blacklisted right now; expired entries are dropped (and logged) here.
+pub fn venue_blacklist() -> Vec<BlacklistedVenue> {
+    active_blacklist(&mut VENUES.lock().unwrap(), Instant::now())
+}
+
+fn active_blacklist(venues: &mut HashMap<String, VenueState>, now: Instant) -> Vec<BlacklistedVenue> {
+    let mut active = Vec::new();
+    for (label, state) in venues.iter_mut() {
+        match state.blacklisted_until {
+            Some(until) if until > now => active.push(BlacklistedVenue {
+                label: label.clone(),
+                remaining_secs: (until - now).as_secs(),
+            }),
+            Some(_) => {
+                state.blacklisted_until = None;
+                info!(venue = %label, "Venue blacklist expired");
+            }
+            None => {}
+        }
+    }
+    active
+}
+
+/// Labels to add to every quote's `excludeDexes`: the configured denylist and the blacklist.
+pub fn excluded_venues() -> Vec<String> {
+    merge_labels(CONFIG.venues.deny_dexes.clone(), venue_blacklist().into_iter().map(|v| v.label))
+}
+
+/// `labels` followed by those of `extra` not already listed.
+pub fn merge_labels(mut labels: Vec<String>, extra: impl IntoIterator<Item = String>) -> Vec<String> {
+    for label in extra {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    labels
+}
+
+/// Control API routes of the venue policy: `GET /venues` lists the current blacklist.
+pub fn venue_routes() -> Router {
+    Router::new().route("/venues", get(|| async { Json(venue_blacklist()) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(blacklist_after_failures: u32) -> VenueConfig {
+        VenueConfig {
+            blacklist_after_failures,
+            failure_window_secs: 60,
+            blacklist_secs: 300,
+            ..VenueConfig::default()
+        }
+    }
+
+    fn labels(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    fn blacklisted(venues: &mut HashMap<String, VenueState>, now: Instant) -> Vec<String> {
+        let mut names: Vec<String> = active_blacklist(venues, now).into_iter().map(|v| v.label).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn blacklists_at_the_failure_threshold() {
+        let mut venues = HashMap::new();
+        let now = Instant::now();
+        note_failures(&mut venues, &labels(&["Raydium", "Whirlpool"]), "preflight", &policy(3), now);
+        note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(3), now + Duration::from_secs(1));
+        assert!(blacklisted(&mut venues, now + Duration::from_secs(1)).is_empty());
+
+        note_failures(&mut venues, &labels(&["Raydium"]), "unprofitable", &policy(3), now + Duration::from_secs(2));
+        let active = active_blacklist(&mut venues, now + Duration::from_secs(2));
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].label, "Raydium");
+        assert_eq!(active[0].remaining_secs, 300);
+    }
+
+    #[test]
+    fn failures_outside_the_window_do_not_count() {
+        let mut venues = HashMap::new();
+        let now = Instant::now();
+        note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(2), now);
+        note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(2), now + Duration::from_secs(61));
+        assert!(blacklisted(&mut venues, now + Duration::from_secs(61)).is_empty());
+
+        note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(2), now + Duration::from_secs(62));
+        assert_eq!(blacklisted(&mut venues, now + Duration::from_secs(62)), labels(&["Raydium"]));
+    }
+
+    #[test]
+    fn zero_threshold_never_blacklists() {
+        let mut venues = HashMap::new();
+        let now = Instant::now();
+        for _ in 0..10 {
+            note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(0), now);
+        }
+        assert!(venues.is_empty());
+    }
+
+    #[test]
+    fn blacklist_expires() {
+        let mut venues = HashMap::new();
+        let now = Instant::now();
+        note_failures(&mut venues, &labels(&["Raydium"]), "preflight", &policy(1), now);
+        assert_eq!(blacklisted(&mut venues, now + Duration::from_secs(299)), labels(&["Raydium"]));
+        assert!(blacklisted(&mut venues, now + Duration::from_secs(300)).is_empty());
+        assert!(venues["Raydium"].blacklisted_until.is_none());
+        // The failure count restarted when it was blacklisted
+        assert!(venues["Raydium"].failures.is_empty());
+    }
+
+    #[test]
+    fn merges_exclusions_without_duplicates() {
+        let merged = merge_labels(labels(&["Lifinity V2", "Raydium"]), labels(&["Raydium", "Whirlpool"]));
+        assert_eq!(merged, labels(&["Lifinity V2", "Raydium", "Whirlpool"]));
+        assert_eq!(merge_labels(Vec::new(), labels(&["Raydium"])), labels(&["Raydium"]));
+    }
+}
@@ -0,0 +1,9 @@
+//! Local market state: pool accounts mirrored from Geyser and decoded per DEX program.
+
+pub mod pool_cache;
+pub mod pool_state;
+pub mod subscribe;
+
+pub use pool_cache::*;
+pub use pool_state::*;
+pub use subscribe::*;
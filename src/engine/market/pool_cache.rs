@@ -0,0 +1,289 @@
+//! Slot-tagged in-memory cache of pool state mirrored from Geyser account updates.
+//!
+//! Every cached value remembers the slot at which it was last written. While the Geyser
+//! stream is live an unchanged account is still current, so a pool's state is known to hold
+//! from its last change up to the newest slot seen on the stream. When the stream drops, all
+//! entries are marked stale until they are reloaded.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{CONFIG, MARKET_POOLS};
+
+use super::pool_state::{decode_cpmm_trade_fee_rate, decode_token_account_amount, PoolState};
+
+/// Approximate slot time, used to turn `max_staleness_slots` into a wall-clock bound.
+const SLOT_MS: u64 = 400;
+
+#[derive(Debug, Clone)]
+struct Tagged<T> {
+    value: T,
+    slot: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccountRole {
+    VaultA,
+    VaultB,
+    CpmmConfig,
+}
+
+#[derive(Debug)]
+struct PoolEntry {
+    state: Tagged<PoolState>,
+    vault_a: Option<Tagged<u64>>,
+    vault_b: Option<Tagged<u64>>,
+    fee_rate: Option<Tagged<u64>>,
+    live: bool,
+}
+
+#[derive(Debug, Default)]
+struct PoolCache {
+    pools: HashMap<Pubkey, PoolEntry>,
+    dependents: HashMap<Pubkey, Vec<(Pubkey, AccountRole)>>,
+    latest_slot: u64,
+    latest_slot_at: Option<Instant>,
+}
+
+static POOL_CACHE: Lazy<RwLock<PoolCache>> = Lazy::new(|| RwLock::new(PoolCache::default()));
+
+/// Point-in-time view of one pool, as returned by the query API.
+#[derive(Debug, Clone)]
+pub struct PoolSnapshot {
+    pub address: Pubkey,
+    pub state: PoolState,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Tradable reserves in raw units (vault balances minus fees owed to the protocol).
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    /// Input fee as (numerator, denominator).
+    pub fee: (u64, u64),
+    /// Price of token A in token B, raw units (B per A).
+    pub price_a_in_b: f64,
+    /// Newest slot at which any part of the snapshot changed.
+    pub data_slot: u64,
+    /// Slot through which the snapshot is known to hold.
+    pub valid_through_slot: u64,
+    pub stale: bool,
+}
+
+impl PoolSnapshot {
+    /// Reserves and fee oriented for a swap from `input_mint`: (reserve_in, reserve_out).
+    pub fn reserves_for_input(&self, input_mint: &Pubkey) -> Option<(u64, u64)> {
+        if *input_mint == self.mint_a {
+            Some((self.reserve_a, self.reserve_b))
+        } else if *input_mint == self.mint_b {
+            Some((self.reserve_b, self.reserve_a))
+        } else {
+            None
+        }
+    }
+}
+
+/// Apply a raw account write. Returns true when the account belongs to a tracked pool.
+pub fn apply_account(pubkey: &Pubkey, owner: &Pubkey, data: &[u8], slot: u64) -> bool {
+    let mut cache = POOL_CACHE.write().unwrap();
+    cache.observe_slot(slot);
+
+    if MARKET_POOLS.contains(pubkey) {
+        let Some(state) = PoolState::decode(owner, data) else {
+            return true;
+        };
+        cache.set_pool_state(*pubkey, state, slot);
+        return true;
+    }
+
+    let Some(roles) = cache.dependents.get(pubkey).cloned() else {
+        return false;
+    };
+    for (pool, role) in roles {
+        let value = match role {
+            AccountRole::VaultA | AccountRole::VaultB => decode_token_account_amount(data),
+            AccountRole::CpmmConfig => decode_cpmm_trade_fee_rate(data),
+        };
+        let (Some(value), Some(entry)) = (value, cache.pools.get_mut(&pool)) else {
+            continue;
+        };
+        let field = match role {
+            AccountRole::VaultA => &mut entry.vault_a,
+            AccountRole::VaultB => &mut entry.vault_b,
+            AccountRole::CpmmConfig => &mut entry.fee_rate,
+        };
+        if field.as_ref().map_or(true, |t| t.slot <= slot) {
+            *field = Some(Tagged { value, slot });
+        }
+    }
+    true
+}
+
+/// Record a slot seen on the stream (slot updates, or the slot of any account/tx update).
+pub fn note_slot(slot: u64) {
+    POOL_CACHE.write().unwrap().observe_slot(slot);
+}
+
+/// Newest slot seen on the stream.
+pub fn latest_slot() -> u64 {
+    POOL_CACHE.read().unwrap().latest_slot
+}
+
+/// Mark every cached pool as live (stream subscribed) or stale (stream down).
+pub fn set_pool_cache_live(live: bool) {
+    let mut cache = POOL_CACHE.write().unwrap();
+    for entry in cache.pools.values_mut() {
+        entry.live = live;
+    }
+}
+
+/// All accounts that must be subscribed: configured pools plus their vaults and configs.
+pub fn tracked_pool_accounts() -> Vec<Pubkey> {
+    let cache = POOL_CACHE.read().unwrap();
+    let mut accounts: Vec<Pubkey> = MARKET_POOLS.clone();
+    accounts.extend(cache.dependents.keys().copied());
+    accounts.sort();
+    accounts.dedup();
+    accounts
+}
+
+/// Current state of `pool`, or None if it is unknown or not fully loaded yet.
+pub fn pool_snapshot(pool: &Pubkey) -> Option<PoolSnapshot> {
+    let cache = POOL_CACHE.read().unwrap();
+    cache.snapshot(pool)
+}
+
+/// State of `pool` as of `slot`: only returned when the cached state is known to hold at
+/// that slot (it last changed at or before `slot` and has been confirmed through it).
+pub fn pool_snapshot_at(pool: &Pubkey, slot: u64) -> Option<PoolSnapshot> {
+    pool_snapshot(pool).filter(|s| s.data_slot <= slot && slot <= s.valid_through_slot)
+}
+
+/// Fully loaded pools trading `mint_x` against `mint_y` (either orientation).
+pub fn pools_for_pair(mint_x: &Pubkey, mint_y: &Pubkey) -> Vec<PoolSnapshot> {
+    let cache = POOL_CACHE.read().unwrap();
+    cache
+        .pools
+        .iter()
+        .filter(|(_, entry)| {
+            let (a, b) = entry.state.value.mints();
+            (a == *mint_x && b == *mint_y) || (a == *mint_y && b == *mint_x)
+        })
+        .filter_map(|(addr, _)| cache.snapshot(addr))
+        .collect()
+}
+
+/// Fully loaded pools with `mint` on either side.
+pub fn pools_with_mint(mint: &Pubkey) -> Vec<PoolSnapshot> {
+    let cache = POOL_CACHE.read().unwrap();
+    cache
+        .pools
+        .iter()
+        .filter(|(_, entry)| {
+            let (a, b) = entry.state.value.mints();
+            a == *mint || b == *mint
+        })
+        .filter_map(|(addr, _)| cache.snapshot(addr))
+        .collect()
+}
+
+impl PoolCache {
+    fn observe_slot(&mut self, slot: u64) {
+        if slot >= self.latest_slot {
+            self.latest_slot = slot;
+            self.latest_slot_at = Some(Instant::now());
+        }
+    }
+
+    fn set_pool_state(&mut self, pool: Pubkey, state: PoolState, slot: u64) {
+        let (vault_a, vault_b) = state.vaults();
+        let mut roles = vec![(vault_a, AccountRole::VaultA), (vault_b, AccountRole::VaultB)];
+        roles.extend(
+            state
+                .dependent_accounts()
+                .into_iter()
+                .map(|acc| (acc, AccountRole::CpmmConfig)),
+        );
+        for (account, role) in roles {
+            let entry = self.dependents.entry(account).or_default();
+            if !entry.contains(&(pool, role)) {
+                entry.push((pool, role));
+            }
+        }
+
+        match self.pools.get_mut(&pool) {
+            Some(entry) if entry.state.slot > slot => {}
+            Some(entry) => entry.state = Tagged { value: state, slot },
+            None => {
+                self.pools.insert(
+                    pool,
+                    PoolEntry {
+                        state: Tagged { value: state, slot },
+                        vault_a: None,
+                        vault_b: None,
+                        fee_rate: None,
+                        live: false,
+                    },
+                );
+            }
+        }
+    }
+
+    fn stream_silent(&self) -> bool {
+        let max_silence =
+            Duration::from_millis(CONFIG.markets.max_staleness_slots.saturating_mul(SLOT_MS));
+        self.latest_slot_at
+            .map_or(true, |at| at.elapsed() > max_silence)
+    }
+
+    fn snapshot(&self, pool: &Pubkey) -> Option<PoolSnapshot> {
+        let entry = self.pools.get(pool)?;
+        let state = &entry.state.value;
+        let vault_a = entry.vault_a.as_ref()?;
+        let vault_b = entry.vault_b.as_ref()?;
+        let (mint_a, mint_b) = state.mints();
+        let (reserve_a, reserve_b) = state.reserves(vault_a.value, vault_b.value);
+
+        let mut data_slot = entry.state.slot.max(vault_a.slot).max(vault_b.slot);
+        let fee = match state {
+            PoolState::RaydiumAmm(p) => (p.swap_fee_numerator, p.swap_fee_denominator),
+            PoolState::RaydiumCpmm(_) => {
+                let fee_rate = entry.fee_rate.as_ref()?;
+                data_slot = data_slot.max(fee_rate.slot);
+                (fee_rate.value, 1_000_000)
+            }
+            PoolState::Whirlpool(p) => (p.fee_rate as u64, 1_000_000),
+        };
+
+        let price_a_in_b = match state {
+            PoolState::Whirlpool(p) => {
+                let sqrt = p.sqrt_price as f64 / 2_f64.powi(64);
+                sqrt * sqrt
+            }
+            _ if reserve_a > 0 => reserve_b as f64 / reserve_a as f64,
+            _ => 0.0,
+        };
+
+        let valid_through_slot = if entry.live {
+            self.latest_slot.max(data_slot)
+        } else {
+            data_slot
+        };
+
+        Some(PoolSnapshot {
+            address: *pool,
+            state: state.clone(),
+            mint_a,
+            mint_b,
+            reserve_a,
+            reserve_b,
+            fee,
+            price_a_in_b,
+            data_slot,
+            valid_through_slot,
+            stale: !entry.live || self.stream_silent(),
+        })
+    }
+}
@@ -0,0 +1,333 @@
+//! Per-program decoders for pool, vault and config accounts.
+//!
+//! Only the fields needed for quoting and building swaps are read; offsets follow the
+//! published account layouts of each program.
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{RAYDIUM_AMM_PROGRAM, RAYDIUM_CPMM_PROGRAM, WHIRLPOOL_PROGRAM};
+
+/// Raydium AMM v4 (`AmmInfo`) state.
+#[derive(Debug, Clone)]
+pub struct RaydiumAmmPool {
+    pub status: u64,
+    pub base_need_take_pnl: u64,
+    pub quote_need_take_pnl: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub open_orders: Pubkey,
+}
+
+/// Raydium CPMM (`PoolState`) state. The trade fee lives in the separate `amm_config` account.
+#[derive(Debug, Clone)]
+pub struct RaydiumCpmmPool {
+    pub amm_config: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_program: Pubkey,
+    pub token_1_program: Pubkey,
+    pub observation_key: Pubkey,
+    pub status: u8,
+    pub protocol_fees_token_0: u64,
+    pub protocol_fees_token_1: u64,
+    pub fund_fees_token_0: u64,
+    pub fund_fees_token_1: u64,
+}
+
+/// Orca Whirlpool state (concentrated liquidity; price comes from `sqrt_price`).
+#[derive(Debug, Clone)]
+pub struct WhirlpoolPool {
+    pub tick_spacing: u16,
+    pub fee_rate: u16,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    pub token_mint_a: Pubkey,
+    pub token_vault_a: Pubkey,
+    pub token_mint_b: Pubkey,
+    pub token_vault_b: Pubkey,
+}
+
+#[derive(Debug, Clone)]
+pub enum PoolState {
+    RaydiumAmm(RaydiumAmmPool),
+    RaydiumCpmm(RaydiumCpmmPool),
+    Whirlpool(WhirlpoolPool),
+}
+
+impl PoolState {
+    /// Decode a pool account, choosing the layout from its owner program.
+    pub fn decode(owner: &Pubkey, data: &[u8]) -> Option<Self> {
+        if *owner == RAYDIUM_AMM_PROGRAM {
+            decode_raydium_amm(data).map(PoolState::RaydiumAmm)
+        } else if *owner == RAYDIUM_CPMM_PROGRAM {
+            decode_raydium_cpmm(data).map(PoolState::RaydiumCpmm)
+        } else if *owner == WHIRLPOOL_PROGRAM {
+            decode_whirlpool(data).map(PoolState::Whirlpool)
+        } else {
+            None
+        }
+    }
+
+    pub fn dex(&self) -> &'static str {
+        match self {
+            PoolState::RaydiumAmm(_) => "Raydium",
+            PoolState::RaydiumCpmm(_) => "Raydium CP",
+            PoolState::Whirlpool(_) => "Whirlpool",
+        }
+    }
+
+    /// (mint_a, mint_b) in the pool's own ordering.
+    pub fn mints(&self) -> (Pubkey, Pubkey) {
+        match self {
+            PoolState::RaydiumAmm(p) => (p.base_mint, p.quote_mint),
+            PoolState::RaydiumCpmm(p) => (p.token_0_mint, p.token_1_mint),
+            PoolState::Whirlpool(p) => (p.token_mint_a, p.token_mint_b),
+        }
+    }
+
+    /// (vault_a, vault_b) matching the order of `mints()`.
+    pub fn vaults(&self) -> (Pubkey, Pubkey) {
+        match self {
+            PoolState::RaydiumAmm(p) => (p.base_vault, p.quote_vault),
+            PoolState::RaydiumCpmm(p) => (p.token_0_vault, p.token_1_vault),
+            PoolState::Whirlpool(p) => (p.token_vault_a, p.token_vault_b),
+        }
+    }
+
+    /// Extra accounts (besides vaults) the pool needs mirrored, e.g. the CPMM fee config.
+    pub fn dependent_accounts(&self) -> Vec<Pubkey> {
+        match self {
+            PoolState::RaydiumCpmm(p) => vec![p.amm_config],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Tradable reserves given raw vault balances (vault balances minus fees owed to the protocol).
+    pub fn reserves(&self, vault_a: u64, vault_b: u64) -> (u64, u64) {
+        match self {
+            PoolState::RaydiumAmm(p) => (
+                vault_a.saturating_sub(p.base_need_take_pnl),
+                vault_b.saturating_sub(p.quote_need_take_pnl),
+            ),
+            PoolState::RaydiumCpmm(p) => (
+                vault_a
+                    .saturating_sub(p.protocol_fees_token_0)
+                    .saturating_sub(p.fund_fees_token_0),
+                vault_b
+                    .saturating_sub(p.protocol_fees_token_1)
+                    .saturating_sub(p.fund_fees_token_1),
+            ),
+            PoolState::Whirlpool(_) => (vault_a, vault_b),
+        }
+    }
+}
+
+/// Raydium CPMM `AmmConfig.trade_fee_rate` (denominator 1_000_000).
+pub fn decode_cpmm_trade_fee_rate(data: &[u8]) -> Option<u64> {
+    read_u64(data, 12)
+}
+
+/// `amount` field of an SPL Token / Token-2022 token account.
+pub fn decode_token_account_amount(data: &[u8]) -> Option<u64> {
+    if data.len() < 165 {
+        return None;
+    }
+    read_u64(data, 64)
+}
+
+fn decode_raydium_amm(data: &[u8]) -> Option<RaydiumAmmPool> {
+    if data.len() != 752 {
+        return None;
+    }
+    Some(RaydiumAmmPool {
+        status: read_u64(data, 0)?,
+        swap_fee_numerator: read_u64(data, 176)?,
+        swap_fee_denominator: read_u64(data, 184)?,
+        base_need_take_pnl: read_u64(data, 192)?,
+        quote_need_take_pnl: read_u64(data, 200)?,
+        base_vault: read_pubkey(data, 336)?,
+        quote_vault: read_pubkey(data, 368)?,
+        base_mint: read_pubkey(data, 400)?,
+        quote_mint: read_pubkey(data, 432)?,
+        open_orders: read_pubkey(data, 496)?,
+    })
+}
+
+fn decode_raydium_cpmm(data: &[u8]) -> Option<RaydiumCpmmPool> {
+    if data.len() < 381 {
+        return None;
+    }
+    Some(RaydiumCpmmPool {
+        amm_config: read_pubkey(data, 8)?,
+        token_0_vault: read_pubkey(data, 72)?,
+        token_1_vault: read_pubkey(data, 104)?,
+        token_0_mint: read_pubkey(data, 168)?,
+        token_1_mint: read_pubkey(data, 200)?,
+        token_0_program: read_pubkey(data, 232)?,
+        token_1_program: read_pubkey(data, 264)?,
+        observation_key: read_pubkey(data, 296)?,
+        status: *data.get(329)?,
+        protocol_fees_token_0: read_u64(data, 341)?,
+        protocol_fees_token_1: read_u64(data, 349)?,
+        fund_fees_token_0: read_u64(data, 357)?,
+        fund_fees_token_1: read_u64(data, 365)?,
+    })
+}
+
+fn decode_whirlpool(data: &[u8]) -> Option<WhirlpoolPool> {
+    if data.len() < 245 {
+        return None;
+    }
+    Some(WhirlpoolPool {
+        tick_spacing: read_u16(data, 41)?,
+        fee_rate: read_u16(data, 45)?,
+        liquidity: read_u128(data, 49)?,
+        sqrt_price: read_u128(data, 65)?,
+        tick_current_index: read_u32(data, 81)? as i32,
+        token_mint_a: read_pubkey(data, 101)?,
+        token_vault_a: read_pubkey(data, 133)?,
+        token_mint_b: read_pubkey(data, 181)?,
+        token_vault_b: read_pubkey(data, 213)?,
+    })
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let bytes: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    Some(Pubkey::new_from_array(bytes))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Option<u128> {
+    Some(u128::from_le_bytes(data.get(offset..offset + 16)?.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(byte: u8) -> Pubkey {
+        Pubkey::new_from_array([byte; 32])
+    }
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn decodes_raydium_amm() {
+        let mut data = vec![0u8; 752];
+        put(&mut data, 0, &6u64.to_le_bytes());
+        put(&mut data, 176, &25u64.to_le_bytes());
+        put(&mut data, 184, &10_000u64.to_le_bytes());
+        put(&mut data, 192, &11u64.to_le_bytes());
+        put(&mut data, 200, &12u64.to_le_bytes());
+        put(&mut data, 336, key(1).as_ref());
+        put(&mut data, 368, key(2).as_ref());
+        put(&mut data, 400, key(3).as_ref());
+        put(&mut data, 432, key(4).as_ref());
+        put(&mut data, 496, key(5).as_ref());
+
+        let Some(PoolState::RaydiumAmm(pool)) = PoolState::decode(&RAYDIUM_AMM_PROGRAM, &data) else {
+            panic!("not decoded as Raydium AMM");
+        };
+        assert_eq!(pool.status, 6);
+        assert_eq!((pool.swap_fee_numerator, pool.swap_fee_denominator), (25, 10_000));
+        assert_eq!((pool.base_need_take_pnl, pool.quote_need_take_pnl), (11, 12));
+        assert_eq!((pool.base_vault, pool.quote_vault), (key(1), key(2)));
+        assert_eq!((pool.base_mint, pool.quote_mint), (key(3), key(4)));
+        assert_eq!(pool.open_orders, key(5));
+
+        let state = PoolState::RaydiumAmm(pool);
+        assert_eq!(state.reserves(100, 100), (89, 88));
+        assert!(PoolState::decode(&RAYDIUM_AMM_PROGRAM, &data[..751]).is_none());
+    }
+
+    #[test]
+    fn decodes_raydium_cpmm() {
+        let mut data = vec![0u8; 637];
+        put(&mut data, 8, key(1).as_ref());
+        put(&mut data, 72, key(2).as_ref());
+        put(&mut data, 104, key(3).as_ref());
+        put(&mut data, 168, key(4).as_ref());
+        put(&mut data, 200, key(5).as_ref());
+        put(&mut data, 232, key(6).as_ref());
+        put(&mut data, 264, key(7).as_ref());
+        put(&mut data, 296, key(8).as_ref());
+        data[329] = 4;
+        put(&mut data, 341, &1u64.to_le_bytes());
+        put(&mut data, 349, &2u64.to_le_bytes());
+        put(&mut data, 357, &3u64.to_le_bytes());
+        put(&mut data, 365, &4u64.to_le_bytes());
+
+        let Some(PoolState::RaydiumCpmm(pool)) = PoolState::decode(&RAYDIUM_CPMM_PROGRAM, &data) else {
+            panic!("not decoded as Raydium CPMM");
+        };
+        assert_eq!(pool.amm_config, key(1));
+        assert_eq!((pool.token_0_vault, pool.token_1_vault), (key(2), key(3)));
+        assert_eq!((pool.token_0_mint, pool.token_1_mint), (key(4), key(5)));
+        assert_eq!((pool.token_0_program, pool.token_1_program), (key(6), key(7)));
+        assert_eq!(pool.observation_key, key(8));
+        assert_eq!(pool.status, 4);
+
+        let state = PoolState::RaydiumCpmm(pool);
+        assert_eq!(state.dependent_accounts(), vec![key(1)]);
+        assert_eq!(state.reserves(100, 100), (96, 94));
+        assert!(PoolState::decode(&RAYDIUM_CPMM_PROGRAM, &data[..380]).is_none());
+    }
+
+    #[test]
+    fn decodes_whirlpool() {
+        let mut data = vec![0u8; 653];
+        put(&mut data, 41, &64u16.to_le_bytes());
+        put(&mut data, 45, &3_000u16.to_le_bytes());
+        put(&mut data, 49, &5_000_000u128.to_le_bytes());
+        put(&mut data, 65, &(1u128 << 64).to_le_bytes());
+        put(&mut data, 81, &(-120i32).to_le_bytes());
+        put(&mut data, 101, key(1).as_ref());
+        put(&mut data, 133, key(2).as_ref());
+        put(&mut data, 181, key(3).as_ref());
+        put(&mut data, 213, key(4).as_ref());
+
+        let Some(PoolState::Whirlpool(pool)) = PoolState::decode(&WHIRLPOOL_PROGRAM, &data) else {
+            panic!("not decoded as Whirlpool");
+        };
+        assert_eq!((pool.tick_spacing, pool.fee_rate), (64, 3_000));
+        assert_eq!(pool.liquidity, 5_000_000);
+        assert_eq!(pool.sqrt_price, 1u128 << 64);
+        assert_eq!(pool.tick_current_index, -120);
+        assert_eq!((pool.token_mint_a, pool.token_vault_a), (key(1), key(2)));
+        assert_eq!((pool.token_mint_b, pool.token_vault_b), (key(3), key(4)));
+        assert!(PoolState::decode(&WHIRLPOOL_PROGRAM, &data[..244]).is_none());
+    }
+
+    #[test]
+    fn ignores_unknown_owner() {
+        assert!(PoolState::decode(&key(9), &[0u8; 752]).is_none());
+    }
+
+    #[test]
+    fn reads_token_account_amount() {
+        let mut data = vec![0u8; 165];
+        put(&mut data, 64, &42u64.to_le_bytes());
+        assert_eq!(decode_token_account_amount(&data), Some(42));
+        assert_eq!(decode_token_account_amount(&data[..164]), None);
+    }
+}
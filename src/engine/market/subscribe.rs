@@ -0,0 +1,124 @@
+//! Geyser subscription plumbing for the pool state cache: RPC bootstrap, account filters and
+//! routing of account/slot updates into the cache.
+
+use std::collections::HashMap;
+
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use tracing::{info, warn};
+use yellowstone_grpc_proto::geyser::{
+    subscribe_update, SubscribeRequestFilterAccounts, SubscribeRequestFilterSlots,
+};
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+use crate::{filter_tradable_mints, MARKET_POOLS, RPC_CLIENT};
+
+use super::pool_cache::{apply_account, note_slot, pool_snapshot, tracked_pool_accounts};
+
+/// Max accounts per `getMultipleAccounts` call.
+const RPC_BATCH: usize = 100;
+
+/// Load configured pools and their vault/config accounts over RPC. Run after subscribing so
+/// that no write between the RPC read and the first stream update is missed.
+pub async fn bootstrap_pool_cache() -> Result<(), anyhow::Error> {
+    if MARKET_POOLS.is_empty() {
+        return Ok(());
+    }
+
+    // First pass loads the pools (which reveals vaults/configs), second pass loads those.
+    load_accounts(&MARKET_POOLS).await?;
+    let dependents: Vec<Pubkey> = tracked_pool_accounts()
+        .into_iter()
+        .filter(|acc| !MARKET_POOLS.contains(acc))
+        .collect();
+    load_accounts(&dependents).await?;
+
+    // Resolve pool mints in the token registry so direct execution can vet them
+    let mints: Vec<String> = MARKET_POOLS
+        .iter()
+        .filter_map(pool_snapshot)
+        .flat_map(|pool| [pool.mint_a.to_string(), pool.mint_b.to_string()])
+        .collect::<std::collections::HashSet<_>>()
+        .into_iter()
+        .collect();
+    filter_tradable_mints(mints).await;
+
+    info!(
+        pools = MARKET_POOLS.len(),
+        accounts = dependents.len(),
+        "Pool state cache loaded"
+    );
+    Ok(())
+}
+
+async fn load_accounts(accounts: &[Pubkey]) -> Result<(), anyhow::Error> {
+    for chunk in accounts.chunks(RPC_BATCH) {
+        let response = RPC_CLIENT
+            .get_multiple_accounts_with_commitment(chunk, CommitmentConfig::processed())
+            .await?;
+        let slot = response.context.slot;
+        for (pubkey, account) in chunk.iter().zip(response.value) {
+            match account {
+                Some(account) => {
+                    apply_account(pubkey, &account.owner, &account.data, slot);
+                }
+                None => warn!(%pubkey, "Tracked pool account not found"),
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Account filters for the `accounts` field of the Geyser `SubscribeRequest`.
+/// Empty when no pools are configured.
+pub fn pool_account_filters() -> HashMap<String, SubscribeRequestFilterAccounts> {
+    let accounts: Vec<String> = tracked_pool_accounts()
+        .iter()
+        .map(|acc| acc.to_string())
+        .collect();
+    if accounts.is_empty() {
+        return HashMap::new();
+    }
+
+    let mut filters = HashMap::new();
+    filters.insert(
+        "pools".to_string(),
+        SubscribeRequestFilterAccounts {
+            account: accounts,
+            ..Default::default()
+        },
+    );
+    filters
+}
+
+/// Slot filter so the cache can tell how far the stream has progressed.
+pub fn pool_slot_filters() -> HashMap<String, SubscribeRequestFilterSlots> {
+    if MARKET_POOLS.is_empty() {
+        return HashMap::new();
+    }
+    let mut filters = HashMap::new();
+    filters.insert("slots".to_string(), SubscribeRequestFilterSlots::default());
+    filters
+}
+
+/// Route account and slot updates into the pool cache. Returns true when the update was
+/// consumed here, false when it should go on to trade processing.
+pub fn apply_market_update(update: &SubscribeUpdate) -> bool {
+    match &update.update_oneof {
+        Some(subscribe_update::UpdateOneof::Account(acc)) => {
+            if let Some(info) = &acc.account {
+                if let (Ok(pubkey), Ok(owner)) = (
+                    Pubkey::try_from(info.pubkey.as_slice()),
+                    Pubkey::try_from(info.owner.as_slice()),
+                ) {
+                    apply_account(&pubkey, &owner, &info.data, acc.slot);
+                }
+            }
+            true
+        }
+        Some(subscribe_update::UpdateOneof::Slot(slot)) => {
+            note_slot(slot.slot);
+            true
+        }
+        _ => false,
+    }
+}
@@ -1,9 +1,13 @@
+pub mod direct;
 pub mod discovery;
 pub mod execute;
 pub mod jupiter;
+pub mod market;
 pub mod runtime;
 
+pub use direct::*;
 pub use discovery::*;
 pub use execute::*;
 pub use jupiter::*;
+pub use market::*;
 pub use runtime::*;
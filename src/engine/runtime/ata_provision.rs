@@ -0,0 +1,310 @@
+//! ATA provisioning: pre-creates every trading wallet's token accounts for base tokens, quote targets
+//! and `INTEMEDIATE_TOKEN` mints outside the arbitrage path, so arb transactions do not pay
+//! rent or carry create instructions. Optionally closes empty ATAs of other mints once they have
+//! stayed empty, and unused by any route, for `CLOSE_IDLE_AFTER`.
+
+use once_cell::sync::Lazy;
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    transaction::Transaction,
+};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, RwLock};
+use std::time::Instant;
+use tokio::time::{interval, Duration};
+use tracing::{debug, info, warn};
+
+use crate::*;
+
+/// Instructions per maintenance transaction (create and close both fit comfortably).
+const IXS_PER_TX: usize = 8;
+const RPC_BATCH: usize = 100;
+/// SPL Token / Token-2022 `CloseAccount` instruction tag.
+const CLOSE_ACCOUNT_TAG: u8 = 9;
+/// An empty account is closed only after it has been empty, and its mint unused by any
+/// route, for this long.
+const CLOSE_IDLE_AFTER: Duration = Duration::from_secs(1_800);
+
+/// ATAs known to exist; their create instructions are dropped from arb transactions.
+static PROVISIONED_ATAS: Lazy<RwLock<HashSet<Pubkey>>> = Lazy::new(|| RwLock::new(HashSet::new()));
+/// Mints the arbitrage routes passed through, and when they were last used.
+static ROUTE_MINTS_USED: Lazy<Mutex<HashMap<Pubkey, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+/// Empty token accounts and when cleanup first saw them empty.
+static EMPTY_SINCE: Lazy<Mutex<HashMap<Pubkey, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Record the mints of a route being built so cleanup leaves their accounts open.
+pub fn note_route_mints(mints: impl IntoIterator<Item = Pubkey>) {
+    let now = Instant::now();
+    let mut used = ROUTE_MINTS_USED.lock().unwrap();
+    for mint in mints {
+        used.insert(mint, now);
+    }
+}
+
+/// Base tokens, quote targets and intermediate tokens the bot routes through.
+fn provision_mints() -> Vec<String> {
+    BASE_TOKENS
+        .iter()
+        .map(|t| t.mint.clone())
+        .chain(std::iter::once(TARGET_TOKEN.clone()))
+        .chain(BASE_TOKENS.iter().flat_map(|t| t.target_mints.iter().cloned()))
+        .chain(STABLE_QUOTE_MINTS.iter().map(|m| m.to_string()))
+        .chain(INTEMEDIATE_TOKEN.iter().map(|m| m.to_string()))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Create any missing ATA for the provisioned mints in every wallet and record the ones that exist.
+pub async fn provision_atas() -> Result<(), anyhow::Error> {
+    let mints = filter_tradable_mints(provision_mints()).await;
+    for wallet in wallets() {
+        provision_wallet_atas(wallet, &mints).await?;
+    }
+    Ok(())
+}
+
+async fn provision_wallet_atas(wallet: &TradingWallet, mints: &[String]) -> Result<(), anyhow::Error> {
+    let owner = wallet.pubkey;
+    let mut wanted: Vec<(Pubkey, Pubkey, Pubkey)> = Vec::new();
+    for mint in mints {
+        let (Ok(mint_key), Some(meta)) = (mint.parse::<Pubkey>(), token_meta(mint)) else {
+            continue;
+        };
+        let program = meta.token_program();
+        let ata = get_associated_token_address_with_program_id(&owner, &mint_key, &program);
+        wanted.push((ata, mint_key, program));
+    }
+
+    let mut missing = Vec::new();
+    for chunk in wanted.chunks(RPC_BATCH) {
+        let atas: Vec<Pubkey> = chunk.iter().map(|(ata, _, _)| *ata).collect();
+        let accounts = RPC_CLIENT.get_multiple_accounts(&atas).await?;
+        let mut provisioned = PROVISIONED_ATAS.write().unwrap();
+        for (entry, account) in chunk.iter().zip(accounts) {
+            match account {
+                Some(_) => {
+                    provisioned.insert(entry.0);
+                }
+                None => missing.push(*entry),
+            }
+        }
+    }
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if !CONFIG.strategy.live_trading {
+        info!(wallet = %owner, count = missing.len(), "ATAs missing; not created while execution is disabled");
+        return Ok(());
+    }
+
+    for chunk in missing.chunks(IXS_PER_TX) {
+        let ixs: Vec<Instruction> = chunk
+            .iter()
+            .map(|(_, mint, program)| create_associated_token_account_idempotent(&owner, &owner, mint, program))
+            .collect();
+        match send_maintenance_tx(wallet, &ixs).await {
+            Ok(signature) => {
+                PROVISIONED_ATAS
+                    .write()
+                    .unwrap()
+                    .extend(chunk.iter().map(|(ata, _, _)| *ata));
+                info!(wallet = %owner, count = chunk.len(), %signature, "ATAs created");
+            }
+            Err(e) => warn!(wallet = %owner, error = %e, "ATA creation failed"),
+        }
+    }
+    Ok(())
+}
+
+/// Close zero-balance token accounts of mints outside the provisioned set to reclaim rent.
+/// Accounts still holding withheld Token-2022 fees cannot be closed and are left alone.
+pub async fn close_empty_atas() -> Result<(), anyhow::Error> {
+    let keep: HashSet<String> = provision_mints().into_iter().collect();
+    let mut empty = HashSet::new();
+    for wallet in wallets() {
+        close_wallet_empty_atas(wallet, &keep, &mut empty).await?;
+    }
+    // Accounts that were closed or received tokens start over
+    EMPTY_SINCE.lock().unwrap().retain(|account, _| empty.contains(account));
+    Ok(())
+}
+
+async fn close_wallet_empty_atas(
+    wallet: &TradingWallet,
+    keep: &HashSet<String>,
+    empty: &mut HashSet<Pubkey>,
+) -> Result<(), anyhow::Error> {
+    let owner = wallet.pubkey;
+    let mut candidates = Vec::new();
+
+    for program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let accounts = RPC_CLIENT
+            .get_token_accounts_by_owner(&owner, TokenAccountsFilter::ProgramId(program))
+            .await?;
+        for keyed in accounts {
+            // jsonParsed token account: data.parsed.info.{mint, tokenAmount.amount}
+            let value = serde_json::to_value(&keyed.account)?;
+            let info = &value["data"]["parsed"]["info"];
+            let (Some(mint), Some(amount)) = (
+                info["mint"].as_str(),
+                info["tokenAmount"]["amount"].as_str(),
+            ) else {
+                continue;
+            };
+            if amount != "0" || keep.contains(mint) {
+                continue;
+            }
+            let (Ok(account), Ok(mint)) = (keyed.pubkey.parse::<Pubkey>(), mint.parse::<Pubkey>()) else {
+                continue;
+            };
+            let withheld = withheld_fee_amount(info);
+            if withheld > 0 {
+                debug!(%account, %mint, withheld, "Empty token account holds withheld fees; not closed");
+                continue;
+            }
+            candidates.push((account, mint, program));
+        }
+    }
+
+    let closable: Vec<Instruction> = {
+        let mut empty_since = EMPTY_SINCE.lock().unwrap();
+        let used = ROUTE_MINTS_USED.lock().unwrap();
+        candidates
+            .into_iter()
+            .filter(|(account, mint, _)| {
+                empty.insert(*account);
+                let since = *empty_since.entry(*account).or_insert_with(Instant::now);
+                since.elapsed() >= CLOSE_IDLE_AFTER
+                    && used.get(mint).is_none_or(|at| at.elapsed() >= CLOSE_IDLE_AFTER)
+            })
+            .map(|(account, _, program)| close_account_ix(&program, &account, &owner))
+            .collect()
+    };
+
+    if closable.is_empty() || !CONFIG.strategy.live_trading {
+        return Ok(());
+    }
+    for chunk in closable.chunks(IXS_PER_TX) {
+        match send_maintenance_tx(wallet, chunk).await {
+            Ok(signature) => info!(wallet = %owner, count = chunk.len(), %signature, "Empty token accounts closed"),
+            Err(e) => warn!(wallet = %owner, error = %e, "Closing empty token accounts failed"),
+        }
+    }
+    Ok(())
+}
+
+/// Transfer fees withheld in a jsonParsed Token-2022 account (`transferFeeAmount` extension).
+fn withheld_fee_amount(info: &serde_json::Value) -> u64 {
+    info["extensions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|ext| ext["extension"] == "transferFeeAmount")
+        .filter_map(|ext| {
+            let withheld = &ext["state"]["withheldAmount"];
+            withheld.as_u64().or_else(|| withheld.as_str()?.parse().ok())
+        })
+        .sum()
+}
+
+fn close_account_ix(token_program: &Pubkey, account: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *token_program,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*owner, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![CLOSE_ACCOUNT_TAG],
+    }
+}
+
+async fn send_maintenance_tx(wallet: &TradingWallet, ixs: &[Instruction]) -> Result<String, anyhow::Error> {
+    let blockhash = RPC_CLIENT.get_latest_blockhash().await?;
+    let mut tx = Transaction::new_with_payer(ixs, Some(&wallet.pubkey));
+    tx.try_sign(&[wallet.signer], blockhash)?;
+    let signature = RPC_CLIENT.send_and_confirm_transaction(&tx).await?;
+    Ok(signature.to_string())
+}
+
+/// Startup run followed by one run every `interval_secs` (0 = startup only).
+pub async fn start_ata_maintenance(interval_secs: u64) {
+    let run = || async {
+        if CONFIG.inventory.provision_atas {
+            if let Err(e) = provision_atas().await {
+                warn!(error = %e, "ATA provisioning failed");
+            }
+        }
+        if CONFIG.inventory.close_empty_atas {
+            if let Err(e) = close_empty_atas().await {
+                warn!(error = %e, "Empty ATA cleanup failed");
+            }
+        }
+    };
+
+    run().await;
+    if interval_secs == 0 {
+        return;
+    }
+    let mut ticker = interval(Duration::from_secs(interval_secs));
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        run().await;
+    }
+}
+
+/// Drop idempotent ATA-create instructions for accounts that are already provisioned.
+pub fn strip_provisioned_ata_creates(ixs: Vec<Instruction>) -> Vec<Instruction> {
+    let provisioned = PROVISIONED_ATAS.read().unwrap();
+    if provisioned.is_empty() {
+        return ixs;
+    }
+    ixs.into_iter()
+        .filter(|ix| {
+            !(ix.program_id == spl_associated_token_account::id()
+                && ix.accounts.get(1).is_some_and(|ata| provisioned.contains(&ata.pubkey)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_withheld_transfer_fees() {
+        let info = json!({
+            "mint": "So11111111111111111111111111111111111111112",
+            "tokenAmount": { "amount": "0" },
+            "extensions": [
+                { "extension": "immutableOwner" },
+                { "extension": "transferFeeAmount", "state": { "withheldAmount": 42 } }
+            ]
+        });
+        assert_eq!(withheld_fee_amount(&info), 42);
+
+        let string_amount = json!({ "extensions": [
+            { "extension": "transferFeeAmount", "state": { "withheldAmount": "7" } }
+        ] });
+        assert_eq!(withheld_fee_amount(&string_amount), 7);
+    }
+
+    #[test]
+    fn plain_accounts_hold_no_withheld_fees() {
+        assert_eq!(withheld_fee_amount(&json!({ "mint": "x", "tokenAmount": { "amount": "0" } })), 0);
+        let drained = json!({ "extensions": [
+            { "extension": "transferFeeAmount", "state": { "withheldAmount": 0 } }
+        ] });
+        assert_eq!(withheld_fee_amount(&drained), 0);
+    }
+}
@@ -3,6 +3,7 @@ use tokio::time::{Duration, sleep};
 
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
+use tracing::error;
 
 use crate::RPC_CLIENT;
 
@@ -30,10 +31,7 @@ pub async fn recent_blockhash_handler() {
                 break; // Exit the loop once we get a valid blockhash
             }
             Err(e) => {
-                println!(
-                    "[BLOCKHASH ERROR]\n\t* ERR MSG : {}\n\t* Retrying in 200ms...",
-                    e
-                );
+                error!(error = %e, "Failed to fetch latest blockhash; retrying in 200ms");
                 sleep(Duration::from_millis(200)).await;
             }
         }
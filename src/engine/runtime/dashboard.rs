@@ -0,0 +1,359 @@
+//! Live terminal dashboard (`[dashboard]` or `--dashboard`): feed and price freshness,
+//! per-instrument sweeps, recent submissions with their landing status, session PnL and the
+//! latest log lines, which go here instead of stdout while it runs.
+//!
+//! The trading loops only push into shared state (no-ops while the dashboard is off); a
+//! dedicated thread redraws it. Log lines reach it through a bounded queue, so logging never
+//! waits on a redraw, and go to stdout whenever the dashboard is not running. `q` or Ctrl-C
+//! restores the terminal and signals `dashboard_closed`, on which `main` shuts down.
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use ratatui::{
+    crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Paragraph, Row, Table},
+    Frame,
+};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::*;
+
+const MAX_SUBMISSIONS: usize = 50;
+const MAX_LOG_LINES: usize = 200;
+
+static DASHBOARD_ACTIVE: AtomicBool = AtomicBool::new(false);
+static DASHBOARD: Lazy<Mutex<DashboardState>> = Lazy::new(|| Mutex::new(DashboardState::default()));
+/// Log lines for the dashboard; lines beyond its capacity are dropped.
+static LOG_QUEUE: Lazy<(SyncSender<String>, Mutex<Receiver<String>>)> = Lazy::new(|| {
+    let (tx, rx) = sync_channel(MAX_LOG_LINES);
+    (tx, Mutex::new(rx))
+});
+static CLOSED: Notify = Notify::const_new();
+
+#[derive(Debug)]
+struct Sweep {
+    mode: &'static str,
+    at: Instant,
+    /// Best `(out - in) / in` of the sweep, before costs.
+    best_spread_bps: Option<f64>,
+    /// Best net profit after costs, raw base-token units.
+    best_net: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum SubmissionStatus {
+    /// Sent; stays here when landing is not tracked for it.
+    Submitted,
+    Landed(i64),
+    NotLanded,
+}
+
+#[derive(Debug)]
+struct Submission {
+    id: OpportunityId,
+    at: DateTime<Local>,
+    source: &'static str,
+    mint: String,
+    wallet: String,
+    in_amount: u64,
+    expected_out: u64,
+    status: SubmissionStatus,
+}
+
+#[derive(Debug, Default)]
+struct DashboardState {
+    /// Instrument mint -> latest sweep.
+    sweeps: BTreeMap<String, Sweep>,
+    /// Newest first.
+    submissions: VecDeque<Submission>,
+    /// Instrument mint -> realized balance change of landings this session, raw units.
+    session_pnl: BTreeMap<String, i64>,
+}
+
+fn with_state(update: impl FnOnce(&mut DashboardState)) {
+    if DASHBOARD_ACTIVE.load(Ordering::Relaxed) {
+        update(&mut DASHBOARD.lock().unwrap());
+    }
+}
+
+/// Record a finished sweep over `mint`'s amounts and targets.
+pub fn note_sweep(mint: &str, mode: &'static str, best_spread_bps: Option<f64>, best_net: Option<i64>) {
+    with_state(|state| {
+        state.sweeps.insert(
+            mint.to_string(),
+            Sweep { mode, at: Instant::now(), best_spread_bps, best_net },
+        );
+    });
+}
+
+/// Record a submitted trade; its status follows `note_landing`.
+pub fn note_submitted(id: OpportunityId, source: &'static str, mint: &str, wallet: String, in_amount: u64, expected_out: u64) {
+    with_state(|state| {
+        state.submissions.push_front(Submission {
+            id,
+            at: Local::now(),
+            source,
+            mint: mint.to_string(),
+            wallet,
+            in_amount,
+            expected_out,
+            status: SubmissionStatus::Submitted,
+        });
+        state.submissions.truncate(MAX_SUBMISSIONS);
+    });
+}
+
+/// Landing outcome of a tracked submission: the base-token balance change, or None when it
+/// never landed.
+pub fn note_landing(id: OpportunityId, balance_change: Option<i64>) {
+    with_state(|state| {
+        let Some(submission) = state.submissions.iter_mut().find(|s| s.id == id) else {
+            return;
+        };
+        submission.status = match balance_change {
+            Some(change) => SubmissionStatus::Landed(change),
+            None => SubmissionStatus::NotLanded,
+        };
+        if let Some(change) = balance_change {
+            let mint = submission.mint.clone();
+            *state.session_pnl.entry(mint).or_default() += change;
+        }
+    });
+}
+
+/// Console log sink when a dashboard was requested: the dashboard's log pane while it runs,
+/// stdout before it starts, if it fails to and after it stops. Never blocks on the dashboard.
+pub struct DashboardLogWriter;
+
+impl Write for DashboardLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if !DASHBOARD_ACTIVE.load(Ordering::Relaxed) {
+            return std::io::stdout().write(buf);
+        }
+        let text = String::from_utf8_lossy(buf);
+        for line in text.lines().filter(|l| !l.trim().is_empty()) {
+            let _ = LOG_QUEUE.0.try_send(line.to_string());
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !DASHBOARD_ACTIVE.load(Ordering::Relaxed) {
+            return std::io::stdout().flush();
+        }
+        Ok(())
+    }
+}
+
+/// Take over the terminal and redraw it every `refresh_ms` on a dedicated thread. On error
+/// the terminal is left as it was and logs keep going to stdout.
+pub fn start_dashboard(refresh_ms: u64) -> Result<(), anyhow::Error> {
+    // Installs a panic hook that restores the terminal
+    let terminal = ratatui::try_init()?;
+    let refresh = Duration::from_millis(refresh_ms);
+    if let Err(e) = std::thread::Builder::new()
+        .name("dashboard".to_string())
+        .spawn(move || run_dashboard(terminal, refresh))
+    {
+        ratatui::restore();
+        return Err(e.into());
+    }
+    DASHBOARD_ACTIVE.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Resolves once the user closed the dashboard with `q` or Ctrl-C.
+pub async fn dashboard_closed() {
+    CLOSED.notified().await;
+}
+
+fn run_dashboard(mut terminal: ratatui::DefaultTerminal, refresh: Duration) {
+    let started = Instant::now();
+    let mut logs: VecDeque<String> = VecDeque::new();
+    loop {
+        logs.extend(LOG_QUEUE.1.lock().unwrap().try_iter());
+        if logs.len() > MAX_LOG_LINES {
+            logs.drain(..logs.len() - MAX_LOG_LINES);
+        }
+        if let Err(e) = terminal.draw(|frame| draw(frame, started, &logs)) {
+            stop_dashboard();
+            warn!(error = %e, "Dashboard stopped; logging to stdout");
+            return;
+        }
+        if !event::poll(refresh).unwrap_or(false) {
+            continue;
+        }
+        if let Ok(TermEvent::Key(key)) = event::read() {
+            let ctrl_c = key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c');
+            if key.kind == KeyEventKind::Press && (key.code == KeyCode::Char('q') || ctrl_c) {
+                stop_dashboard();
+                CLOSED.notify_one();
+                return;
+            }
+        }
+    }
+}
+
+fn stop_dashboard() {
+    DASHBOARD_ACTIVE.store(false, Ordering::Relaxed);
+    ratatui::restore();
+}
+
+fn draw(frame: &mut Frame, started: Instant, logs: &VecDeque<String>) {
+    // Gathered before locking: the status sources may log
+    let status = status_lines(started);
+    let state = DASHBOARD.lock().unwrap();
+    let [status_area, sweeps_area, submissions_area, logs_area] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Length(state.sweeps.len() as u16 + 3),
+        Constraint::Min(6),
+        Constraint::Length(10),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(
+        Paragraph::new(status)
+            .block(Block::bordered().title(format!(" {}  (q to quit) ", env!("CARGO_PKG_NAME")))),
+        status_area,
+    );
+
+    let header = Style::default().add_modifier(Modifier::BOLD);
+    let sweeps: Vec<Row> = state
+        .sweeps
+        .iter()
+        .map(|(mint, sweep)| {
+            Row::new(vec![
+                token_symbol(mint),
+                sweep.mode.to_string(),
+                format!("{:.1}s ago", sweep.at.elapsed().as_secs_f64()),
+                sweep.best_spread_bps.map(|bps| format!("{:.1}", bps)).unwrap_or_else(|| "-".to_string()),
+                sweep.best_net.map(|net| human_amount(mint, net)).unwrap_or_else(|| "-".to_string()),
+                state.session_pnl.get(mint).map(|pnl| human_amount(mint, *pnl)).unwrap_or_else(|| "0".to_string()),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        Table::new(
+            sweeps,
+            [
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(12),
+                Constraint::Length(18),
+                Constraint::Length(18),
+                Constraint::Length(18),
+            ],
+        )
+        .header(Row::new(["instrument", "mode", "last sweep", "best spread bps", "best net profit", "session pnl"]).style(header))
+        .block(Block::bordered().title(" Instruments ")),
+        sweeps_area,
+    );
+
+    let submissions: Vec<Row> = state
+        .submissions
+        .iter()
+        .map(|s| {
+            let (status, color) = match s.status {
+                SubmissionStatus::Submitted => ("submitted".to_string(), Color::Yellow),
+                SubmissionStatus::Landed(change) if change > 0 => {
+                    (format!("landed +{}", human_amount(&s.mint, change)), Color::Green)
+                }
+                SubmissionStatus::Landed(change) => (format!("landed {}", human_amount(&s.mint, change)), Color::Red),
+                SubmissionStatus::NotLanded => ("not landed".to_string(), Color::DarkGray),
+            };
+            Row::new(vec![
+                s.at.format("%H:%M:%S").to_string(),
+                s.source.to_string(),
+                token_symbol(&s.mint),
+                s.wallet.chars().take(8).collect(),
+                human_amount(&s.mint, s.in_amount as i64),
+                human_amount(&s.mint, s.expected_out as i64),
+                status,
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+    frame.render_widget(
+        Table::new(
+            submissions,
+            [
+                Constraint::Length(9),
+                Constraint::Length(10),
+                Constraint::Length(10),
+                Constraint::Length(9),
+                Constraint::Length(16),
+                Constraint::Length(16),
+                Constraint::Min(12),
+            ],
+        )
+        .header(Row::new(["time", "source", "instrument", "wallet", "in", "expected out", "status"]).style(header))
+        .block(Block::bordered().title(" Recent submissions ")),
+        submissions_area,
+    );
+
+    let visible = logs_area.height.saturating_sub(2) as usize;
+    let logs: Vec<Line> = logs
+        .iter()
+        .skip(logs.len().saturating_sub(visible))
+        .map(|line| Line::raw(line.clone()))
+        .collect();
+    frame.render_widget(Paragraph::new(logs).block(Block::bordered().title(" Log ")), logs_area);
+}
+
+fn status_lines(started: Instant) -> Vec<Line<'static>> {
+    let uptime = started.elapsed().as_secs();
+    let sol_price = SOL_PRICE.try_lock().ok().and_then(|p| *p).unwrap_or(FEES.sol_usd);
+    let price_age = sol_price_age().map(|age| format!(", {}s ago", age.as_secs())).unwrap_or_default();
+    let nonce = nonce_age()
+        .map(|age| format!("{:.1}s", age.as_secs_f64()))
+        .unwrap_or_else(|| "never".to_string());
+    let geyser = if !CONFIG.strategy.watch_flows && MARKET_POOLS.is_empty() {
+        "off".to_string()
+    } else if let Some(down) = geyser_down_for() {
+        format!("down {}s", down.as_secs())
+    } else {
+        geyser_lag()
+            .map(|lag| format!("lag {}ms", lag.as_millis()))
+            .unwrap_or_else(|| "connected".to_string())
+    };
+    let breaker = match risk_tripped() {
+        Some(remaining) => format!("TRIPPED {}s", remaining.as_secs()),
+        None => "armed".to_string(),
+    };
+    vec![
+        Line::raw(format!(
+            "uptime {:02}:{:02}:{:02}   live trading {}   wallets {}",
+            uptime / 3600,
+            uptime / 60 % 60,
+            uptime % 60,
+            if CONFIG.strategy.live_trading { "on" } else { "off" },
+            wallets().len(),
+        )),
+        Line::raw(format!(
+            "SOL ${:.2} ({}{})   nonce {}   geyser {}   breaker {}",
+            sol_price,
+            sol_price_source(),
+            price_age,
+            nonce,
+            geyser,
+            breaker,
+        )),
+    ]
+}
+
+fn human_amount(mint: &str, raw: i64) -> String {
+    match token_meta(mint) {
+        Some(meta) => format!("{:.6}", raw as f64 / 10_f64.powi(meta.decimals as i32)),
+        None => raw.to_string(),
+    }
+}
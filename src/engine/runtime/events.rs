@@ -0,0 +1,112 @@
+//! Typed trading events. Each one is emitted as a tracing event under the `events` target,
+//! whose fields become one flat JSON line in the event log (see `init_logging`).
+
+use tracing::info;
+
+/// Tracing target of the event stream.
+pub const EVENT_TARGET: &str = "events";
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A quote round trip cleared min profit after costs.
+    OpportunityFound {
+        instrument: String,
+        target_token: String,
+        /// `polling` or `big_trade`.
+        mode: &'static str,
+        in_amount: u64,
+        out_amount: u64,
+        tx_cost: i64,
+        net_profit: i64,
+    },
+    /// A transaction was handed to the relay services.
+    Submitted {
+        /// `polling`, `direct` or `big_trade`.
+        source: &'static str,
+        instrument: String,
+        wallet: String,
+        in_amount: u64,
+        expected_out: u64,
+        /// Relay path, as recorded in the opportunity DB (see `submission_label`).
+        service: String,
+        signature: Option<String>,
+    },
+    /// A tracked trade landed (its nonce advanced).
+    Landed {
+        wallet: String,
+        mint: String,
+        profitable: bool,
+        /// Change of the wallet's spendable balance of `mint`, raw units.
+        balance_change: i64,
+        venues: String,
+    },
+    QuoteFailed {
+        instrument: String,
+        target_token: String,
+        in_amount: u64,
+        error: String,
+    },
+    BigTradeDetected {
+        tx_id: String,
+        instrument: String,
+        mint: String,
+        min_profit: f64,
+        /// `SYMBOL:delta` per changed balance, comma-separated.
+        changes: String,
+        programs: String,
+    },
+}
+
+pub fn emit(event: Event) {
+    match event {
+        Event::OpportunityFound { instrument, target_token, mode, in_amount, out_amount, tx_cost, net_profit } => info!(
+            target: EVENT_TARGET,
+            event = "OpportunityFound",
+            %instrument,
+            %target_token,
+            mode,
+            in_amount,
+            out_amount,
+            tx_cost,
+            net_profit,
+        ),
+        Event::Submitted { source, instrument, wallet, in_amount, expected_out, service, signature } => info!(
+            target: EVENT_TARGET,
+            event = "Submitted",
+            source,
+            %instrument,
+            %wallet,
+            in_amount,
+            expected_out,
+            %service,
+            signature = signature.as_deref(),
+        ),
+        Event::Landed { wallet, mint, profitable, balance_change, venues } => info!(
+            target: EVENT_TARGET,
+            event = "Landed",
+            %wallet,
+            %mint,
+            profitable,
+            balance_change,
+            %venues,
+        ),
+        Event::QuoteFailed { instrument, target_token, in_amount, error } => info!(
+            target: EVENT_TARGET,
+            event = "QuoteFailed",
+            %instrument,
+            %target_token,
+            in_amount,
+            %error,
+        ),
+        Event::BigTradeDetected { tx_id, instrument, mint, min_profit, changes, programs } => info!(
+            target: EVENT_TARGET,
+            event = "BigTradeDetected",
+            %tx_id,
+            %instrument,
+            %mint,
+            min_profit,
+            %changes,
+            %programs,
+        ),
+    }
+}
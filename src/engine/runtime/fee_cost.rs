@@ -1,6 +1,9 @@
-//! Transaction cost calculation using SOL price and tx cost config.
+//! Transaction cost calculation using token prices and tx cost config.
 
 use crate::app::config::TxCostConfig;
+use super::token_extensions::TransferFee;
+use super::token_registry::token_meta;
+use crate::flash_loan_reserve;
 use crate::chain::TRANSACTION_FEE;
 
 use super::sol_price::SOL_PRICE;
@@ -18,47 +21,70 @@ pub fn calculate_tx_cost_for_profit(fee: &TxCostConfig, gross_profit_sol: f64) -
     (total_sol, third_party_fee_sol)
 }
 
-/// Transaction cost for a trade: takes gross profit in raw token units and returns (total_tx_cost in raw token units, third_party_fee_sol for submission).
-/// Use `sol_price` (USD per SOL) when token is not SOL so that gross profit can be converted to SOL for the fee formula.
-pub fn calculate_tx_cost_for_trade_with_sol_price(
-    fee: &TxCostConfig,
-    gross_profit_raw: i64,
-    token_is_sol: bool,
-    token_decimal: u8,
-    sol_price: f64,
-) -> (i64, f64) {
-    let pow = 10_f64.powf(token_decimal as f64);
-    let gross_profit_sol = if token_is_sol {
-        gross_profit_raw as f64 / pow
-    } else {
-        (gross_profit_raw as f64 / pow) / sol_price
-    };
-    let (total_sol, third_party_fee_sol) = calculate_tx_cost_for_profit(fee, gross_profit_sol);
-    let total_tx_cost_raw = if token_is_sol {
-        (total_sol * pow) as i64
-    } else {
-        (total_sol * sol_price * pow) as i64
+/// Flash loan fee in raw token units for borrowing `amount` of `mint`; 0 when the mint has no
+/// flash reserve (the trade is funded from inventory). Rounded up like the lending program.
+pub fn flash_loan_fee_raw(mint: &str, amount: u64) -> i64 {
+    flash_loan_reserve(mint).map_or(0, |reserve| reserve.fee_for(amount) as i64)
+}
+
+/// Token-2022 transfer fees of a round trip in raw base-token units. The base token moves
+/// wallet -> pool (`in_amount`) and pool -> wallet (`out_amount`); the target moves pool ->
+/// wallet (`mid_amount`, the leg 1 output) and what arrived back wallet -> pool.
+pub fn transfer_fee_cost_raw(
+    base_mint: &str,
+    target_mint: &str,
+    in_amount: u64,
+    mid_amount: u64,
+    out_amount: u64,
+) -> i64 {
+    round_trip_transfer_fees(
+        token_meta(base_mint).and_then(|t| t.transfer_fee),
+        token_meta(target_mint).and_then(|t| t.transfer_fee),
+        in_amount,
+        mid_amount,
+        out_amount,
+    ) as i64
+}
+
+/// Each transfer is charged on its own amount, capped at `maximum_fee`. Target fees are
+/// converted to base units at the leg 1 rate `in_amount / mid_amount`, rounded up.
+fn round_trip_transfer_fees(
+    base_fee: Option<TransferFee>,
+    target_fee: Option<TransferFee>,
+    in_amount: u64,
+    mid_amount: u64,
+    out_amount: u64,
+) -> u64 {
+    let base = base_fee.map_or(0, |f| f.fee_for(in_amount) + f.fee_for(out_amount));
+    let target = match target_fee {
+        Some(f) if mid_amount > 0 => {
+            let received = f.fee_for(mid_amount);
+            let sent = f.fee_for(mid_amount - received);
+            ((received + sent) as u128 * in_amount as u128).div_ceil(mid_amount as u128) as u64
+        }
+        _ => 0,
     };
-    (total_tx_cost_raw, third_party_fee_sol)
+    base + target
 }
 
-/// Async version: uses current SOL price from runtime. Returns (total_tx_cost in raw token units, third_party_fee_sol for submission).
-pub async fn calculate_tx_cost_for_trade(
+/// Transaction cost for a trade: takes gross profit in raw token units and returns (total_tx_cost in raw token units, third_party_fee_sol for submission).
+/// `token_price_sol` (SOL per whole token, 1.0 for SOL) converts gross profit to SOL for the fee formula and the SOL cost back to token units.
+/// Includes the flash loan fee on `in_amount` when `mint` is flash-funded.
+pub fn calculate_tx_cost_for_trade_with_price(
     fee: &TxCostConfig,
     gross_profit_raw: i64,
-    token_is_sol: bool,
     token_decimal: u8,
+    token_price_sol: f64,
+    mint: &str,
+    in_amount: u64,
 ) -> (i64, f64) {
-    let sol_price = {
-        let guard = SOL_PRICE.lock().await;
-        guard.unwrap_or(fee.sol_usd)
-    };
-    calculate_tx_cost_for_trade_with_sol_price(
-        fee,
-        gross_profit_raw,
-        token_is_sol,
-        token_decimal,
-        sol_price,
+    let pow = 10_f64.powf(token_decimal as f64);
+    let gross_profit_sol = gross_profit_raw as f64 / pow * token_price_sol;
+    let (total_sol, third_party_fee_sol) = calculate_tx_cost_for_profit(fee, gross_profit_sol);
+    let total_tx_cost_raw = (total_sol / token_price_sol * pow) as i64;
+    (
+        total_tx_cost_raw + flash_loan_fee_raw(mint, in_amount),
+        third_party_fee_sol,
     )
 }
 
@@ -72,3 +98,88 @@ pub async fn calculate_tx_cost_usdc(fee: &TxCostConfig) -> f64 {
     let (total_sol, _) = calculate_tx_cost_for_profit(fee, 0.0);
     total_sol * sol_price
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEE_1PCT: TransferFee = TransferFee { basis_points: 100, maximum_fee: u64::MAX };
+    /// No flash reserve is loaded in tests, so trades carry no flash loan fee.
+    const MINT: &str = "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN";
+
+    fn fees(tip_sol: f64, third_party_fee_profit_pct: Option<f64>) -> TxCostConfig {
+        TxCostConfig {
+            compute_units: 400_000,
+            priority_lamports: 10_000,
+            tip_sol,
+            third_party_fee_profit_pct,
+            sol_usd: 150.0,
+        }
+    }
+
+    /// (cost, tip) of a trade of 100 tokens.
+    fn trade(fee: TxCostConfig, gross_profit_raw: i64, decimals: u8, token_price_sol: f64) -> (i64, f64) {
+        let in_amount = 100 * 10_u64.pow(decimals as u32);
+        calculate_tx_cost_for_trade_with_price(&fee, gross_profit_raw, decimals, token_price_sol, MINT, in_amount)
+    }
+
+    fn assert_near(actual: i64, expected: i64) {
+        assert!((actual - expected).abs() <= 1, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn fixed_tip_in_sol_units() {
+        // 0.000005 SOL network fee + 0.001 SOL tip, in lamports
+        let (cost, tip) = trade(fees(0.001, None), 10_000_000, 9, 1.0);
+        assert_near(cost, 1_005_000);
+        assert_eq!(tip, 0.001);
+    }
+
+    #[test]
+    fn cost_converted_at_token_price() {
+        // 6 decimals at 0.005 SOL per token: 0.001005 SOL = 0.201 tokens
+        let (cost, _) = trade(fees(0.001, None), 2_000_000, 6, 0.005);
+        assert_near(cost, 201_000);
+    }
+
+    #[test]
+    fn profit_share_replaces_fixed_tip() {
+        // 2 tokens of gross profit = 0.01 SOL; half of it is the tip
+        let (cost, tip) = trade(fees(0.001, Some(0.5)), 2_000_000, 6, 0.005);
+        assert!((tip - 0.005).abs() < 1e-12);
+        assert_near(cost, 1_001_000);
+        // A loss pays no share
+        let (_, tip) = trade(fees(0.001, Some(0.5)), -2_000_000, 6, 0.005);
+        assert_eq!(tip, 0.0);
+        // Out of range falls back to the fixed tip
+        let (_, tip) = trade(fees(0.001, Some(1.5)), 2_000_000, 6, 0.005);
+        assert_eq!(tip, 0.001);
+    }
+
+    #[test]
+    fn no_fees_without_token_2022() {
+        assert_eq!(round_trip_transfer_fees(None, None, 1_000, 2_000, 1_010), 0);
+    }
+
+    #[test]
+    fn base_fee_charged_on_each_leg_amount() {
+        // 1% of 1_000 in and 1% of 1_200 out
+        assert_eq!(round_trip_transfer_fees(Some(FEE_1PCT), None, 1_000, 2_000, 1_200), 22);
+    }
+
+    #[test]
+    fn target_fee_follows_what_arrived_and_converts_to_base() {
+        // 20 withheld on the way in, 1% of the remaining 1_980 (19.8 -> 20) on the way out:
+        // 40 target units at 1_000 base per 2_000 target
+        assert_eq!(round_trip_transfer_fees(None, Some(FEE_1PCT), 1_000, 2_000, 1_010), 20);
+        assert_eq!(round_trip_transfer_fees(None, Some(FEE_1PCT), 1_000, 0, 1_010), 0);
+    }
+
+    #[test]
+    fn fees_respect_maximum() {
+        let capped = TransferFee { basis_points: 100, maximum_fee: 5 };
+        assert_eq!(round_trip_transfer_fees(Some(capped), None, 1_000_000, 1, 1_000_000), 10);
+        // 5 + 5 target units at 1 base per 10 target, rounded up
+        assert_eq!(round_trip_transfer_fees(None, Some(capped), 1_000, 10_000, 1_000), 1);
+    }
+}
@@ -0,0 +1,226 @@
+//! Liveness and readiness for a process supervisor, served on `/healthz` and `/readyz`.
+//!
+//! Liveness only asks whether the nonce fetcher is still running. Readiness also needs a
+//! SOL price fresher than `sol_price.stale_after_secs`, a responding Jupiter endpoint, a connected and flowing Yellowstone
+//! stream (when one is used) and, when the `[risk]` breaker is configured, an untripped breaker.
+
+use jupiter_swap_api_client::quote::QuoteRequest;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use crate::*;
+
+const NONCE_MAX_AGE: Duration = Duration::from_secs(10);
+const GEYSER_MAX_SILENCE: Duration = Duration::from_secs(30);
+/// A Jupiter call that succeeded this recently makes a probe unnecessary.
+const JUPITER_OK_WINDOW: Duration = Duration::from_secs(60);
+const JUPITER_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// A probe result is reused this long, so frequent `/readyz` polls do not each quote Jupiter.
+const JUPITER_PROBE_TTL: Duration = Duration::from_secs(15);
+
+static LAST_JUPITER_OK: RwLock<Option<Instant>> = RwLock::new(None);
+/// Latest probe: when it ran, whether it passed and its detail.
+static LAST_JUPITER_PROBE: RwLock<Option<(Instant, bool, String)>> = RwLock::new(None);
+static GEYSER_CONNECTED: AtomicBool = AtomicBool::new(false);
+static LAST_GEYSER_MESSAGE: RwLock<Option<Instant>> = RwLock::new(None);
+static LAST_GEYSER_LAG: RwLock<Option<Duration>> = RwLock::new(None);
+/// When the stream last went down (or the monitor started without one); None while connected.
+static GEYSER_DOWN_SINCE: RwLock<Option<Instant>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    pub ok: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    fn new(checks: Vec<HealthCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.ok),
+            checks,
+        }
+    }
+}
+
+pub fn note_jupiter_ok() {
+    *LAST_JUPITER_OK.write().unwrap() = Some(Instant::now());
+}
+
+pub fn set_geyser_connected(connected: bool) {
+    GEYSER_CONNECTED.store(connected, Ordering::Relaxed);
+    let mut down_since = GEYSER_DOWN_SINCE.write().unwrap();
+    if connected {
+        *down_since = None;
+    } else if down_since.is_none() {
+        *down_since = Some(Instant::now());
+    }
+}
+
+/// How long the Yellowstone stream has been down, if it is used and not connected.
+pub fn geyser_down_for() -> Option<Duration> {
+    if !GEYSER_CONNECTED.load(Ordering::Relaxed) {
+        return GEYSER_DOWN_SINCE.read().unwrap().map(|since| since.elapsed());
+    }
+    None
+}
+
+pub fn note_geyser_message() {
+    *LAST_GEYSER_MESSAGE.write().unwrap() = Some(Instant::now());
+}
+
+/// Delivery lag of the latest Geyser update that carried a creation time.
+pub fn note_geyser_lag(lag: Duration) {
+    *LAST_GEYSER_LAG.write().unwrap() = Some(lag);
+}
+
+pub fn geyser_lag() -> Option<Duration> {
+    *LAST_GEYSER_LAG.read().unwrap()
+}
+
+fn age_check(name: &'static str, age: Option<Duration>, max: Option<Duration>) -> HealthCheck {
+    match age {
+        None => HealthCheck { name, ok: false, detail: "never updated".to_string() },
+        Some(age) => HealthCheck {
+            name,
+            ok: max.is_none_or(|max| age <= max),
+            detail: format!("updated {:.1}s ago", age.as_secs_f64()),
+        },
+    }
+}
+
+fn sol_price_check() -> HealthCheck {
+    age_check("sol_price", sol_price_age(), sol_price_max_age(CONFIG.sol_price.stale_after_secs))
+}
+
+/// `stale_after_secs` as a limit; 0 never goes stale.
+fn sol_price_max_age(stale_after_secs: u64) -> Option<Duration> {
+    (stale_after_secs > 0).then(|| Duration::from_secs(stale_after_secs))
+}
+
+fn nonce_check() -> HealthCheck {
+    age_check("nonce", nonce_age(), Some(NONCE_MAX_AGE))
+}
+
+async fn jupiter_check() -> HealthCheck {
+    let last_ok = *LAST_JUPITER_OK.read().unwrap();
+    if let Some(at) = last_ok.filter(|at| at.elapsed() <= JUPITER_OK_WINDOW) {
+        return HealthCheck {
+            name: "jupiter",
+            ok: true,
+            detail: format!("last success {:.1}s ago", at.elapsed().as_secs_f64()),
+        };
+    }
+    if let Some((at, ok, detail)) = LAST_JUPITER_PROBE.read().unwrap().clone() {
+        if at.elapsed() <= JUPITER_PROBE_TTL {
+            let detail = format!("{} {:.1}s ago", detail, at.elapsed().as_secs_f64());
+            return HealthCheck { name: "jupiter", ok, detail };
+        }
+    }
+    // Idle: probe with a small USDC -> SOL quote
+    let request = QuoteRequest {
+        amount: 1_000_000,
+        input_mint: USDC_MINT,
+        output_mint: WSOL,
+        ..QuoteRequest::default()
+    };
+    let probe = observe_jupiter("quote", JUPITER_CLIENT.quote(&request));
+    let (ok, detail) = match tokio::time::timeout(JUPITER_PROBE_TIMEOUT, probe).await {
+        Ok(Ok(_)) => (true, "probe quote succeeded".to_string()),
+        Ok(Err(e)) => (false, format!("probe quote failed: {}", e)),
+        Err(_) => (false, "probe quote timed out".to_string()),
+    };
+    *LAST_JUPITER_PROBE.write().unwrap() = Some((Instant::now(), ok, detail.clone()));
+    HealthCheck { name: "jupiter", ok, detail }
+}
+
+fn geyser_check() -> Option<HealthCheck> {
+    if !CONFIG.strategy.watch_flows && MARKET_POOLS.is_empty() {
+        return None;
+    }
+    if !GEYSER_CONNECTED.load(Ordering::Relaxed) {
+        return Some(HealthCheck { name: "geyser", ok: false, detail: "not connected".to_string() });
+    }
+    let last_message = LAST_GEYSER_MESSAGE.read().unwrap().map(|at| at.elapsed());
+    Some(age_check("geyser", last_message, Some(GEYSER_MAX_SILENCE)))
+}
+
+/// None when the loss-streak breaker is not configured.
+fn risk_check() -> Option<HealthCheck> {
+    if CONFIG.risk.max_consecutive_losses == 0 {
+        return None;
+    }
+    Some(match risk_tripped() {
+        Some(remaining) => HealthCheck {
+            name: "risk",
+            ok: false,
+            detail: format!("circuit breaker tripped, {}s left", remaining.as_secs()),
+        },
+        None => HealthCheck { name: "risk", ok: true, detail: "armed".to_string() },
+    })
+}
+
+/// Liveness: the nonce fetcher keeps reading.
+pub fn liveness() -> HealthReport {
+    HealthReport::new(vec![nonce_check()])
+}
+
+/// Readiness: everything trading depends on.
+pub async fn readiness() -> HealthReport {
+    let mut checks = vec![
+        nonce_check(),
+        sol_price_check(),
+        jupiter_check().await,
+    ];
+    checks.extend(geyser_check());
+    checks.extend(risk_check());
+    HealthReport::new(checks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn age_check_compares_against_the_limit() {
+        let fresh = age_check("nonce", Some(Duration::from_secs(3)), Some(NONCE_MAX_AGE));
+        assert!(fresh.ok);
+        assert_eq!(fresh.detail, "updated 3.0s ago");
+        assert!(age_check("nonce", Some(NONCE_MAX_AGE), Some(NONCE_MAX_AGE)).ok);
+        assert!(!age_check("nonce", Some(NONCE_MAX_AGE + Duration::from_millis(1)), Some(NONCE_MAX_AGE)).ok);
+        assert!(age_check("sol_price", Some(Duration::from_secs(86_400)), None).ok);
+
+        let never = age_check("nonce", None, None);
+        assert!(!never.ok);
+        assert_eq!(never.detail, "never updated");
+    }
+
+    #[test]
+    fn sol_price_limit_follows_stale_after() {
+        assert_eq!(sol_price_max_age(0), None);
+        assert_eq!(sol_price_max_age(60), Some(Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn report_needs_every_check() {
+        let ok = |name| HealthCheck { name, ok: true, detail: String::new() };
+        assert!(HealthReport::new(vec![ok("nonce"), ok("sol_price"), ok("jupiter")]).ok);
+        assert!(HealthReport::new(Vec::new()).ok);
+
+        let stale_nonce = age_check("nonce", Some(Duration::from_secs(11)), Some(NONCE_MAX_AGE));
+        let report = HealthReport::new(vec![stale_nonce, ok("sol_price"), ok("jupiter")]);
+        assert!(!report.ok);
+        assert_eq!(report.checks.len(), 3);
+
+        let stale_price = age_check("sol_price", Some(Duration::from_secs(61)), sol_price_max_age(60));
+        assert!(!HealthReport::new(vec![ok("nonce"), stale_price, ok("jupiter")]).ok);
+    }
+}
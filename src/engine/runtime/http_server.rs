@@ -0,0 +1,72 @@
+//! Operational HTTP servers: `/healthz` and `/readyz` for the process supervisor, always served;
+//! Prometheus `/metrics` and the control API routes (`venue_routes`) when telemetry is enabled.
+
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use tracing::{error, info};
+
+use crate::*;
+
+/// Spawn the HTTP servers: health on `health_listen_addr` (default `listen_addr`), and metrics
+/// plus the control API on `listen_addr` when `enabled`. One server takes both when the
+/// addresses are the same.
+pub fn start_http_servers(config: &TelemetryConfig) {
+    let health_addr = config.health_listen_addr.clone().unwrap_or_else(|| config.listen_addr.clone());
+    if !config.enabled {
+        tokio::spawn(serve_http(health_addr, health_routes()));
+    } else if health_addr == config.listen_addr {
+        tokio::spawn(serve_http(health_addr, health_routes().merge(telemetry_routes())));
+    } else {
+        tokio::spawn(serve_http(health_addr, health_routes()));
+        tokio::spawn(serve_http(config.listen_addr.clone(), telemetry_routes()));
+    }
+}
+
+fn health_routes() -> Router {
+    Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+}
+
+fn telemetry_routes() -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .merge(venue_routes())
+}
+
+/// Bind `listen_addr` and serve `app` until the process exits. Bind errors are logged, not fatal.
+async fn serve_http(listen_addr: String, app: Router) {
+    let listener = match tokio::net::TcpListener::bind(&listen_addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!(error = %e, %listen_addr, "HTTP server bind failed");
+            return;
+        }
+    };
+    info!(%listen_addr, "HTTP server listening");
+    if let Err(e) = axum::serve(listener, app).await {
+        error!(error = %e, "HTTP server stopped");
+    }
+}
+
+/// 200 when every check passes, 503 otherwise; the report is the body either way.
+fn health_response(report: HealthReport) -> impl IntoResponse {
+    let status = if report.ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+async fn healthz() -> impl IntoResponse {
+    health_response(liveness())
+}
+
+async fn readyz() -> impl IntoResponse {
+    health_response(readiness().await)
+}
+
+async fn metrics() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], render_metrics())
+}
@@ -0,0 +1,343 @@
+//! Wallet inventory: SOL and the ATA balance of every instrument for each trading wallet,
+//! refreshed periodically over RPC and from Geyser account updates in between. Trading loops
+//! size trades with `effective_notional_range`, which clamps to what the best funded wallet
+//! holds and pauses instruments below their floor or when SOL for fees runs low.
+
+use once_cell::sync::Lazy;
+use solana_sdk::{commitment_config::CommitmentConfig, pubkey::Pubkey};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use tokio::time::{interval, Duration};
+use tracing::{info, warn};
+use yellowstone_grpc_proto::geyser::{subscribe_update, SubscribeRequestFilterAccounts};
+use yellowstone_grpc_proto::prelude::SubscribeUpdate;
+
+use crate::app::config::BaseTokenConfig;
+use crate::*;
+
+const RPC_BATCH: usize = 100;
+
+#[derive(Debug, Default)]
+struct Inventory {
+    /// Wallet -> (lamports, slot); absent until the first refresh.
+    sol: HashMap<Pubkey, (u64, u64)>,
+    /// Instrument ATA -> (owner wallet, mint, amount, slot). A missing ATA holds 0.
+    tokens: HashMap<Pubkey, (Pubkey, String, u64, u64)>,
+    /// Instruments currently paused, to log transitions once.
+    paused: HashSet<String>,
+}
+
+static INVENTORY: Lazy<RwLock<Inventory>> = Lazy::new(|| RwLock::new(Inventory::default()));
+
+/// Instrument ATAs of every wallet, derived with each mint's owner program from the token
+/// registry: (ata, owner, mint).
+fn instrument_atas() -> Vec<(Pubkey, Pubkey, String)> {
+    let mut atas = Vec::new();
+    for wallet in wallets() {
+        for t in BASE_TOKENS.iter() {
+            let (Some(meta), Ok(mint)) = (token_meta(&t.mint), t.mint.parse::<Pubkey>()) else {
+                continue;
+            };
+            let ata = get_associated_token_address_with_program_id(&wallet.pubkey, &mint, &meta.token_program());
+            atas.push((ata, wallet.pubkey, t.mint.clone()));
+        }
+    }
+    atas
+}
+
+/// Re-read every wallet's SOL and instrument ATAs with batched `getMultipleAccounts` calls.
+pub async fn refresh_inventory() -> Result<(), anyhow::Error> {
+    let owners: Vec<Pubkey> = wallets().iter().map(|w| w.pubkey).collect();
+    let atas = instrument_atas();
+    let keys: Vec<Pubkey> = owners.iter().copied().chain(atas.iter().map(|(ata, _, _)| *ata)).collect();
+
+    let mut fetched = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(RPC_BATCH) {
+        let response = RPC_CLIENT
+            .get_multiple_accounts_with_commitment(chunk, CommitmentConfig::processed())
+            .await?;
+        let slot = response.context.slot;
+        fetched.extend(response.value.into_iter().map(|account| (account, slot)));
+    }
+    let mut fetched = fetched.into_iter();
+
+    let mut inventory = INVENTORY.write().unwrap();
+    for (owner, (account, slot)) in owners.into_iter().zip(fetched.by_ref()) {
+        let lamports = account.map(|a| a.lamports).unwrap_or(0);
+        let entry = inventory.sol.entry(owner).or_insert((lamports, slot));
+        if entry.1 <= slot {
+            *entry = (lamports, slot);
+        }
+    }
+    for ((ata, owner, mint), (account, slot)) in atas.into_iter().zip(fetched) {
+        let amount = account
+            .and_then(|a| decode_token_account_amount(&a.data))
+            .unwrap_or(0);
+        let entry = inventory.tokens.entry(ata).or_insert((owner, mint, 0, 0));
+        if entry.3 <= slot {
+            entry.2 = amount;
+            entry.3 = slot;
+        }
+    }
+    Ok(())
+}
+
+/// Periodic RPC refresh; runs forever.
+pub async fn start_inventory_refresher(interval_ms: u64) {
+    let mut ticker = interval(Duration::from_millis(interval_ms));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        if let Err(e) = refresh_inventory().await {
+            warn!(error = %e, "Inventory refresh failed");
+        }
+    }
+}
+
+/// Account filter for the wallets and their instrument ATAs, merged into the Geyser subscription.
+pub fn inventory_account_filters() -> HashMap<String, SubscribeRequestFilterAccounts> {
+    let accounts: Vec<String> = wallets()
+        .iter()
+        .map(|w| w.pubkey.to_string())
+        .chain(instrument_atas().into_iter().map(|(ata, _, _)| ata.to_string()))
+        .collect();
+    let mut filters = HashMap::new();
+    filters.insert(
+        "inventory".to_string(),
+        SubscribeRequestFilterAccounts {
+            account: accounts,
+            ..Default::default()
+        },
+    );
+    filters
+}
+
+/// Apply a Geyser account update for a wallet or an instrument ATA. Returns true when the
+/// update was consumed here.
+pub fn apply_inventory_update(update: &SubscribeUpdate) -> bool {
+    let Some(subscribe_update::UpdateOneof::Account(acc)) = &update.update_oneof else {
+        return false;
+    };
+    let Some(info) = &acc.account else {
+        return false;
+    };
+    let Ok(pubkey) = Pubkey::try_from(info.pubkey.as_slice()) else {
+        return false;
+    };
+
+    let mut inventory = INVENTORY.write().unwrap();
+    if wallet_by_pubkey(&pubkey).is_some() {
+        let entry = inventory.sol.entry(pubkey).or_insert((info.lamports, acc.slot));
+        if entry.1 <= acc.slot {
+            *entry = (info.lamports, acc.slot);
+        }
+        return true;
+    }
+    match inventory.tokens.get_mut(&pubkey) {
+        Some(entry) => {
+            if entry.3 <= acc.slot {
+                // A closed account arrives with empty data
+                entry.2 = decode_token_account_amount(&info.data).unwrap_or(0);
+                entry.3 = acc.slot;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Raw balance of `mint` in `owner`'s ATA, if known.
+pub fn token_balance(owner: &Pubkey, mint: &str) -> Option<u64> {
+    let inventory = INVENTORY.read().unwrap();
+    inventory
+        .tokens
+        .values()
+        .find(|(o, m, _, _)| o == owner && m == mint)
+        .map(|(_, _, amount, _)| *amount)
+}
+
+/// `owner`'s SOL in lamports, if known.
+pub fn sol_balance(owner: &Pubkey) -> Option<u64> {
+    INVENTORY.read().unwrap().sol.get(owner).map(|(lamports, _)| *lamports)
+}
+
+/// `[inventory].min_sol_reserve` in lamports.
+pub fn fee_reserve_lamports() -> u64 {
+    (CONFIG.inventory.min_sol_reserve * 1e9) as u64
+}
+
+/// Whether `owner` holds at least the fee reserve in SOL.
+pub fn has_fee_reserve(owner: &Pubkey) -> bool {
+    sol_balance(owner).is_some_and(|lamports| lamports >= fee_reserve_lamports())
+}
+
+/// Raw amount of `mint` that `owner` can spend on a swap. For WSOL with the swap API
+/// builder, native SOL above the fee reserve also counts (it is wrapped inside the transaction).
+pub fn spendable_balance(owner: &Pubkey, mint: &str) -> Option<u64> {
+    let wraps_native_sol = mint == WSOL.to_string() && CONFIG.strategy.route_builder == RouteBuilder::SwapApi;
+    let wrappable_lamports = if wraps_native_sol { Some(sol_balance(owner)?) } else { None };
+    Some(spendable_raw(token_balance(owner, mint)?, wrappable_lamports, fee_reserve_lamports()))
+}
+
+/// `token_amount` plus the `wrappable_lamports` above the fee reserve, if native SOL is wrapped.
+fn spendable_raw(token_amount: u64, wrappable_lamports: Option<u64>, reserve_lamports: u64) -> u64 {
+    token_amount + wrappable_lamports.map_or(0, |lamports| lamports.saturating_sub(reserve_lamports))
+}
+
+/// Notional range the instrument can trade right now, in token units, sized to the best funded
+/// wallet. None pauses it: the inventory is not loaded yet, no wallet has SOL above the fee
+/// reserve, or no balance reaches the instrument's floor or its minimum notional.
+/// Flash-funded instruments keep their range.
+pub fn effective_notional_range(config: &BaseTokenConfig, decimals: u8) -> Option<[f64; 2]> {
+    let range = effective_range_inner(config, decimals);
+    let mut inventory = INVENTORY.write().unwrap();
+    match &range {
+        Ok(_) => {
+            if inventory.paused.remove(&config.mint) {
+                info!(mint = %config.mint, "Instrument resumed");
+            }
+        }
+        Err(reason) => {
+            if inventory.paused.insert(config.mint.clone()) {
+                warn!(mint = %config.mint, %reason, "Instrument paused");
+            }
+        }
+    }
+    range.ok()
+}
+
+fn effective_range_inner(config: &BaseTokenConfig, decimals: u8) -> Result<[f64; 2], String> {
+    let funds: Vec<(Option<u64>, Option<u64>)> = wallets()
+        .iter()
+        .map(|w| (sol_balance(&w.pubkey), spendable_balance(&w.pubkey, &config.mint)))
+        .collect();
+    notional_range(
+        &funds,
+        fee_reserve_lamports(),
+        flash_loan_reserve(&config.mint).is_some(),
+        config,
+        decimals,
+    )
+}
+
+/// `effective_notional_range` over each wallet's (SOL lamports, spendable raw balance).
+fn notional_range(
+    funds: &[(Option<u64>, Option<u64>)],
+    reserve_lamports: u64,
+    flash_funded: bool,
+    config: &BaseTokenConfig,
+    decimals: u8,
+) -> Result<[f64; 2], String> {
+    let pow = 10_f64.powf(decimals as f64);
+
+    if funds.iter().all(|(sol, _)| sol.is_none()) {
+        return Err("inventory not loaded".to_string());
+    }
+    let funded: Vec<Option<u64>> = funds
+        .iter()
+        .filter(|(sol, _)| sol.is_some_and(|lamports| lamports >= reserve_lamports))
+        .map(|(_, spendable)| *spendable)
+        .collect();
+    if funded.is_empty() {
+        return Err("SOL below fee reserve on every wallet".to_string());
+    }
+    if flash_funded {
+        return Ok(config.amount_range);
+    }
+
+    let raw = funded.into_iter().flatten().max().ok_or("balance not loaded")?;
+    let balance = raw as f64 / pow;
+
+    if balance < config.min_balance {
+        return Err(format!("balance {:.6} below floor {:.6}", balance, config.min_balance));
+    }
+    let max = config.amount_range[1].min(balance);
+    if max < config.amount_range[0] {
+        return Err(format!("balance {:.6} below minimum notional", balance));
+    }
+    Ok([config.amount_range[0], max])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESERVE: u64 = 50_000_000;
+
+    fn config(amount_range: [f64; 2], min_balance: f64) -> BaseTokenConfig {
+        BaseTokenConfig {
+            mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            threshold: 0.0,
+            min_profit: 0.01,
+            min_profit_usd: None,
+            amount_range,
+            steps: 4,
+            min_balance,
+            target_mints: Vec::new(),
+            dexes: Vec::new(),
+            exclude_dexes: Vec::new(),
+            max_route_hops: None,
+            only_direct_routes: None,
+        }
+    }
+
+    #[test]
+    fn pauses_until_inventory_loads() {
+        let range = notional_range(&[(None, None), (None, None)], RESERVE, false, &config([1.0, 10.0], 0.0), 6);
+        assert_eq!(range, Err("inventory not loaded".to_string()));
+    }
+
+    #[test]
+    fn pauses_when_every_wallet_is_below_the_sol_reserve() {
+        let funds = [(Some(RESERVE - 1), Some(100_000_000)), (None, Some(100_000_000))];
+        let range = notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 0.0), 6);
+        assert_eq!(range, Err("SOL below fee reserve on every wallet".to_string()));
+        // The reserve still applies to flash-funded instruments
+        assert!(notional_range(&funds, RESERVE, true, &config([1.0, 10.0], 0.0), 6).is_err());
+    }
+
+    #[test]
+    fn flash_funded_instruments_keep_their_range() {
+        let funds = [(Some(RESERVE), Some(0))];
+        assert_eq!(notional_range(&funds, RESERVE, true, &config([1.0, 10.0], 5.0), 6), Ok([1.0, 10.0]));
+    }
+
+    #[test]
+    fn sizes_to_the_best_funded_wallet_above_the_reserve() {
+        // The richest wallet lacks SOL for fees, so the 4.0 balance sets the range
+        let funds = [(Some(RESERVE - 1), Some(9_000_000)), (Some(RESERVE), Some(4_000_000)), (Some(RESERVE), None)];
+        assert_eq!(notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 0.0), 6), Ok([1.0, 4.0]));
+        // Balances above the configured maximum leave it unclamped
+        let funds = [(Some(RESERVE), Some(20_000_000))];
+        assert_eq!(notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 0.0), 6), Ok([1.0, 10.0]));
+    }
+
+    #[test]
+    fn pauses_below_the_balance_floor() {
+        let funds = [(Some(RESERVE), Some(4_000_000))];
+        let range = notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 5.0), 6);
+        assert_eq!(range, Err("balance 4.000000 below floor 5.000000".to_string()));
+    }
+
+    #[test]
+    fn pauses_below_the_minimum_notional() {
+        let funds = [(Some(RESERVE), Some(500_000))];
+        let range = notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 0.0), 6);
+        assert_eq!(range, Err("balance 0.500000 below minimum notional".to_string()));
+    }
+
+    #[test]
+    fn pauses_when_no_funded_balance_is_known() {
+        let funds = [(Some(RESERVE), None)];
+        let range = notional_range(&funds, RESERVE, false, &config([1.0, 10.0], 0.0), 6);
+        assert_eq!(range, Err("balance not loaded".to_string()));
+    }
+
+    #[test]
+    fn wrapped_sol_counts_native_sol_above_the_reserve() {
+        assert_eq!(spendable_raw(1_000, None, RESERVE), 1_000);
+        assert_eq!(spendable_raw(1_000, Some(RESERVE + 2_000_000_000), RESERVE), 2_000_001_000);
+        assert_eq!(spendable_raw(1_000, Some(RESERVE - 1), RESERVE), 1_000);
+    }
+}
@@ -0,0 +1,75 @@
+//! Tracing setup: human-readable logs on stdout (filtered by `RUST_LOG`, default `info`) and,
+//! when enabled, the JSON event stream in a rotating file. While the terminal dashboard runs,
+//! the console lines go to its log panel instead of stdout.
+
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{
+    filter::filter_fn, fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt,
+    EnvFilter, Layer,
+};
+
+use crate::app::config::{LogRotation, LoggingConfig};
+use crate::{DashboardLogWriter, EVENT_TARGET};
+
+/// Install the global subscriber. `config` is None when the config failed to load; only the
+/// console is set up then. Keep the returned guard alive so buffered events are flushed.
+pub fn init_logging(config: Option<&LoggingConfig>, dashboard: bool) -> Option<WorkerGuard> {
+    let config = config.filter(|c| c.event_log_enabled);
+
+    let mut console_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    if config.is_some() {
+        // Events go to the file; the console keeps its regular log lines
+        console_filter = console_filter.add_directive(format!("{}=off", EVENT_TARGET).parse().unwrap());
+    }
+    let console_writer = if dashboard {
+        BoxMakeWriter::new(|| DashboardLogWriter)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let console = tracing_subscriber::fmt::layer()
+        .with_target(false)
+        .with_ansi(!dashboard)
+        .with_writer(console_writer)
+        .with_filter(console_filter);
+
+    let (events, guard) = match config.map(event_file_writer) {
+        Some(Ok((writer, guard))) => {
+            let layer = tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true)
+                .with_current_span(false)
+                .with_span_list(false)
+                .with_writer(writer)
+                .with_filter(filter_fn(|meta| meta.target() == EVENT_TARGET));
+            (Some(layer), Some(guard))
+        }
+        Some(Err(e)) => {
+            eprintln!("Event log disabled: {}", e);
+            (None, None)
+        }
+        None => (None, None),
+    };
+
+    tracing_subscriber::registry().with(console).with(events).init();
+    guard
+}
+
+fn event_file_writer(
+    config: &LoggingConfig,
+) -> Result<(tracing_appender::non_blocking::NonBlocking, WorkerGuard), anyhow::Error> {
+    let rotation = match config.rotation {
+        LogRotation::Minutely => rolling::Rotation::MINUTELY,
+        LogRotation::Hourly => rolling::Rotation::HOURLY,
+        LogRotation::Daily => rolling::Rotation::DAILY,
+        LogRotation::Never => rolling::Rotation::NEVER,
+    };
+    let mut builder = rolling::Builder::new()
+        .rotation(rotation)
+        .filename_prefix(&config.event_log_prefix)
+        .filename_suffix("jsonl");
+    if config.max_files > 0 {
+        builder = builder.max_log_files(config.max_files);
+    }
+    let appender = builder.build(&config.event_log_dir)?;
+    Ok(tracing_appender::non_blocking(appender))
+}
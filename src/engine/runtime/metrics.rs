@@ -0,0 +1,138 @@
+//! Prometheus metrics, served in text format on `/metrics` by the HTTP server.
+
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_gauge, register_gauge_vec, register_histogram, register_histogram_vec, register_int_counter, register_int_counter_vec,
+    register_int_gauge, Encoder, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, TextEncoder,
+};
+use std::future::Future;
+
+use crate::*;
+
+const LATENCY_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Jupiter API latency by endpoint (`quote`, `swap`, `swap_instructions`).
+pub static JUPITER_REQUEST_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "jupiter_request_seconds",
+        "Jupiter API request latency",
+        &["endpoint"],
+        LATENCY_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+/// Jupiter API requests by endpoint and result (`ok`, `error`).
+pub static JUPITER_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("jupiter_requests_total", "Jupiter API requests", &["endpoint", "result"]).unwrap()
+});
+
+/// Duration of one quote sweep by instrument and mode (`polling`, `big_trade`, `direct`).
+pub static SWEEP_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "simulate_sweep_seconds",
+        "Duration of a quote sweep over amounts and targets",
+        &["instrument", "mode"],
+        LATENCY_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+pub static OPPORTUNITIES_FOUND: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "opportunities_found_total",
+        "Quotes clearing min profit after costs",
+        &["instrument", "mode"]
+    )
+    .unwrap()
+});
+
+pub static OPPORTUNITIES_SUBMITTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("opportunities_submitted_total", "Trades submitted", &["instrument"]).unwrap()
+});
+
+/// Transactions handed to each relay service (`rpc` for plain RPC submission).
+pub static SUBMISSIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("submissions_total", "Transactions sent per relay service", &["service"]).unwrap()
+});
+
+/// Outcomes of submitted and preflighted trades: `profit`, `loss` (landed without profit),
+/// `not_landed` and `preflight_failed`.
+pub static LANDINGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("trade_outcomes_total", "Outcomes of trades", &["outcome"]).unwrap()
+});
+
+/// Candidates handled by the opportunity scheduler by source and outcome: `dispatched`, `expired`,
+/// `duplicate`, `no_wallet` and `queue_full`.
+pub static SCHEDULED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("scheduler_candidates_total", "Opportunity scheduler outcomes", &["source", "outcome"]).unwrap()
+});
+
+pub static NONCE_AGE_SECONDS: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!("nonce_age_seconds", "Age of the stalest wallet nonce held (-1 = not read yet)").unwrap()
+});
+
+pub static SOL_PRICE_AGE_SECONDS: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!("sol_price_age_seconds", "Age of the newest source in the SOL price (-1 = never fetched)").unwrap()
+});
+
+/// Last SOL/USD price per oracle source, and `median` for the one in use.
+pub static SOL_PRICE_USD: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!("sol_price_usd", "SOL/USD price by oracle source", &["source"]).unwrap()
+});
+
+/// USD price per token of each priced instrument and rebalance mint.
+pub static TOKEN_PRICE_USD: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!("token_price_usd", "USD price per whole token", &["token"]).unwrap()
+});
+
+pub static GEYSER_RECONNECTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!("geyser_reconnects_total", "Yellowstone stream reconnections after the first subscribe").unwrap()
+});
+
+/// Time from the Geyser node creating an update to this process receiving it.
+pub static GEYSER_LAG_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "geyser_message_lag_seconds",
+        "Yellowstone update delivery lag",
+        LATENCY_BUCKETS.to_vec()
+    )
+    .unwrap()
+});
+
+pub static VENUES_BLACKLISTED: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!("venues_blacklisted", "DEX labels currently blacklisted").unwrap()
+});
+
+/// Alerts by sink and result (`sent`, `failed`); `queue` and `batch` count `dropped` ones.
+pub static ALERTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!("alerts_total", "Alerts delivered, failed or dropped", &["sink", "result"]).unwrap()
+});
+
+/// Time a Jupiter API call and count its result under `endpoint`.
+pub async fn observe_jupiter<T, E>(endpoint: &str, call: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+    let timer = JUPITER_REQUEST_SECONDS.with_label_values(&[endpoint]).start_timer();
+    let result = call.await;
+    timer.observe_duration();
+    let outcome = if result.is_ok() {
+        note_jupiter_ok();
+        "ok"
+    } else {
+        "error"
+    };
+    JUPITER_REQUESTS.with_label_values(&[endpoint, outcome]).inc();
+    result
+}
+
+/// Refresh the point-in-time gauges and encode every metric in the Prometheus text format.
+pub fn render_metrics() -> String {
+    NONCE_AGE_SECONDS.set(nonce_age().map_or(-1.0, |age| age.as_secs_f64()));
+    SOL_PRICE_AGE_SECONDS.set(sol_price_age().map_or(-1.0, |age| age.as_secs_f64()));
+    VENUES_BLACKLISTED.set(venue_blacklist().len() as i64);
+
+    let mut buffer = Vec::new();
+    if let Err(e) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        tracing::warn!(error = %e, "Failed to encode metrics");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
@@ -1,10 +1,34 @@
 pub mod alt;
+pub mod ata_provision;
 pub mod block_hash;
+pub mod dashboard;
+pub mod events;
 pub mod fee_cost;
+pub mod health;
+pub mod http_server;
+pub mod inventory;
+pub mod logging;
+pub mod metrics;
 pub mod nonce;
+pub mod notifier;
 pub mod sol_price;
+pub mod token_extensions;
+pub mod token_price;
+pub mod token_registry;
 pub use alt::*;
+pub use ata_provision::*;
 pub use block_hash::*;
+pub use dashboard::*;
+pub use events::*;
 pub use fee_cost::*;
+pub use health::*;
+pub use http_server::*;
+pub use inventory::*;
+pub use logging::*;
+pub use metrics::*;
 pub use nonce::*;
-pub use sol_price::*;
\ No newline at end of file
+pub use notifier::*;
+pub use sol_price::*;
+pub use token_extensions::*;
+pub use token_price::*;
+pub use token_registry::*;
@@ -1,49 +1,79 @@
 use solana_rpc_client_nonce_utils::data_from_account;
-use solana_sdk::{commitment_config::CommitmentConfig, nonce::state::Data as NonceData};
+use solana_sdk::{commitment_config::CommitmentConfig, nonce::state::Data as NonceData, pubkey::Pubkey};
 use tokio::time::{Duration, sleep};
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Instant;
+use tracing::error;
 
-use crate::{NONCE_ADDR, RPC_CLIENT};
+use crate::{wallets, NONCE_ADDR, RPC_CLIENT};
 
-pub static GLOBAL_CURRENT_NONCE: Lazy<Mutex<NonceData>> =
-    Lazy::new(|| Mutex::new(NonceData::default()));
+/// Latest nonce data of every wallet's nonce account.
+pub static GLOBAL_CURRENT_NONCE: Lazy<RwLock<HashMap<Pubkey, NonceData>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
 
-pub fn set_nonce(new_nonce: NonceData) {
-    let mut nonce = GLOBAL_CURRENT_NONCE.lock().unwrap();
-    *nonce = new_nonce;
+/// When each nonce account's data was last read and decoded.
+static NONCE_READ_AT: Lazy<RwLock<HashMap<Pubkey, Instant>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+pub fn set_nonce(nonce_account: Pubkey, new_nonce: NonceData) {
+    GLOBAL_CURRENT_NONCE.write().unwrap().insert(nonce_account, new_nonce);
+    NONCE_READ_AT.write().unwrap().insert(nonce_account, Instant::now());
 }
 
+/// Nonce data of wallet 0's nonce account.
 pub fn get_nonce() -> NonceData {
-    let nonce = GLOBAL_CURRENT_NONCE.lock().unwrap();
-    nonce.clone()
+    get_nonce_of(&NONCE_ADDR)
+}
+
+/// Nonce data of `nonce_account`; default (zero blockhash) until first fetched.
+pub fn get_nonce_of(nonce_account: &Pubkey) -> NonceData {
+    GLOBAL_CURRENT_NONCE
+        .read()
+        .unwrap()
+        .get(nonce_account)
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// Age of the stalest nonce held: time since that wallet's nonce account was last read and
+/// decoded. A fetch that fails, or returns a missing or undecodable account, does not refresh
+/// it. None until every wallet's nonce has been read once.
+pub fn nonce_age() -> Option<std::time::Duration> {
+    let read_at = NONCE_READ_AT.read().unwrap();
+    wallets()
+        .iter()
+        .map(|w| read_at.get(&w.nonce_account).map(|at| at.elapsed()))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .max()
 }
 
 pub async fn fetch_nonce() {
+    let nonce_accounts: Vec<Pubkey> = wallets().iter().map(|w| w.nonce_account).collect();
     loop {
         match RPC_CLIENT
-            .get_account_with_commitment(&NONCE_ADDR, CommitmentConfig::processed())
+            .get_multiple_accounts_with_commitment(&nonce_accounts, CommitmentConfig::processed())
             .await
         {
             Ok(response) => {
-                if let Some(account) = response.value {
-                    match data_from_account(&account) {
-                        Ok(nonce_data) => {
-                            set_nonce(nonce_data);
-                        }
-                        Err(err) => {
-                            eprintln!("[NONCE ERROR] Failed to decode nonce: {}", err);
+                for (nonce_account, account) in nonce_accounts.iter().zip(response.value) {
+                    if let Some(account) = account {
+                        match data_from_account(&account) {
+                            Ok(nonce_data) => {
+                                set_nonce(*nonce_account, nonce_data);
+                            }
+                            Err(err) => {
+                                error!(%nonce_account, error = %err, "Failed to decode nonce account");
+                            }
                         }
+                    } else {
+                        error!(%nonce_account, "Nonce account not found");
                     }
-                } else {
-                    eprintln!("[NONCE ERROR] Nonce account not found.");
                 }
             }
             Err(e) => {
-                eprintln!(
-                    "[NONCE ERROR]\n\t* ERR MSG : {}\n\t* Retrying in 200ms...",
-                    e
-                );
+                error!(error = %e, "Failed to fetch nonce accounts; retrying in 200ms");
             }
         }
 
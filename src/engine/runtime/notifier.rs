@@ -0,0 +1,373 @@
+//! Alert delivery to webhooks, Telegram, Discord and Slack. `notify` only queues; a single
+//! background task batches queued alerts, enforces `min_interval_secs` between deliveries and
+//! hands each batch to a delivery task, so neither the trading loops nor the watches below
+//! wait on a network call.
+//!
+//! The same task watches wallet SOL balances and the Yellowstone stream once a second;
+//! submission and landing outcomes are fed in by the submit path.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::app::config::{AlertSinkConfig, AlertSinkKind};
+use crate::*;
+
+const QUEUE_CAPACITY: usize = 1_024;
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const TELEGRAM_API: &str = "https://api.telegram.org";
+/// Message length limits of Discord and Telegram.
+const DISCORD_MAX_CHARS: usize = 2_000;
+const TELEGRAM_MAX_CHARS: usize = 4_096;
+
+static ALERT_QUEUE: OnceCell<mpsc::Sender<Alert>> = OnceCell::new();
+static FAILED_SUBMISSIONS: AtomicU32 = AtomicU32::new(0);
+/// Alerts lost to a full queue or batch since the last delivery.
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    ProfitableLanding,
+    SubmissionFailures,
+    CircuitBreakerTripped,
+    LowSolBalance,
+    GeyserDown,
+    GeyserRecovered,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
+
+impl Alert {
+    fn line(&self) -> String {
+        format!("[{}] {:?}: {}", self.at.format("%H:%M:%S"), self.kind, self.message)
+    }
+}
+
+/// Queue an alert for delivery. Never blocks; alerts are dropped while alerting is disabled,
+/// before the notifier starts or when the queue is full.
+pub fn notify(kind: AlertKind, message: impl Into<String>) {
+    let Some(queue) = ALERT_QUEUE.get() else {
+        return;
+    };
+    let alert = Alert {
+        kind,
+        message: message.into(),
+        at: Utc::now(),
+    };
+    if queue.try_send(alert).is_err() {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+        ALERTS.with_label_values(&["queue", "dropped"]).inc();
+    }
+}
+
+/// Count a submission that failed to send or to land; alerts once per streak of
+/// `failed_submissions`.
+pub fn note_submission_failed(reason: &str) {
+    let limit = CONFIG.alerts.failed_submissions;
+    let failures = FAILED_SUBMISSIONS.fetch_add(1, Ordering::Relaxed) + 1;
+    if limit > 0 && failures == limit {
+        notify(
+            AlertKind::SubmissionFailures,
+            format!("{} submissions in a row failed (last: {})", failures, reason),
+        );
+    }
+}
+
+/// A submission landed; ends the failure streak.
+pub fn note_submission_landed() {
+    FAILED_SUBMISSIONS.store(0, Ordering::Relaxed);
+}
+
+/// Alert on a landed trade whose gain in `mint` reaches `landed_profit_above`.
+pub fn note_landed_profit(wallet: &Pubkey, mint: &str, gain_raw: u64) {
+    let (Some(threshold), Some(meta)) = (CONFIG.alerts.landed_profit_above, token_meta(mint)) else {
+        return;
+    };
+    let gain = gain_raw as f64 / 10_f64.powf(meta.decimals as f64);
+    if gain >= threshold {
+        notify(
+            AlertKind::ProfitableLanding,
+            format!("trade landed +{:.6} {} on wallet {}", gain, meta.symbol, wallet),
+        );
+    }
+}
+
+/// Run the notifier until the process exits: queue intake, batching, rate limiting and the
+/// balance and Geyser watches. No-op unless `[alerts]` is enabled.
+pub async fn start_notifier() {
+    let config = &CONFIG.alerts;
+    if !config.enabled {
+        return;
+    }
+    let (tx, mut rx) = mpsc::channel(QUEUE_CAPACITY);
+    if ALERT_QUEUE.set(tx).is_err() {
+        return;
+    }
+    info!(sinks = config.sinks.len(), "Alert notifier started");
+
+    let batch_window = Duration::from_secs(config.batch_window_secs);
+    let min_interval = Duration::from_secs(config.min_interval_secs);
+    let mut pending: Vec<Alert> = Vec::new();
+    let mut first_pending_at: Option<Instant> = None;
+    let mut last_delivery: Option<Instant> = None;
+    let mut watch = Watch::default();
+    let mut ticker = interval(WATCH_INTERVAL);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        tokio::select! {
+            Some(alert) = rx.recv() => {
+                if pending.len() < config.max_batch {
+                    first_pending_at.get_or_insert_with(Instant::now);
+                    pending.push(alert);
+                } else {
+                    DROPPED.fetch_add(1, Ordering::Relaxed);
+                    ALERTS.with_label_values(&["batch", "dropped"]).inc();
+                }
+            }
+            _ = ticker.tick() => {
+                watch.check();
+                let window_elapsed = first_pending_at.is_some_and(|at| at.elapsed() >= batch_window);
+                let rate_ok = last_delivery.is_none_or(|at| at.elapsed() >= min_interval);
+                if window_elapsed && rate_ok {
+                    let batch = std::mem::take(&mut pending);
+                    first_pending_at = None;
+                    last_delivery = Some(Instant::now());
+                    let dropped = DROPPED.swap(0, Ordering::Relaxed);
+                    tokio::spawn(async move { deliver(&config.sinks, &batch, dropped).await });
+                }
+            }
+        }
+    }
+}
+
+/// Edge-triggered watches: each condition alerts once until it clears.
+#[derive(Default)]
+struct Watch {
+    low_sol: HashSet<Pubkey>,
+    geyser_down: bool,
+}
+
+impl Watch {
+    fn check(&mut self) {
+        let config = &CONFIG.alerts;
+        if config.sol_balance_floor > 0.0 {
+            let floor_lamports = (config.sol_balance_floor * 1e9) as u64;
+            for wallet in wallets() {
+                let Some(lamports) = sol_balance(&wallet.pubkey) else {
+                    continue;
+                };
+                if lamports >= floor_lamports {
+                    self.low_sol.remove(&wallet.pubkey);
+                } else if self.low_sol.insert(wallet.pubkey) {
+                    notify(
+                        AlertKind::LowSolBalance,
+                        format!(
+                            "wallet {} holds {:.4} SOL, below the {} SOL floor",
+                            wallet.pubkey,
+                            lamports as f64 / 1e9,
+                            config.sol_balance_floor
+                        ),
+                    );
+                }
+            }
+        }
+
+        if config.geyser_down_secs > 0 {
+            let down_for = geyser_down_for();
+            match down_for {
+                Some(down) if !self.geyser_down && down.as_secs() >= config.geyser_down_secs => {
+                    self.geyser_down = true;
+                    notify(
+                        AlertKind::GeyserDown,
+                        format!("Yellowstone stream down for {}s", down.as_secs()),
+                    );
+                }
+                None if self.geyser_down => {
+                    self.geyser_down = false;
+                    notify(AlertKind::GeyserRecovered, "Yellowstone stream reconnected");
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Post one batch to every sink concurrently; failures are logged and counted, not retried.
+async fn deliver(sinks: &[AlertSinkConfig], batch: &[Alert], dropped: u64) {
+    let mut text = batch.iter().map(Alert::line).collect::<Vec<_>>().join("\n");
+    if dropped > 0 {
+        text.push_str(&format!("\n({} more alerts dropped)", dropped));
+    }
+    let sends = sinks.iter().map(|sink| {
+        let text = text.as_str();
+        async move {
+            let name = sink_name(sink.kind);
+            let (url, body) = match sink.kind {
+                AlertSinkKind::Webhook => (
+                    sink.url.clone(),
+                    json!({ "source": env!("CARGO_PKG_NAME"), "alerts": batch, "dropped": dropped }),
+                ),
+                AlertSinkKind::Slack => (sink.url.clone(), json!({ "text": text })),
+                AlertSinkKind::Discord => (sink.url.clone(), json!({ "content": truncate(text, DISCORD_MAX_CHARS) })),
+                AlertSinkKind::Telegram => {
+                    let base = if sink.url.is_empty() { TELEGRAM_API } else { sink.url.trim_end_matches('/') };
+                    (
+                        format!("{}/bot{}/sendMessage", base, sink.bot_token),
+                        json!({ "chat_id": sink.chat_id, "text": truncate(text, TELEGRAM_MAX_CHARS) }),
+                    )
+                }
+            };
+            let result = HTTP_CLIENT
+                .post(&url)
+                .json(&body)
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            match result {
+                Ok(_) => {
+                    debug!(sink = name, alerts = batch.len(), "Alerts delivered");
+                    ALERTS.with_label_values(&[name, "sent"]).inc_by(batch.len() as u64);
+                }
+                Err(e) => {
+                    // reqwest errors carry the URL, which holds the Telegram bot token
+                    warn!(sink = name, error = %e.without_url(), "Alert delivery failed");
+                    ALERTS.with_label_values(&[name, "failed"]).inc_by(batch.len() as u64);
+                }
+            }
+        }
+    });
+    futures::future::join_all(sends).await;
+}
+
+fn sink_name(kind: AlertSinkKind) -> &'static str {
+    match kind {
+        AlertSinkKind::Webhook => "webhook",
+        AlertSinkKind::Telegram => "telegram",
+        AlertSinkKind::Discord => "discord",
+        AlertSinkKind::Slack => "slack",
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => text[..end].to_string(),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{extract::State, http::Uri, Json, Router};
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    type Received = Arc<Mutex<Vec<(String, Value)>>>;
+
+    /// Local HTTP stub recording the path and JSON body of every POST; returns its base URL.
+    async fn stub_server() -> (String, Received) {
+        async fn record(State(received): State<Received>, uri: Uri, Json(body): Json<Value>) {
+            received.lock().unwrap().push((uri.path().to_string(), body));
+        }
+        let received = Received::default();
+        let app = Router::new().fallback(record).with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (base, received)
+    }
+
+    fn sink(kind: AlertSinkKind, url: String) -> AlertSinkConfig {
+        AlertSinkConfig {
+            kind,
+            url,
+            bot_token: "123:abc".to_string(),
+            chat_id: "42".to_string(),
+        }
+    }
+
+    fn alert(kind: AlertKind, message: &str) -> Alert {
+        Alert { kind, message: message.to_string(), at: Utc::now() }
+    }
+
+    #[tokio::test]
+    async fn delivers_batch_to_every_sink_format() {
+        let (base, received) = stub_server().await;
+        let sinks = [
+            sink(AlertSinkKind::Webhook, format!("{}/hook", base)),
+            sink(AlertSinkKind::Slack, format!("{}/slack", base)),
+            sink(AlertSinkKind::Discord, format!("{}/discord", base)),
+            sink(AlertSinkKind::Telegram, format!("{}/", base)),
+        ];
+        let batch = [
+            alert(AlertKind::GeyserDown, "Yellowstone stream down for 30s"),
+            alert(AlertKind::LowSolBalance, "wallet below floor"),
+        ];
+        deliver(&sinks, &batch, 3).await;
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort_by(|a, b| a.0.cmp(&b.0));
+        let paths: Vec<&str> = received.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(paths, ["/bot123:abc/sendMessage", "/discord", "/hook", "/slack"]);
+
+        let body = |path: &str| &received.iter().find(|(p, _)| p == path).unwrap().1;
+        let webhook = body("/hook");
+        assert_eq!(webhook["alerts"].as_array().unwrap().len(), 2);
+        assert_eq!(webhook["alerts"][0]["kind"], "geyser_down");
+        assert_eq!(webhook["dropped"], 3);
+
+        let text = body("/slack")["text"].as_str().unwrap();
+        assert!(text.contains("GeyserDown: Yellowstone stream down for 30s"));
+        assert!(text.contains("LowSolBalance: wallet below floor"));
+        assert!(text.ends_with("(3 more alerts dropped)"));
+        assert_eq!(body("/discord")["content"].as_str().unwrap(), text);
+        let telegram = body("/bot123:abc/sendMessage");
+        assert_eq!(telegram["chat_id"], "42");
+        assert_eq!(telegram["text"].as_str().unwrap(), text);
+    }
+
+    #[tokio::test]
+    async fn truncates_to_sink_limits() {
+        let (base, received) = stub_server().await;
+        let sinks = [sink(AlertSinkKind::Discord, base)];
+        deliver(&sinks, &[alert(AlertKind::ProfitableLanding, &"é".repeat(3_000))], 0).await;
+
+        let received = received.lock().unwrap();
+        let content = received[0].1["content"].as_str().unwrap();
+        assert_eq!(content.chars().count(), DISCORD_MAX_CHARS);
+    }
+
+    #[tokio::test]
+    async fn failing_sink_does_not_stop_the_others() {
+        let (base, received) = stub_server().await;
+        let sinks = [
+            // Nothing listens on port 9 (discard)
+            sink(AlertSinkKind::Slack, "http://127.0.0.1:9/slack".to_string()),
+            sink(AlertSinkKind::Slack, format!("{}/slack", base)),
+        ];
+        deliver(&sinks, &[alert(AlertKind::SubmissionFailures, "10 submissions in a row failed")], 0).await;
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+}
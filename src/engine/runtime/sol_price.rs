@@ -1,12 +1,57 @@
-use std::sync::Arc;
-use tokio::sync::Mutex;
+//! SOL/USD price oracle. Jupiter (a 1 SOL -> USDC quote), Pyth and Switchboard price accounts
+//! and CoinGecko are polled every `refresh_interval_secs`; the price is the median of the
+//! sources that answered within `max_source_age_secs`. `fees.sol_price_usd` is only used until
+//! the first source answers.
+//!
+//! Non-SOL instruments convert costs with this price, so they are not traded while it is older
+//! than `stale_after_secs` (see `sol_price_blocks`).
+
+use chrono::Utc;
+use jupiter_swap_api_client::quote::QuoteRequest;
 use once_cell::sync::Lazy;
 use serde::Deserialize;
-use chrono::Utc;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+use crate::app::config::{PriceSourceKind, SolPriceConfig};
+use crate::*;
 
-/// Shared state for SOL price (fetched from CoinGecko)
+/// Shared state for SOL price (median of the oracle sources)
 pub static SOL_PRICE: Lazy<Arc<Mutex<Option<f64>>>> = Lazy::new(|| Arc::new(Mutex::new(None)));
 
+/// When the newest source in SOL_PRICE was observed
+static SOL_PRICE_UPDATED: std::sync::RwLock<Option<Instant>> = std::sync::RwLock::new(None);
+
+/// Sources that made up SOL_PRICE, e.g. `median(jupiter, pyth)`
+static SOL_PRICE_SOURCES: std::sync::RwLock<String> = std::sync::RwLock::new(String::new());
+
+/// Last price of each source and when it was observed (publish time for on-chain feeds).
+static OBSERVATIONS: Lazy<std::sync::Mutex<HashMap<PriceSourceKind, (f64, Instant)>>> =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Bounds one source's fetch, including CoinGecko's retries.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+/// Pyth prices whose confidence interval is wider than this share of the price are ignored.
+const PYTH_MAX_CONF_RATIO: f64 = 0.01;
+
+/// `PriceUpdateV2`: discriminator, write authority, then the verification level
+/// (`Partial { num_signatures: u8 }` = 0, `Full` = 1) and the price feed message.
+const PYTH_LEVEL_OFFSET: usize = 8 + 32;
+/// Offsets in the price feed message: feed id, then price, conf, exponent, publish time.
+const PYTH_PRICE: usize = 32;
+const PYTH_CONF: usize = 40;
+const PYTH_EXPONENT: usize = 48;
+const PYTH_PUBLISH_TIME: usize = 52;
+
+/// `PullFeedAccountData`: discriminator and 32 oracle submissions of 64 bytes, then feed
+/// settings up to `last_update_timestamp`; `result.value` is an i128 scaled by 1e18.
+const SWITCHBOARD_LAST_UPDATE: usize = 2216;
+const SWITCHBOARD_RESULT_VALUE: usize = 2264;
+
 /// CoinGecko API response structure
 #[derive(Debug, Deserialize)]
 struct CoinGeckoResponse {
@@ -22,16 +67,16 @@ struct CoinGeckoPrice {
 pub async fn fetch_sol_price_from_coingecko() -> Result<f64, anyhow::Error> {
     const MAX_RETRIES: u32 = 3;
     const INITIAL_DELAY_MS: u64 = 1000; // 1 second
-    
+
     let url = "https://api.coingecko.com/api/v3/simple/price?ids=solana&vs_currencies=usd";
-    
+
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(10))
         .user_agent("Jupiter-Arbitrage-Bot/1.0") // CoinGecko requires User-Agent header
         .build()?;
-    
+
     let mut last_error = None;
-    
+
     for attempt in 0..=MAX_RETRIES {
         let response = match client
             .get(url)
@@ -50,7 +95,7 @@ pub async fn fetch_sol_price_from_coingecko() -> Result<f64, anyhow::Error> {
                 return Err(anyhow::anyhow!("Failed after {} retries: {}", MAX_RETRIES + 1, last_error.unwrap()));
             }
         };
-        
+
         if response.status().is_success() {
             match response.json::<CoinGeckoResponse>().await {
                 Ok(data) => return Ok(data.solana.usd),
@@ -67,12 +112,12 @@ pub async fn fetch_sol_price_from_coingecko() -> Result<f64, anyhow::Error> {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
             last_error = Some(format!("HTTP {}: {}", status, error_text));
-            
+
             // Don't retry on 403/401 (authentication errors) or 404
             if status == 403 || status == 401 || status == 404 {
                 return Err(anyhow::anyhow!("CoinGecko API error: {}", last_error.unwrap()));
             }
-            
+
             // Retry on 429 (rate limit) or 5xx (server errors)
             if attempt < MAX_RETRIES && (status == 429 || status.as_u16() >= 500) {
                 let delay_ms = INITIAL_DELAY_MS * (1 << attempt);
@@ -81,14 +126,142 @@ pub async fn fetch_sol_price_from_coingecko() -> Result<f64, anyhow::Error> {
             }
         }
     }
-    
+
     Err(anyhow::anyhow!("Failed after {} retries: {}", MAX_RETRIES + 1, last_error.unwrap_or_else(|| "Unknown error".to_string())))
 }
 
+/// SOL price in USDC from a 1 SOL -> USDC Jupiter quote.
+async fn fetch_sol_price_from_jupiter() -> Result<f64, anyhow::Error> {
+    let request = QuoteRequest {
+        amount: 1_000_000_000,
+        input_mint: WSOL,
+        output_mint: USDC_MINT,
+        ..QuoteRequest::default()
+    };
+    let quote = observe_jupiter("quote", JUPITER_CLIENT.quote(&request)).await?;
+    Ok(quote.out_amount as f64 / 1e6)
+}
+
+/// Price and publish time (unix seconds) from a Pyth price update account.
+async fn fetch_sol_price_from_pyth(account: &Pubkey) -> Result<(f64, i64), anyhow::Error> {
+    decode_pyth_price(&RPC_CLIENT.get_account_data(account).await?)
+}
+
+fn decode_pyth_price(data: &[u8]) -> Result<(f64, i64), anyhow::Error> {
+    let message = match data.get(PYTH_LEVEL_OFFSET) {
+        Some(1) => PYTH_LEVEL_OFFSET + 1,
+        Some(0) => return Err(anyhow::anyhow!("price update is only partially verified")),
+        _ => return Err(anyhow::anyhow!("not a Pyth price update account")),
+    };
+    let field = |offset: usize, len: usize| data.get(message + offset..message + offset + len);
+    let (Some(price), Some(conf), Some(exponent), Some(publish_time)) = (
+        field(PYTH_PRICE, 8).map(|b| i64::from_le_bytes(b.try_into().unwrap())),
+        field(PYTH_CONF, 8).map(|b| u64::from_le_bytes(b.try_into().unwrap())),
+        field(PYTH_EXPONENT, 4).map(|b| i32::from_le_bytes(b.try_into().unwrap())),
+        field(PYTH_PUBLISH_TIME, 8).map(|b| i64::from_le_bytes(b.try_into().unwrap())),
+    ) else {
+        return Err(anyhow::anyhow!("Pyth account too short ({} bytes)", data.len()));
+    };
+    let scale = 10_f64.powi(exponent);
+    let (price, conf) = (price as f64 * scale, conf as f64 * scale);
+    if conf > price * PYTH_MAX_CONF_RATIO {
+        return Err(anyhow::anyhow!("confidence ±{:.4} too wide for price {:.4}", conf, price));
+    }
+    Ok((price, publish_time))
+}
+
+/// Price and last update time (unix seconds) from a Switchboard on-demand pull feed.
+async fn fetch_sol_price_from_switchboard(account: &Pubkey) -> Result<(f64, i64), anyhow::Error> {
+    decode_switchboard_price(&RPC_CLIENT.get_account_data(account).await?)
+}
+
+fn decode_switchboard_price(data: &[u8]) -> Result<(f64, i64), anyhow::Error> {
+    let (Some(updated_at), Some(value)) = (
+        data.get(SWITCHBOARD_LAST_UPDATE..SWITCHBOARD_LAST_UPDATE + 8)
+            .map(|b| i64::from_le_bytes(b.try_into().unwrap())),
+        data.get(SWITCHBOARD_RESULT_VALUE..SWITCHBOARD_RESULT_VALUE + 16)
+            .map(|b| i128::from_le_bytes(b.try_into().unwrap())),
+    ) else {
+        return Err(anyhow::anyhow!("Switchboard account too short ({} bytes)", data.len()));
+    };
+    Ok((value as f64 / 1e18, updated_at))
+}
+
+/// One source's price and when it was observed.
+async fn fetch_source(source: PriceSourceKind, config: &SolPriceConfig) -> Result<(f64, Instant), anyhow::Error> {
+    let now = Instant::now();
+    // On-chain feeds carry their publish time; an old one counts as an old observation
+    let published = |(price, unix_secs): (f64, i64)| {
+        let age = Duration::from_secs((Utc::now().timestamp() - unix_secs).max(0) as u64);
+        (price, now.checked_sub(age).unwrap_or(now))
+    };
+    let (price, observed_at) = match source {
+        PriceSourceKind::Jupiter => (fetch_sol_price_from_jupiter().await?, now),
+        PriceSourceKind::Coingecko => (fetch_sol_price_from_coingecko().await?, now),
+        PriceSourceKind::Pyth => published(fetch_sol_price_from_pyth(&config.pyth_account.parse()?).await?),
+        PriceSourceKind::Switchboard => {
+            let account = config.switchboard_account.as_deref().unwrap_or_default().parse()?;
+            published(fetch_sol_price_from_switchboard(&account).await?)
+        }
+    };
+    if !price.is_finite() || price <= 0.0 {
+        return Err(anyhow::anyhow!("implausible price {}", price));
+    }
+    Ok((price, observed_at))
+}
+
+/// Median of `prices`; the mean of the middle two for an even count. None when empty.
+fn median(prices: &[f64]) -> Option<f64> {
+    let mut sorted = prices.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let mid = sorted.len() / 2;
+    match sorted.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((sorted[mid - 1] + sorted[mid]) / 2.0),
+        _ => Some(sorted[mid]),
+    }
+}
+
+fn source_name(source: PriceSourceKind) -> &'static str {
+    match source {
+        PriceSourceKind::Jupiter => "jupiter",
+        PriceSourceKind::Pyth => "pyth",
+        PriceSourceKind::Switchboard => "switchboard",
+        PriceSourceKind::Coingecko => "coingecko",
+    }
+}
+
 /// Update the shared SOL price state
-pub async fn update_sol_price(price: f64) {
+pub async fn update_sol_price(price: f64, observed_at: Instant, sources: String) {
     let mut price_guard = SOL_PRICE.lock().await;
     *price_guard = Some(price);
+    *SOL_PRICE_UPDATED.write().unwrap() = Some(observed_at);
+    *SOL_PRICE_SOURCES.write().unwrap() = sources;
+}
+
+/// Age of the newest source in the current price; None while only the fallback is available
+pub fn sol_price_age() -> Option<std::time::Duration> {
+    SOL_PRICE_UPDATED.read().unwrap().map(|at| at.elapsed())
+}
+
+/// Where the current SOL price comes from
+pub fn sol_price_source() -> String {
+    match SOL_PRICE_UPDATED.read().unwrap().is_some() {
+        true => SOL_PRICE_SOURCES.read().unwrap().clone(),
+        false => "config fallback".to_string(),
+    }
+}
+
+/// Older than `stale_after_secs`, or never fetched.
+pub fn sol_price_stale() -> bool {
+    let stale_after = CONFIG.sol_price.stale_after_secs;
+    stale_after > 0 && sol_price_age().is_none_or(|age| age.as_secs() > stale_after)
+}
+
+/// Whether trading `mint` must wait for a fresh SOL price: every instrument but SOL itself
+/// converts its costs with it.
+pub fn sol_price_blocks(mint: &str) -> bool {
+    mint != WSOL.to_string() && sol_price_stale()
 }
 
 /// Get the current SOL price (with fallback to config value)
@@ -97,53 +270,154 @@ pub async fn get_sol_price_usdc(fallback_price: f64) -> f64 {
     price_guard.unwrap_or(fallback_price)
 }
 
-/// Background task to fetch SOL price every 1 hour
-pub async fn start_sol_price_fetcher(fallback_price: f64) {
-    println!("[SOL Price] Starting price fetcher (updates every 1 hour)");
-    
-    // Fetch immediately on startup
-    match fetch_sol_price_from_coingecko().await {
-        Ok(price) => {
-            update_sol_price(price).await;
-            println!(
-                "[{}] [SOL Price] ✅ Fetched SOL price: ${:.2} USDC",
-                Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                price
-            );
-        }
-        Err(e) => {
-            eprintln!(
-                "[{}] [SOL Price] ⚠️ Failed to fetch SOL price: {}. Using fallback: ${:.2}",
-                Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                e,
-                fallback_price
-            );
-        }
-    }
-    
-    // Then fetch every 1 hour
-    let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(3600)); // 1 hour = 3600 seconds
-    
+/// Poll every configured source and keep the median of the fresh ones in SOL_PRICE.
+pub async fn start_sol_price_oracle() {
+    let config = &CONFIG.sol_price;
+    let sources: Vec<&str> = config.sources.iter().map(|s| source_name(*s)).collect();
+    info!(
+        sources = %sources.join(","),
+        refresh_interval_secs = config.refresh_interval_secs,
+        fallback_price = FEES.sol_usd,
+        "Starting SOL price oracle"
+    );
+
+    let max_source_age = Duration::from_secs(config.max_source_age_secs);
+    let coingecko_interval = Duration::from_secs(config.coingecko_interval_secs);
+    let mut last_coingecko: Option<Instant> = None;
+    let mut failing: HashSet<PriceSourceKind> = HashSet::new();
+    let mut was_stale = false;
+    let mut interval = tokio::time::interval(Duration::from_secs(config.refresh_interval_secs));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
     loop {
         interval.tick().await;
-        
-        match fetch_sol_price_from_coingecko().await {
-            Ok(price) => {
-                update_sol_price(price).await;
-                println!(
-                    "[{}] [SOL Price] ✅ Updated SOL price: ${:.2} USDC",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                    price
-                );
-            }
-            Err(e) => {
-                eprintln!(
-                    "[{}] [SOL Price] ⚠️ Failed to fetch SOL price: {}. Keeping previous value or using fallback: ${:.2}",
-                    Utc::now().format("%Y-%m-%d %H:%M:%S"),
-                    e,
-                    fallback_price
-                );
+
+        let due: Vec<PriceSourceKind> = config
+            .sources
+            .iter()
+            .copied()
+            .filter(|s| {
+                *s != PriceSourceKind::Coingecko || last_coingecko.is_none_or(|at| at.elapsed() >= coingecko_interval)
+            })
+            .collect();
+        if due.contains(&PriceSourceKind::Coingecko) {
+            // Counted on failure too, so errors do not bring us closer to the rate limit
+            last_coingecko = Some(Instant::now());
+        }
+        let fetches = due.iter().map(|&source| async move {
+            let result = match tokio::time::timeout(FETCH_TIMEOUT, fetch_source(source, config)).await {
+                Ok(result) => result,
+                Err(_) => Err(anyhow::anyhow!("timed out")),
+            };
+            (source, result)
+        });
+        for (source, result) in futures::future::join_all(fetches).await {
+            let name = source_name(source);
+            match result {
+                Ok((price, observed_at)) => {
+                    if failing.remove(&source) {
+                        info!(source = name, price, "SOL price source recovered");
+                    }
+                    SOL_PRICE_USD.with_label_values(&[name]).set(price);
+                    OBSERVATIONS.lock().unwrap().insert(source, (price, observed_at));
+                }
+                Err(e) if failing.insert(source) => warn!(source = name, error = %e, "SOL price source failing"),
+                Err(e) => debug!(source = name, error = %e, "SOL price source still failing"),
             }
         }
+
+        let fresh: Vec<(PriceSourceKind, f64, Instant)> = OBSERVATIONS
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, (_, at))| at.elapsed() <= max_source_age)
+            .map(|(source, (price, at))| (*source, *price, *at))
+            .collect();
+        let prices: Vec<f64> = fresh.iter().map(|(_, price, _)| *price).collect();
+        if let Some(median) = median(&prices) {
+            let newest = fresh.iter().map(|(_, _, at)| *at).max().unwrap();
+            let used: Vec<&str> = fresh.iter().map(|(source, _, _)| source_name(*source)).collect();
+            SOL_PRICE_USD.with_label_values(&["median"]).set(median);
+            update_sol_price(median, newest, format!("median({})", used.join(", "))).await;
+        }
+
+        let stale = sol_price_stale();
+        if stale && !was_stale {
+            warn!(
+                age_secs = sol_price_age().map(|age| age.as_secs()),
+                stale_after_secs = config.stale_after_secs,
+                "SOL price stale; non-SOL instruments paused"
+            );
+        } else if !stale && was_stale {
+            info!(price = get_sol_price_usdc(FEES.sol_usd).await, source = %sol_price_source(), "SOL price fresh; non-SOL instruments resumed");
+        }
+        was_stale = stale;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `PriceUpdateV2` bytes with the given verification level and feed message fields.
+    fn pyth_fixture(level: u8, price: i64, conf: u64, exponent: i32, publish_time: i64) -> Vec<u8> {
+        let mut data = vec![0u8; PYTH_LEVEL_OFFSET];
+        data.push(level);
+        let mut message = vec![0xAB; 32]; // feed id
+        message.extend(price.to_le_bytes());
+        message.extend(conf.to_le_bytes());
+        message.extend(exponent.to_le_bytes());
+        message.extend(publish_time.to_le_bytes());
+        message.extend(publish_time.to_le_bytes()); // prev_publish_time
+        data.extend(message);
+        data
+    }
+
+    fn switchboard_fixture(value: i128, updated_at: i64) -> Vec<u8> {
+        let mut data = vec![0u8; SWITCHBOARD_RESULT_VALUE + 16 + 64];
+        data[SWITCHBOARD_LAST_UPDATE..SWITCHBOARD_LAST_UPDATE + 8].copy_from_slice(&updated_at.to_le_bytes());
+        data[SWITCHBOARD_RESULT_VALUE..SWITCHBOARD_RESULT_VALUE + 16].copy_from_slice(&value.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn decodes_pyth_price_update() {
+        // $187.53 with ±$0.09, exponent -8
+        let data = pyth_fixture(1, 18_753_000_000, 9_000_000, -8, 1_760_000_000);
+        let (price, publish_time) = decode_pyth_price(&data).unwrap();
+        assert!((price - 187.53).abs() < 1e-9);
+        assert_eq!(publish_time, 1_760_000_000);
+    }
+
+    #[test]
+    fn rejects_bad_pyth_accounts() {
+        // Partially verified
+        assert!(decode_pyth_price(&pyth_fixture(0, 18_753_000_000, 9_000_000, -8, 0)).is_err());
+        // Confidence over 1% of the price
+        assert!(decode_pyth_price(&pyth_fixture(1, 18_753_000_000, 200_000_000, -8, 0)).is_err());
+        // Truncated before the publish time
+        let data = pyth_fixture(1, 18_753_000_000, 9_000_000, -8, 0);
+        assert!(decode_pyth_price(&data[..PYTH_LEVEL_OFFSET + 1 + PYTH_PUBLISH_TIME + 4]).is_err());
+        // Not a price update
+        assert!(decode_pyth_price(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn decodes_switchboard_feed() {
+        let data = switchboard_fixture(187_530_000_000_000_000_000, 1_760_000_123);
+        let (price, updated_at) = decode_switchboard_price(&data).unwrap();
+        assert!((price - 187.53).abs() < 1e-9);
+        assert_eq!(updated_at, 1_760_000_123);
+        assert!(decode_switchboard_price(&data[..SWITCHBOARD_RESULT_VALUE + 8]).is_err());
+    }
+
+    #[test]
+    fn median_of_sources() {
+        assert_eq!(median(&[]), None);
+        assert_eq!(median(&[187.0]), Some(187.0));
+        assert_eq!(median(&[190.0, 186.0]), Some(188.0));
+        // An outlier does not move the median
+        assert_eq!(median(&[187.0, 1_000.0, 186.0]), Some(187.0));
+        assert_eq!(median(&[188.0, 186.0, 187.0, 0.5]), Some(186.5));
     }
 }
@@ -0,0 +1,196 @@
+//! Token-2022 mint extension parsing (TLV after the 165-byte base + account type byte).
+//! Only the extensions that matter for a round-trip arbitrage are decoded.
+
+use serde::{Deserialize, Serialize};
+
+/// Extensions start after the base mint padded to token-account size and the account type.
+const EXTENSIONS_OFFSET: usize = 166;
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXT_NON_TRANSFERABLE: u16 = 9;
+const EXT_TRANSFER_HOOK: u16 = 14;
+
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TransferFee {
+    pub basis_points: u16,
+    pub maximum_fee: u64,
+}
+
+impl TransferFee {
+    /// Fee withheld on a transfer of `amount` (rounded up, capped), as Token-2022 computes it.
+    pub fn fee_for(&self, amount: u64) -> u64 {
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        (fee as u64).min(self.maximum_fee)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MintExtensions {
+    pub transfer_fee: Option<TransferFee>,
+    pub transfer_hook: bool,
+    pub non_transferable: bool,
+    pub default_frozen: bool,
+}
+
+impl MintExtensions {
+    /// Why a round trip through this mint cannot work, if it cannot.
+    pub fn unsupported_reason(&self) -> Option<String> {
+        if self.transfer_hook {
+            Some("transfer hook".to_string())
+        } else if self.non_transferable {
+            Some("non-transferable".to_string())
+        } else if self.default_frozen {
+            Some("accounts frozen by default".to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Decode the extensions of a Token-2022 mint. `epoch` selects the active transfer fee; when
+/// a newer fee is scheduled for a later epoch the higher of the two is used.
+pub fn parse_mint_extensions(data: &[u8], epoch: u64) -> MintExtensions {
+    let mut extensions = MintExtensions::default();
+    if data.len() <= EXTENSIONS_OFFSET || data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_MINT {
+        return extensions;
+    }
+
+    let mut offset = EXTENSIONS_OFFSET;
+    while offset + 4 <= data.len() {
+        let ext_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let len = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+        let Some(value) = data.get(offset + 4..offset + 4 + len) else {
+            break;
+        };
+        match ext_type {
+            // Uninitialized padding ends the TLV list
+            0 => break,
+            EXT_TRANSFER_FEE_CONFIG => extensions.transfer_fee = decode_transfer_fee(value, epoch),
+            EXT_DEFAULT_ACCOUNT_STATE => {
+                extensions.default_frozen = value.first() == Some(&ACCOUNT_STATE_FROZEN)
+            }
+            EXT_NON_TRANSFERABLE => extensions.non_transferable = true,
+            // A hook with no program id set is inert
+            EXT_TRANSFER_HOOK => {
+                extensions.transfer_hook = value.get(32..64).is_some_and(|p| p.iter().any(|b| *b != 0))
+            }
+            _ => {}
+        }
+        offset += 4 + len;
+    }
+    extensions
+}
+
+/// TransferFeeConfig: two authorities (64) + withheld amount (8), then older and newer fees,
+/// each `{epoch: u64, maximum_fee: u64, basis_points: u16}`.
+fn decode_transfer_fee(value: &[u8], epoch: u64) -> Option<TransferFee> {
+    let read = |at: usize| -> Option<(u64, TransferFee)> {
+        let fee_epoch = u64::from_le_bytes(value.get(at..at + 8)?.try_into().ok()?);
+        let maximum_fee = u64::from_le_bytes(value.get(at + 8..at + 16)?.try_into().ok()?);
+        let basis_points = u16::from_le_bytes(value.get(at + 16..at + 18)?.try_into().ok()?);
+        Some((fee_epoch, TransferFee { basis_points, maximum_fee }))
+    };
+    let (_, older) = read(72)?;
+    let (newer_epoch, newer) = read(90)?;
+
+    let fee = if epoch >= newer_epoch {
+        newer
+    } else if newer.basis_points >= older.basis_points {
+        newer
+    } else {
+        older
+    };
+    (fee.basis_points > 0).then_some(fee)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mint_with(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; EXTENSIONS_OFFSET];
+        data[ACCOUNT_TYPE_OFFSET] = ACCOUNT_TYPE_MINT;
+        for (ext_type, value) in extensions {
+            data.extend(ext_type.to_le_bytes());
+            data.extend((value.len() as u16).to_le_bytes());
+            data.extend(value);
+        }
+        data
+    }
+
+    fn transfer_fee_config(older: (u64, u64, u16), newer: (u64, u64, u16)) -> Vec<u8> {
+        let mut value = vec![0u8; 72];
+        for (epoch, maximum_fee, basis_points) in [older, newer] {
+            value.extend(epoch.to_le_bytes());
+            value.extend(maximum_fee.to_le_bytes());
+            value.extend(basis_points.to_le_bytes());
+        }
+        value
+    }
+
+    #[test]
+    fn plain_mint_has_no_extensions() {
+        let extensions = parse_mint_extensions(&[0u8; 82], 500);
+        assert!(extensions.transfer_fee.is_none());
+        assert!(extensions.unsupported_reason().is_none());
+    }
+
+    #[test]
+    fn selects_transfer_fee_by_epoch() {
+        let data = mint_with(&[(EXT_TRANSFER_FEE_CONFIG, transfer_fee_config((0, 1_000, 50), (600, 2_000, 80)))]);
+        let active = TransferFee { basis_points: 80, maximum_fee: 2_000 };
+        assert_eq!(parse_mint_extensions(&data, 600).transfer_fee, Some(active));
+        // The scheduled fee is higher, so it is assumed before it takes effect
+        assert_eq!(parse_mint_extensions(&data, 500).transfer_fee, Some(active));
+
+        let lowering = mint_with(&[(EXT_TRANSFER_FEE_CONFIG, transfer_fee_config((0, 1_000, 50), (600, 2_000, 20)))]);
+        let older = TransferFee { basis_points: 50, maximum_fee: 1_000 };
+        assert_eq!(parse_mint_extensions(&lowering, 500).transfer_fee, Some(older));
+
+        let zero = mint_with(&[(EXT_TRANSFER_FEE_CONFIG, transfer_fee_config((0, 0, 0), (0, 0, 0)))]);
+        assert!(parse_mint_extensions(&zero, 500).transfer_fee.is_none());
+    }
+
+    #[test]
+    fn flags_unsupported_extensions() {
+        let mut hook = vec![0u8; 64];
+        hook[40] = 1;
+        let hooked = parse_mint_extensions(&mint_with(&[(EXT_TRANSFER_HOOK, hook)]), 0);
+        assert_eq!(hooked.unsupported_reason().as_deref(), Some("transfer hook"));
+
+        let inert = parse_mint_extensions(&mint_with(&[(EXT_TRANSFER_HOOK, vec![0u8; 64])]), 0);
+        assert!(inert.unsupported_reason().is_none());
+
+        let frozen = parse_mint_extensions(&mint_with(&[(EXT_DEFAULT_ACCOUNT_STATE, vec![ACCOUNT_STATE_FROZEN])]), 0);
+        assert!(frozen.default_frozen);
+
+        let soulbound = parse_mint_extensions(&mint_with(&[(EXT_NON_TRANSFERABLE, Vec::new())]), 0);
+        assert_eq!(soulbound.unsupported_reason().as_deref(), Some("non-transferable"));
+    }
+
+    #[test]
+    fn reads_past_other_extensions_and_stops_at_padding() {
+        let data = mint_with(&[
+            (3, vec![7u8; 32]),
+            (EXT_NON_TRANSFERABLE, Vec::new()),
+            (0, Vec::new()),
+            (EXT_TRANSFER_HOOK, vec![1u8; 64]),
+        ]);
+        let extensions = parse_mint_extensions(&data, 0);
+        assert!(extensions.non_transferable);
+        assert!(!extensions.transfer_hook);
+    }
+
+    #[test]
+    fn fee_rounds_up_and_caps() {
+        let fee = TransferFee { basis_points: 30, maximum_fee: 100 };
+        assert_eq!(fee.fee_for(1_000), 3);
+        assert_eq!(fee.fee_for(1_001), 4);
+        assert_eq!(fee.fee_for(1_000_000), 100);
+    }
+}
@@ -0,0 +1,149 @@
+//! USD and SOL value of any mint, so fees, tips and profits of non-stable instruments (JUP,
+//! BONK, ...) are converted at their own price instead of as if every token were a dollar.
+//!
+//! SOL comes from the SOL price oracle and USDC is the unit. Every other instrument and
+//! rebalance mint is priced from a `probe_usd` USDC -> token Jupiter quote every
+//! `refresh_interval_secs`; prices older than `stale_after_secs` count as unknown, and an
+//! instrument without a price is not traded.
+
+use jupiter_swap_api_client::quote::QuoteRequest;
+use once_cell::sync::Lazy;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::*;
+
+/// USD price per whole token and when it was read.
+static TOKEN_PRICES: Lazy<RwLock<HashMap<String, (f64, Instant)>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// USD per whole token; None when the price is unknown or stale.
+pub async fn token_price_usd(mint: &str) -> Option<f64> {
+    if mint == USDC_MINT.to_string() {
+        return Some(1.0);
+    }
+    if mint == WSOL.to_string() {
+        return Some(get_sol_price_usdc(FEES.sol_usd).await);
+    }
+    let max_age = Duration::from_secs(CONFIG.token_prices.stale_after_secs);
+    TOKEN_PRICES
+        .read()
+        .unwrap()
+        .get(mint)
+        .filter(|(_, at)| at.elapsed() <= max_age)
+        .map(|(price, _)| *price)
+}
+
+/// SOL per whole token (1.0 for SOL); None when the price is unknown or stale.
+pub async fn token_price_sol(mint: &str) -> Option<f64> {
+    if mint == WSOL.to_string() {
+        return Some(1.0);
+    }
+    let sol_usd = get_sol_price_usdc(FEES.sol_usd).await;
+    token_price_usd(mint).await.map(|usd| usd / sol_usd)
+}
+
+/// USD value of `raw` base units of `mint`.
+pub async fn token_value_usd(mint: &str, raw: u64) -> Option<f64> {
+    let decimals = token_meta(mint)?.decimals;
+    let price = token_price_usd(mint).await?;
+    Some(raw as f64 / 10_f64.powi(decimals as i32) * price)
+}
+
+/// Min profit of instrument `mint` in token units: its `min_profit_usd` at the current price
+/// when set and priced, else `min_profit` (`min_profit_quote_units`).
+pub async fn min_profit_units(mint: &str, min_profit: f64) -> f64 {
+    let min_profit_usd = base_token_config(mint).and_then(|t| t.min_profit_usd);
+    let price_usd = match min_profit_usd {
+        Some(_) => token_price_usd(mint).await,
+        None => None,
+    };
+    min_profit_at_price(min_profit, min_profit_usd, price_usd)
+}
+
+/// `min_profit_usd` in tokens at `price_usd`, else `min_profit`.
+fn min_profit_at_price(min_profit: f64, min_profit_usd: Option<f64>, price_usd: Option<f64>) -> f64 {
+    match (min_profit_usd, price_usd) {
+        (Some(usd), Some(price)) if price > 0.0 => usd / price,
+        _ => min_profit,
+    }
+}
+
+/// Mints priced by the refresher: instruments and rebalance targets other than SOL and USDC.
+fn priced_mints() -> Vec<String> {
+    let skip = [USDC_MINT.to_string(), WSOL.to_string()];
+    let mut seen = HashSet::new();
+    BASE_TOKENS
+        .iter()
+        .map(|t| t.mint.clone())
+        .chain(CONFIG.rebalance.target_weights.iter().map(|w| w.mint.clone()))
+        .filter(|mint| !skip.contains(mint) && seen.insert(mint.clone()))
+        .collect()
+}
+
+/// USD per whole token from a `probe_usd` USDC -> `mint` quote.
+async fn fetch_token_price(mint: &str) -> Result<f64, anyhow::Error> {
+    let meta = token_meta(mint).ok_or_else(|| anyhow::anyhow!("decimals not resolved"))?;
+    let probe_usd = CONFIG.token_prices.probe_usd;
+    let request = QuoteRequest {
+        amount: (probe_usd * 1e6) as u64,
+        input_mint: USDC_MINT,
+        output_mint: mint.parse::<Pubkey>()?,
+        slippage_bps: 0,
+        ..QuoteRequest::default()
+    };
+    let quote = observe_jupiter("quote", JUPITER_CLIENT.quote(&request)).await?;
+    let tokens = quote.out_amount as f64 / 10_f64.powi(meta.decimals as i32);
+    if tokens <= 0.0 {
+        return Err(anyhow::anyhow!("quote returned no output"));
+    }
+    Ok(probe_usd / tokens)
+}
+
+/// Refresh every priced mint once.
+pub async fn refresh_token_prices() {
+    let mints = priced_mints();
+    let fetches = mints.iter().map(|mint| async move { (mint, fetch_token_price(mint).await) });
+    for (mint, result) in futures::future::join_all(fetches).await {
+        match result {
+            Ok(price) => {
+                TOKEN_PRICE_USD.with_label_values(&[token_symbol(mint).as_str()]).set(price);
+                TOKEN_PRICES.write().unwrap().insert(mint.clone(), (price, Instant::now()));
+                debug!(token = %token_symbol(mint), price, "Token price updated");
+            }
+            Err(e) => warn!(token = %token_symbol(mint), %mint, error = %e, "Token price refresh failed"),
+        }
+    }
+}
+
+/// Periodic refresh; runs forever.
+pub async fn start_token_price_refresher(interval_secs: u64) {
+    info!(mints = priced_mints().len(), interval_secs, "Starting token price refresher");
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        refresh_token_prices().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_profit_follows_usd_price() {
+        // $0.50 at $0.25 per token
+        assert_eq!(min_profit_at_price(1.0, Some(0.5), Some(0.25)), 2.0);
+    }
+
+    #[test]
+    fn min_profit_falls_back_to_token_units() {
+        assert_eq!(min_profit_at_price(1.0, None, Some(0.25)), 1.0);
+        // Not priced yet
+        assert_eq!(min_profit_at_price(1.0, Some(0.5), None), 1.0);
+        assert_eq!(min_profit_at_price(1.0, Some(0.5), Some(0.0)), 1.0);
+    }
+}
@@ -0,0 +1,282 @@
+//! Token metadata resolved on-chain: decimals and owner program from the mint account
+//! (SPL Token or Token-2022), symbol from Metaplex metadata. Entries are cached in
+//! `token_registry.json`; decimals are immutable, so a cached entry stays confirmed.
+//! Token-2022 extensions (transfer fee, hooks, ...) can change and are refreshed at startup.
+
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use super::token_extensions::{parse_mint_extensions, TransferFee};
+use crate::{
+    instrument_targets, BASE_TOKENS, CONFIG, POPULAR_TOKEN_INFO, RPC_CLIENT, TARGET_TOKEN,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+
+const REGISTRY_CACHE_PATH: &str = "token_registry.json";
+
+pub const METADATA_PROGRAM: Pubkey =
+    Pubkey::from_str_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Base mint layout (shared by Token-2022): decimals at 44, is_initialized at 45.
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_INITIALIZED_OFFSET: usize = 45;
+/// Metaplex metadata: key (1) + update_authority (32) + mint (32), then borsh name and symbol.
+const METADATA_NAME_OFFSET: usize = 65;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMeta {
+    pub mint: String,
+    pub decimals: u8,
+    pub symbol: String,
+    pub token_program: String,
+    /// Token-2022 transfer fee withheld on every transfer of this token.
+    #[serde(default)]
+    pub transfer_fee: Option<TransferFee>,
+    /// Set when an extension breaks a round trip (transfer hook, non-transferable, frozen).
+    #[serde(default)]
+    pub unsupported: Option<String>,
+}
+
+impl TokenMeta {
+    pub fn token_program(&self) -> Pubkey {
+        self.token_program.parse().unwrap_or(TOKEN_PROGRAM_ID)
+    }
+
+    /// Transfer fee on `amount`, 0 for tokens without the extension.
+    pub fn transfer_fee_for(&self, amount: u64) -> u64 {
+        self.transfer_fee.map(|f| f.fee_for(amount)).unwrap_or(0)
+    }
+}
+
+static TOKEN_REGISTRY: Lazy<RwLock<HashMap<String, TokenMeta>>> =
+    Lazy::new(|| RwLock::new(load_cache()));
+
+/// Mints whose lookup failed, and when; `filter_tradable_mints` does not retry them until
+/// `RESOLVE_RETRY_AFTER` has passed.
+static RESOLVE_FAILURES: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+const RESOLVE_RETRY_AFTER: Duration = Duration::from_secs(600);
+
+fn load_cache() -> HashMap<String, TokenMeta> {
+    let Ok(raw) = std::fs::read_to_string(REGISTRY_CACHE_PATH) else {
+        return HashMap::new();
+    };
+    match serde_json::from_str::<Vec<TokenMeta>>(&raw) {
+        Ok(entries) => entries.into_iter().map(|t| (t.mint.clone(), t)).collect(),
+        Err(e) => {
+            warn!(error = %e, path = REGISTRY_CACHE_PATH, "Ignoring unreadable token registry cache");
+            HashMap::new()
+        }
+    }
+}
+
+fn save_cache() {
+    let entries: Vec<TokenMeta> = TOKEN_REGISTRY.read().unwrap().values().cloned().collect();
+    match serde_json::to_string_pretty(&entries) {
+        Ok(json) => {
+            if let Err(e) = std::fs::write(REGISTRY_CACHE_PATH, json) {
+                warn!(error = %e, path = REGISTRY_CACHE_PATH, "Failed to write token registry cache");
+            }
+        }
+        Err(e) => warn!(error = %e, "Failed to serialize token registry"),
+    }
+}
+
+/// Confirmed metadata for `mint`, if it has been resolved (this run or a previous one).
+pub fn token_meta(mint: &str) -> Option<TokenMeta> {
+    TOKEN_REGISTRY.read().unwrap().get(mint).cloned()
+}
+
+/// Metadata of a token that can be traded as an instrument or route target: confirmed
+/// decimals and no extension that breaks a round trip. None means do not trade it.
+pub fn instrument_meta(mint: &str) -> Option<TokenMeta> {
+    token_meta(mint).filter(|t| t.unsupported.is_none())
+}
+
+/// Symbol for display; "UNKNOWN" for mints that have not been resolved.
+pub fn token_symbol(mint: &str) -> String {
+    token_meta(mint)
+        .map(|t| t.symbol)
+        .unwrap_or_else(|| "UNKNOWN".to_string())
+}
+
+/// Resolve `mint` from the cache or on-chain, caching the result to disk.
+pub async fn resolve_token(mint: &Pubkey) -> Result<TokenMeta, anyhow::Error> {
+    let key = mint.to_string();
+    if let Some(meta) = token_meta(&key) {
+        return Ok(meta);
+    }
+
+    refresh_token(mint).await
+}
+
+/// Re-read `mint` on-chain and update the cache.
+pub async fn refresh_token(mint: &Pubkey) -> Result<TokenMeta, anyhow::Error> {
+    let meta = fetch_token_meta(mint).await?;
+    TOKEN_REGISTRY.write().unwrap().insert(mint.to_string(), meta.clone());
+    save_cache();
+    Ok(meta)
+}
+
+/// Keep the route targets that can be traded, resolving unseen mints first (one RPC call
+/// per new mint; cached afterwards). Mints that fail to resolve are skipped without another
+/// lookup for `RESOLVE_RETRY_AFTER`.
+pub async fn filter_tradable_mints(mints: Vec<String>) -> Vec<String> {
+    let lookups = mints.into_iter().map(|mint| async move {
+        let pubkey = mint.parse::<Pubkey>().ok()?;
+        if token_meta(&mint).is_none() && recently_failed(&mint) {
+            return None;
+        }
+        let meta = match resolve_token(&pubkey).await {
+            Ok(meta) => meta,
+            Err(e) => {
+                debug!(%mint, error = %e, "Token lookup failed; skipped for a while");
+                RESOLVE_FAILURES.lock().unwrap().insert(mint, Instant::now());
+                return None;
+            }
+        };
+        meta.unsupported.is_none().then_some(mint)
+    });
+    futures::future::join_all(lookups).await.into_iter().flatten().collect()
+}
+
+fn recently_failed(mint: &str) -> bool {
+    let mut failures = RESOLVE_FAILURES.lock().unwrap();
+    match failures.get(mint) {
+        Some(at) if at.elapsed() < RESOLVE_RETRY_AFTER => true,
+        Some(_) => {
+            failures.remove(mint);
+            false
+        }
+        None => false,
+    }
+}
+
+/// Token program owning `mint` (SPL Token or Token-2022).
+pub async fn resolve_token_program(mint: &Pubkey) -> Result<Pubkey, anyhow::Error> {
+    Ok(resolve_token(mint).await?.token_program())
+}
+
+async fn fetch_token_meta(mint: &Pubkey) -> Result<TokenMeta, anyhow::Error> {
+    let (metadata_pda, _) = Pubkey::find_program_address(
+        &[b"metadata", METADATA_PROGRAM.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM,
+    );
+    let mut accounts = RPC_CLIENT
+        .get_multiple_accounts(&[*mint, metadata_pda])
+        .await?
+        .into_iter();
+    let mint_account = accounts
+        .next()
+        .flatten()
+        .ok_or_else(|| anyhow::anyhow!("Mint account {} not found", mint))?;
+    let metadata_account = accounts.next().flatten();
+
+    if mint_account.owner != TOKEN_PROGRAM_ID && mint_account.owner != TOKEN_2022_PROGRAM_ID {
+        return Err(anyhow::anyhow!("{} is not a token mint (owner {})", mint, mint_account.owner));
+    }
+    let data = &mint_account.data;
+    if data.len() <= MINT_INITIALIZED_OFFSET || data[MINT_INITIALIZED_OFFSET] != 1 {
+        return Err(anyhow::anyhow!("{} is not an initialized mint", mint));
+    }
+    let decimals = data[MINT_DECIMALS_OFFSET];
+
+    let extensions = if mint_account.owner == TOKEN_2022_PROGRAM_ID {
+        let epoch = RPC_CLIENT.get_epoch_info().await?.epoch;
+        parse_mint_extensions(data, epoch)
+    } else {
+        Default::default()
+    };
+
+    let symbol = metadata_account
+        .and_then(|acc| decode_metadata_symbol(&acc.data))
+        .or_else(|| {
+            POPULAR_TOKEN_INFO
+                .iter()
+                .find(|t| t.mint == mint.to_string())
+                .map(|t| t.symbol.to_string())
+        })
+        .unwrap_or_else(|| mint.to_string()[..4].to_string());
+
+    Ok(TokenMeta {
+        mint: mint.to_string(),
+        decimals,
+        symbol,
+        token_program: mint_account.owner.to_string(),
+        transfer_fee: extensions.transfer_fee,
+        unsupported: extensions.unsupported_reason(),
+    })
+}
+
+/// Symbol from a Metaplex metadata account (borsh strings, NUL-padded).
+fn decode_metadata_symbol(data: &[u8]) -> Option<String> {
+    let (_name, rest) = read_borsh_string(data.get(METADATA_NAME_OFFSET..)?)?;
+    let (symbol, _) = read_borsh_string(rest)?;
+    let symbol = symbol.trim_matches(char::from(0)).trim().to_string();
+    (!symbol.is_empty()).then_some(symbol)
+}
+
+fn read_borsh_string(data: &[u8]) -> Option<(String, &[u8])> {
+    let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    let bytes = data.get(4..4 + len)?;
+    Some((String::from_utf8_lossy(bytes).into_owned(), &data[4 + len..]))
+}
+
+/// Refresh every configured base token, quote target and rebalance mint at startup, falling
+/// back to the cache when RPC fails. Instruments whose decimals cannot be confirmed, or whose
+/// mint has an unsupported extension, are reported here and skipped by the trading loops.
+pub async fn init_token_registry() {
+    // Instruments, the targets they are actually quoted against (including defaults) and the
+    // rebalancer's mints, which it prices and values by their decimals
+    let mut seen = std::collections::HashSet::new();
+    let rebalance_mints = CONFIG.rebalance.enabled.then_some(&CONFIG.rebalance.target_weights);
+    let mints: Vec<String> = BASE_TOKENS
+        .iter()
+        .map(|t| t.mint.clone())
+        .chain(std::iter::once(TARGET_TOKEN.clone()))
+        .chain(BASE_TOKENS.iter().flat_map(instrument_targets))
+        .chain(rebalance_mints.into_iter().flatten().map(|w| w.mint.clone()))
+        .filter(|mint| seen.insert(mint.clone()))
+        .collect();
+
+    for mint in mints {
+        let Ok(pubkey) = mint.parse::<Pubkey>() else {
+            error!(%mint, "Invalid mint address; instrument disabled");
+            continue;
+        };
+        let refreshed = match refresh_token(&pubkey).await {
+            Ok(meta) => Ok(meta),
+            Err(e) => token_meta(&mint).ok_or(e),
+        };
+        match refreshed {
+            Ok(meta) => {
+                if let Some(known) = POPULAR_TOKEN_INFO.iter().find(|t| t.mint == mint) {
+                    if known.decimals != meta.decimals {
+                        warn!(
+                            %mint,
+                            hardcoded = known.decimals,
+                            onchain = meta.decimals,
+                            "Hardcoded token decimals differ from mint account; using on-chain value"
+                        );
+                    }
+                }
+                if let Some(reason) = &meta.unsupported {
+                    error!(%mint, symbol = %meta.symbol, %reason, "Unsupported Token-2022 extension; instrument disabled");
+                    continue;
+                }
+                info!(
+                    %mint,
+                    symbol = %meta.symbol,
+                    decimals = meta.decimals,
+                    transfer_fee_bps = meta.transfer_fee.map(|f| f.basis_points).unwrap_or(0),
+                    "Token resolved"
+                );
+            }
+            Err(e) => error!(%mint, error = %e, "Token decimals unconfirmed; instrument disabled"),
+        }
+    }
+}
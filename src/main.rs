@@ -1,12 +1,11 @@
 use std::collections::HashMap;
+use std::io::IsTerminal;
 
 use jupiter_arbitrage_bot_offchain::*;
 use chrono::Utc;
-use futures::StreamExt;
+use futures::{FutureExt, StreamExt};
 use solana_relayer_adapter_rust::Tips;
 use jupiter_arbitrage_bot_offchain::submit_with_services;
-use solana_sdk::signer::Signer;
-use solana_sdk::system_instruction::advance_nonce_account;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 use yellowstone_grpc_client::GeyserGrpcClient;
@@ -16,16 +15,31 @@ use yellowstone_grpc_proto::geyser::{
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .with_target(false)
-        .init();
+    // `report [...]` prints summaries of past trading and exits
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|command| command == "report") {
+        return run_report(&init_config()?.opportunity_db.path, &args[2..]);
+    }
+
+    // Fails with every invalid setting listed before any task starts
+    let config = init_config();
+    // The dashboard needs a real terminal; piped or supervised runs keep plain logs
+    let dashboard = (args.iter().any(|arg| arg == "--dashboard")
+        || config.as_ref().is_ok_and(|c| c.dashboard.enabled))
+        && std::io::stdout().is_terminal();
+    let _event_log_guard = init_logging(config.as_ref().ok().map(|c| &c.logging), dashboard);
+    log_config_warnings(config?);
 
     info!("Using RPC only for transaction submission");
 
+    // Loaded before any task touches PUBKEY; may prompt for keystore passwords
+    for wallet in init_wallets()? {
+        info!(index = wallet.index, pubkey = %wallet.pubkey, nonce_account = %wallet.nonce_account, "Wallet loaded");
+    }
+    if let Some(program) = init_profit_guard_program()? {
+        info!(%program, "Profit guard enabled");
+    }
+
     // Start nonce fetcher
     tokio::spawn(async move {
         loop {
@@ -33,18 +47,47 @@ async fn main() -> Result<(), anyhow::Error> {
         }
     });
 
-    // Start SOL price fetcher
-    tokio::spawn({
-        let fallback_price = CONFIG.tx_cost.sol_usd;
-        async move {
-            start_sol_price_fetcher(fallback_price).await;
-        }
-    });
+    // Start SOL price oracle
+    tokio::spawn(start_sol_price_oracle());
 
     let _hash = get_slot();
 
-    let pubkey = keyfile_status().await;
-    info!(pubkey = %pubkey.pubkey(), "Wallet loaded");
+    init_token_registry().await;
+
+    // Reserve vaults and fees come from chain; a bad reserve stops startup
+    let flash_reserves = init_flash_loan().await?;
+    if flash_reserves > 0 {
+        info!(reserves = flash_reserves, "Flash loan funding enabled");
+    }
+
+    if let Err(e) = init_opportunity_db() {
+        warn!(error = %e, path = %CONFIG.opportunity_db.path, "Opportunity database unavailable; opportunities not persisted");
+    }
+
+    // Instruments are only traded once their price is known
+    refresh_token_prices().await;
+    tokio::spawn(start_token_price_refresher(CONFIG.token_prices.refresh_interval_secs));
+
+    // Wallet balances gate and clamp every instrument's notional range
+    if let Err(e) = refresh_inventory().await {
+        warn!(error = %e, "Initial inventory load failed; instruments paused until refresh");
+    }
+    tokio::spawn(start_inventory_refresher(CONFIG.inventory.refresh_interval_ms));
+    tokio::spawn(start_ata_maintenance(CONFIG.inventory.ata_maintenance_interval_secs));
+    // Every mode submits through the scheduler, which ranks and dedupes their candidates
+    tokio::spawn(start_scheduler());
+    if CONFIG.rebalance.enabled {
+        tokio::spawn(start_rebalancer(CONFIG.rebalance.interval_secs));
+    }
+    if CONFIG.alerts.enabled {
+        tokio::spawn(start_notifier());
+    }
+    start_http_servers(&CONFIG.telemetry);
+    if dashboard {
+        if let Err(e) = start_dashboard(CONFIG.dashboard.refresh_ms) {
+            warn!(error = %e, "Dashboard failed to start; logging to stdout");
+        }
+    }
 
     info!(
         big_trades = CONFIG.strategy.watch_flows,
@@ -64,24 +107,47 @@ async fn main() -> Result<(), anyhow::Error> {
         Err(e) => warn!(error = %e, "Jupiter timing estimate skipped"),
     }
 
+    // Load pool state before subscribing so the account filters include vaults and configs
+    if !MARKET_POOLS.is_empty() {
+        if let Err(e) = bootstrap_pool_cache().await {
+            warn!(error = %e, "Pool state cache bootstrap failed");
+        }
+    }
+
     // Start continuous polling if enabled
-    if CONFIG.strategy.poll_quotes {
+    let polling_task = CONFIG.strategy.poll_quotes.then(|| {
         let polling_interval = CONFIG.strategy.poll_interval_ms;
         tokio::spawn(async move {
             continuous_polling_loop(polling_interval).await;
+        })
+    });
+
+    // Start direct (local quote, native instruction) execution if enabled
+    if CONFIG.markets.direct_execution_enabled && !MARKET_POOLS.is_empty() {
+        let polling_interval = CONFIG.strategy.poll_interval_ms;
+        tokio::spawn(async move {
+            direct_arbitrage_loop(polling_interval).await;
         });
     }
 
-    // Run big trades monitor if enabled
-    if CONFIG.strategy.watch_flows {
-        run_big_trades_monitor().await?;
-    } else if CONFIG.strategy.poll_quotes {
-        info!("Big trades monitor disabled; running continuous polling only");
-        loop {
-            tokio::time::sleep(Duration::from_secs(3600)).await;
+    let run = async {
+        // The Geyser stream carries big trades and/or pool account updates for the local cache
+        if CONFIG.strategy.watch_flows || !MARKET_POOLS.is_empty() {
+            run_big_trades_monitor().await?;
+        } else if let Some(polling_task) = polling_task {
+            info!("Big trades monitor disabled; running continuous polling only");
+            // The polling loop never returns; a panic ends the process so the supervisor restarts it
+            polling_task.await?;
+        } else {
+            warn!("Both modes disabled; enable at least one in settings.toml");
         }
-    } else {
-        warn!("Both modes disabled; enable at least one in settings.toml");
+        Ok::<(), anyhow::Error>(())
+    };
+
+    // Closing the dashboard returns from main, so log and database writers flush on exit
+    tokio::select! {
+        result = run => result?,
+        _ = dashboard_closed() => info!("Dashboard closed; shutting down"),
     }
 
     Ok(())
@@ -109,36 +175,26 @@ async fn continuous_polling_loop(interval_ms: u64) {
         for base_token_config in BASE_TOKENS.iter() {
             let mother_token = base_token_config.mint.clone();
             
-            // Look up token info from POPULAR_TOKEN_INFO to get correct decimals and symbol
-            let (decimal, symbol) = POPULAR_TOKEN_INFO
-                .iter()
-                .find(|t| t.mint == mother_token.as_str())
-                .map(|t| (t.decimals, t.symbol))
-                .unwrap_or_else(|| {
-                    // Fallback: Check if it's WSOL/SOL
-                    if mother_token == "So11111111111111111111111111111111111111112" {
-                        (9, "SOL")
-                    } else {
-                        // Default to 6 decimals and "UNKNOWN" symbol if not found
-                        (6, "UNKNOWN")
-                    }
-                });
-            
-            // If mother token is SOL, use stablecoin as target; otherwise use configured target
-            let target_tokens = if symbol == "SOL" || symbol == "WSOL" || mother_token == "So11111111111111111111111111111111111111112" {
-                // For SOL: Use stablecoins (USDC, USDT) as target tokens
-                vec![
-                    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(), // USDC
-                    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB".to_string(), // USDT
-                ]
-            } else {
-                // For other tokens: Use configured target token
-                vec![TARGET_TOKEN.clone()]
+            // Decimals must be confirmed on-chain; unresolved or unsupported mints are not traded
+            let Some(meta) = instrument_meta(&mother_token) else {
+                continue;
             };
+            let (decimal, symbol) = (meta.decimals, meta.symbol.as_str());
+            
+            // Instrument's target_mints, or the stablecoin / global default
+            let target_tokens = instrument_targets(base_token_config);
             
-            let min_range = base_token_config.amount_range[0];
-            let max_range = base_token_config.amount_range[1];
+            // Clamped to wallet balance; None while the instrument is paused
+            let Some([min_range, max_range]) = effective_notional_range(base_token_config, decimal) else {
+                continue;
+            };
             
+            // Costs and profits are converted at the instrument's own price; none means no trade
+            let Some(token_price_sol) = token_price_sol(&mother_token).await else {
+                continue;
+            };
+            let min_profit = min_profit_units(&mother_token, base_token_config.min_profit).await;
+
             let sim_start = std::time::Instant::now();
             let quote_data = simulate_amount_in(
                 mother_token.clone(),
@@ -148,7 +204,7 @@ async fn continuous_polling_loop(interval_ms: u64) {
                 min_range,
                 max_range,
                 base_token_config.steps as usize,
-                base_token_config.min_profit,
+                min_profit,
                 true, // is_polling_mode = true for polling mode
             )
             .await;
@@ -161,23 +217,16 @@ async fn continuous_polling_loop(interval_ms: u64) {
             if !quote_data.is_empty() && CONFIG.strategy.live_trading {
                 info!(count = quote_data.len(), %symbol, "Found profitable opportunities");
                 
-                let best_trade = quote_data.into_iter()
-                    .max_by_key(|(in_amt, out_amt, _, _, _, _)| *out_amt as i64 - *in_amt as i64);
+                let mut quote_data = quote_data;
+                quote_data.sort_by_key(|(in_amt, out_amt, _, _, _, _, _)| std::cmp::Reverse(*out_amt as i64 - *in_amt as i64));
+                for (_, _, _, _, _, _, id) in quote_data.iter().skip(1) {
+                    mark_opportunity_rejected(*id, REJECT_NOT_BEST);
+                }
+                let best_trade = quote_data.into_iter().next();
                 
-                if let Some((in_amount, out_amount, in_res, out_res, _, target_token)) = best_trade {
+                if let Some((in_amount, out_amount, in_res, out_res, _, target_token, opportunity_id)) = best_trade {
                     // Find target token symbol for logging
-                    let target_symbol = POPULAR_TOKEN_INFO
-                        .iter()
-                        .find(|t| t.mint == target_token.as_str())
-                        .map(|t| t.symbol)
-                        .unwrap_or_else(|| {
-                            // Fallback for common stablecoins
-                            match target_token.as_str() {
-                                "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v" => "USDC",
-                                "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB" => "USDT",
-                                _ => "UNKNOWN"
-                            }
-                        });
+                    let target_symbol = token_symbol(&target_token);
                     
                     let in_human = in_amount as f64 / 10_f64.powf(decimal as f64);
                     let out_human = out_amount as f64 / 10_f64.powf(decimal as f64);
@@ -191,15 +240,17 @@ async fn continuous_polling_loop(interval_ms: u64) {
                         "Best opportunity"
                     );
                     
-                    let min_profit = base_token_config.min_profit;
-                    let token_is_sol = symbol == "SOL" || symbol == "WSOL" || mother_token == "So11111111111111111111111111111111111111112";
                     let gross_profit_raw = (out_amount as i64 - in_amount as i64);
-                    let (total_tx_cost_raw, tip_sol) = jupiter_arbitrage_bot_offchain::engine::runtime::calculate_tx_cost_for_trade(
+                    let (total_tx_cost_raw, tip_sol) = calculate_tx_cost_for_trade_with_price(
                         &FEES,
                         gross_profit_raw,
-                        token_is_sol,
                         decimal,
-                    ).await;
+                        token_price_sol,
+                        &mother_token,
+                        in_amount,
+                    );
+                    let total_tx_cost_raw = total_tx_cost_raw
+                        + transfer_fee_cost_raw(&mother_token, &target_token, in_amount, in_res.out_amount, out_amount);
                     let total_tx_cost_in_token = total_tx_cost_raw as f64 / 10_f64.powf(decimal as f64);
                     let real_profit = profit_human - total_tx_cost_in_token;
 
@@ -213,9 +264,28 @@ async fn continuous_polling_loop(interval_ms: u64) {
                             min_profit = %min_profit,
                             "Submitting trade"
                         );
-                        tokio::spawn(async move {
-                            submit_polling_trade(in_res, out_res, min_profit, decimal, tip_sol).await;
-                        });
+                        if let Some(remaining) = risk_tripped() {
+                            debug!(%symbol, remaining_secs = remaining.as_secs(), "Circuit breaker tripped; trade skipped");
+                            mark_opportunity_rejected(opportunity_id, REJECT_CIRCUIT_BREAKER);
+                            continue;
+                        }
+                        schedule(Candidate {
+                            opportunity_id,
+                            source: "polling",
+                            instrument_mint: mother_token.clone(),
+                            target_mint: target_token,
+                            pools: route_pools(&[&in_res, &out_res]),
+                            in_amount,
+                            net_profit: gross_profit_raw - total_tx_cost_raw,
+                            quoted_at: sim_start,
+                            dispatch: Box::new(move |lease| {
+                                submit_polling_trade(lease, in_res, out_res, min_profit, decimal, tip_sol, opportunity_id)
+                                    .boxed()
+                            }),
+                        })
+                        .await;
+                    } else {
+                        mark_opportunity_rejected(opportunity_id, REJECT_BELOW_MIN_PROFIT);
                     }
                 }
             }
@@ -225,63 +295,343 @@ async fn continuous_polling_loop(interval_ms: u64) {
 
 /// Submit a trade from polling mode. `tip_sol` is the third-party fee (fixed or profit-based) to attach.
 async fn submit_polling_trade(
+    lease: WalletLease,
     in_res: jupiter_swap_api_client::quote::QuoteResponse,
     out_res: jupiter_swap_api_client::quote::QuoteResponse,
     min_profit_amount: f64,
     decimal: u8,
     tip_sol: f64,
+    opportunity_id: OpportunityId,
 ) {
-    let instr_advance_nonce_account = advance_nonce_account(&NONCE_ADDR, &PUBKEY);
+    let payer = lease.pubkey();
+    let ledger_entry = LedgerEntry::new(
+        TradeKind::Arbitrage,
+        in_res.input_mint.to_string(),
+        out_res.output_mint.to_string(),
+        in_res.in_amount,
+        out_res.out_amount,
+    )
+    .with_wallet(payer.to_string());
+    let venues = route_venues(&[&in_res, &out_res]);
     
-    let ix = match get_swap_ix(
+    let swap = match build_arb_swap(
         in_res,
         out_res,
         (min_profit_amount * 10_f64.powf(decimal as f64)) as u64,
+        &payer,
     ).await {
-        Ok(ix) => ix,
+        Ok(swap) => swap,
         Err(e) => {
-            error!(error = %e, "Failed to get swap_ix");
+            error!(error = %e, "Failed to build swap instructions");
+            mark_opportunity_failed(opportunity_id, REJECT_BUILD_FAILED, e.to_string());
+            lease.release();
             return;
         }
     };
 
-    let mut raw_swap_ixs = Vec::new();
-    raw_swap_ixs.extend(ix.setup_instructions);
-    raw_swap_ixs.push(ix.swap_instruction);
+    let raw_swap_ixs = swap.instructions;
+    let includes_compute_budget = swap.includes_compute_budget;
 
-    let nonce_data = get_nonce();
-    let recent_blockhash = nonce_data.blockhash();
-    let alts = fetch_alt(ix.address_lookup_table_addresses).await;
+    let alts = fetch_alt(swap.address_lookup_table_addresses).await;
 
-    let service_desc = if jupiter_arbitrage_bot_offchain::use_low_latency_submission() {
-        "low-latency (Jito/Helius/etc.)"
-    } else {
-        "RPC"
-    };
+    if !passes_preflight(&payer, &raw_swap_ixs, includes_compute_budget, &alts, &venues).await {
+        mark_opportunity_rejected(opportunity_id, REJECT_PREFLIGHT_FAILED);
+        lease.release();
+        return;
+    }
+
+    let service = submission_label(lease.signers());
     info!(
-        service = %service_desc,
+        %service,
         time = %Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"),
         "Submitting transaction"
     );
 
-    submit_with_services(
+    let sent = submit_with_services(
         Tips {
             tip_sol_amount: tip_sol,
             tip_addr_idx: 0,
-            cu: Some(FEES.compute_units),
-            priority_fee_micro_lamport: Some(FEES.priority_lamports),
-            payer: *PUBKEY,
+            cu: (!includes_compute_budget).then_some(FEES.compute_units),
+            priority_fee_micro_lamport: (!includes_compute_budget).then_some(FEES.priority_lamports),
+            payer,
             pure_ix: raw_swap_ixs,
         },
-        &*SIGNERS,
-        recent_blockhash,
-        instr_advance_nonce_account,
+        lease.signers(),
+        lease.blockhash(),
+        lease.advance_nonce_ix(),
         alts,
         1,
     )
     .await;
+    if !sent {
+        mark_opportunity_failed(opportunity_id, REJECT_SEND_FAILED, "transaction not sent".to_string());
+        lease.release();
+        return;
+    }
 
-    info!(service = %service_desc, "Transaction submitted");
+    info!(%service, wallet = %payer, "Transaction submitted");
+    let instrument = token_symbol(&ledger_entry.input_mint);
+    emit(Event::Submitted {
+        source: "polling",
+        instrument: instrument.clone(),
+        wallet: payer.to_string(),
+        in_amount: ledger_entry.in_amount,
+        expected_out: ledger_entry.expected_out,
+        service: service.clone(),
+        signature: None,
+    });
+    OPPORTUNITIES_SUBMITTED.with_label_values(&[instrument.as_str()]).inc();
+    mark_opportunity_submitted(opportunity_id, payer.to_string(), service);
+    note_submitted(
+        opportunity_id,
+        "polling",
+        &ledger_entry.input_mint,
+        payer.to_string(),
+        ledger_entry.in_amount,
+        ledger_entry.expected_out,
+    );
+    track_landing(&lease, &ledger_entry.input_mint, venues, opportunity_id);
+    record_trade(&ledger_entry);
+}
+
+// =============================================================================
+// DIRECT EXECUTION MODE
+// =============================================================================
+
+/// Quote cached pools locally and submit native swap instructions. Once an opportunity is
+/// found no network call is made before signing: nonce, SOL price and pool state are cached.
+async fn direct_arbitrage_loop(interval_ms: u64) {
+    info!(interval_ms, "Starting direct execution from pool state cache");
+
+    let mut ticker = interval(Duration::from_millis(interval_ms));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        ticker.tick().await;
+
+        for base_token_config in BASE_TOKENS.iter() {
+            let Ok(base_mint) = base_token_config.mint.parse::<solana_sdk::pubkey::Pubkey>() else {
+                continue;
+            };
+            let Some(meta) = instrument_meta(&base_token_config.mint) else {
+                continue;
+            };
+            if sol_price_blocks(&base_token_config.mint) {
+                continue;
+            }
+            let (decimal, symbol) = (meta.decimals, meta.symbol.as_str());
+            let Some([min_range, max_range]) = effective_notional_range(base_token_config, decimal) else {
+                continue;
+            };
+
+            let amounts = amount_grid(
+                min_range,
+                max_range,
+                base_token_config.steps as usize,
+                decimal,
+            );
+            let quoted_at = std::time::Instant::now();
+            let plans = find_direct_arbs(&base_mint, &amounts, CONFIG.markets.direct_slippage_bps);
+            SWEEP_SECONDS
+                .with_label_values(&[symbol, "direct"])
+                .observe(quoted_at.elapsed().as_secs_f64());
+            let best_spread_bps = plans
+                .iter()
+                .map(|plan| (plan.amount_out as f64 - plan.amount_in as f64) / plan.amount_in as f64 * 1e4)
+                .reduce(f64::max);
+            if plans.is_empty() || !CONFIG.strategy.live_trading {
+                note_sweep(&base_token_config.mint, "direct", best_spread_bps, None);
+                continue;
+            }
+
+            let Some(token_price_sol) = token_price_sol(&base_token_config.mint).await else {
+                continue;
+            };
+            let min_profit = min_profit_units(&base_token_config.mint, base_token_config.min_profit).await;
+            let min_profit_raw = (min_profit * 10_f64.powf(decimal as f64)) as u64;
+            let mut evaluated: Vec<(i64, i64, f64, DirectArbPlan)> = plans
+                .into_iter()
+                .map(|plan| {
+                    let gross_profit = plan.amount_out as i64 - plan.amount_in as i64;
+                    let (total_tx_cost, tip_sol) = calculate_tx_cost_for_trade_with_price(
+                        &FEES,
+                        gross_profit,
+                        decimal,
+                        token_price_sol,
+                        &base_token_config.mint,
+                        plan.amount_in,
+                    );
+                    let total_tx_cost = total_tx_cost
+                        + transfer_fee_cost_raw(
+                            &base_token_config.mint,
+                            &plan.target_mint.to_string(),
+                            plan.amount_in,
+                            plan.mid_amount,
+                            plan.amount_out,
+                        );
+                    (gross_profit - total_tx_cost, total_tx_cost, tip_sol, plan)
+                })
+                .collect();
+            evaluated.sort_by_key(|(net_profit, _, _, _)| std::cmp::Reverse(*net_profit));
+            note_sweep(&base_token_config.mint, "direct", best_spread_bps, evaluated.first().map(|(net, _, _, _)| *net));
+            // Local quotes are cheap and run every tick, so only plans clearing min profit are recorded
+            let recorded: Vec<(i64, f64, DirectArbPlan, OpportunityId)> = evaluated
+                .into_iter()
+                .take_while(|(net_profit, _, _, _)| *net_profit >= min_profit_raw as i64)
+                .enumerate()
+                .map(|(rank, (net_profit, tx_cost, tip_sol, plan))| {
+                    let id = record_opportunity(Opportunity {
+                        mode: "direct",
+                        instrument_mint: base_token_config.mint.clone(),
+                        target_mint: plan.target_mint.to_string(),
+                        in_amount: plan.amount_in,
+                        quoted_out: Some(plan.amount_out),
+                        tx_cost: Some(tx_cost),
+                        tip_sol: Some(tip_sol),
+                        min_profit: min_profit_raw,
+                        reject_reason: (rank > 0).then_some(REJECT_NOT_BEST),
+                        detail: None,
+                    });
+                    (net_profit, tip_sol, plan, id)
+                })
+                .collect();
+            OPPORTUNITIES_FOUND
+                .with_label_values(&[symbol, "direct"])
+                .inc_by(recorded.len() as u64);
+
+            let Some((net_profit, tip_sol, plan, opportunity_id)) = recorded.into_iter().next() else {
+                continue;
+            };
+
+            info!(
+                %symbol,
+                target = %plan.target_mint,
+                leg1 = %plan.leg1.address,
+                leg2 = %plan.leg2.address,
+                in_amount = plan.amount_in,
+                out_amount = plan.amount_out,
+                net_profit,
+                slot = plan.slot,
+                "Submitting direct trade"
+            );
+            if let Some(remaining) = risk_tripped() {
+                debug!(%symbol, remaining_secs = remaining.as_secs(), "Circuit breaker tripped; trade skipped");
+                mark_opportunity_rejected(opportunity_id, REJECT_CIRCUIT_BREAKER);
+                continue;
+            }
+            schedule(Candidate {
+                opportunity_id,
+                source: "direct",
+                instrument_mint: base_token_config.mint.clone(),
+                target_mint: plan.target_mint.to_string(),
+                pools: vec![plan.leg1.address.to_string(), plan.leg2.address.to_string()],
+                in_amount: plan.amount_in,
+                net_profit,
+                quoted_at,
+                dispatch: Box::new(move |lease| {
+                    submit_direct_trade(lease, plan, min_profit_raw, tip_sol, opportunity_id).boxed()
+                }),
+            })
+            .await;
+        }
+    }
+}
+
+async fn submit_direct_trade(
+    lease: WalletLease,
+    plan: DirectArbPlan,
+    min_profit_raw: u64,
+    tip_sol: f64,
+    opportunity_id: OpportunityId,
+) {
+    let payer = lease.pubkey();
+    let Some(swap_ixs) = build_direct_arb_ixs(&plan, &payer, min_profit_raw) else {
+        error!(leg1 = %plan.leg1.address, leg2 = %plan.leg2.address, "Unsupported pool in direct plan");
+        mark_opportunity_failed(opportunity_id, REJECT_BUILD_FAILED, "unsupported pool".to_string());
+        lease.release();
+        return;
+    };
+    let swap_ixs = strip_provisioned_ata_creates(swap_ixs);
+    // Flash-funded when the base token has a reserve; the wrapper then carries the compute budget
+    let flash_ixs = pool_token_program(&plan.leg1, &plan.base_mint).and_then(|program| {
+        wrap_with_flash_loan(swap_ixs.clone(), &payer, &plan.base_mint, &program, plan.amount_in)
+    });
+    let includes_compute_budget = flash_ixs.is_some();
+    let mut swap_ixs = flash_ixs.unwrap_or(swap_ixs);
+    if let Some(program_id) = profit_guard_program() {
+        match build_profit_guard(program_id, &payer, &plan.base_mint, min_profit_raw).await {
+            Ok(guard) => swap_ixs.push(guard),
+            Err(e) => {
+                error!(error = %e, "Failed to build profit guard");
+                mark_opportunity_failed(opportunity_id, REJECT_BUILD_FAILED, e.to_string());
+                lease.release();
+                return;
+            }
+        }
+    }
+
+    let venues = plan.venues();
+    if !passes_preflight(&payer, &swap_ixs, includes_compute_budget, &[], &venues).await {
+        mark_opportunity_rejected(opportunity_id, REJECT_PREFLIGHT_FAILED);
+        lease.release();
+        return;
+    }
+
+    let sent = submit_with_services(
+        Tips {
+            tip_sol_amount: tip_sol,
+            tip_addr_idx: 0,
+            cu: (!includes_compute_budget).then_some(FEES.compute_units),
+            priority_fee_micro_lamport: (!includes_compute_budget).then_some(FEES.priority_lamports),
+            payer,
+            pure_ix: swap_ixs,
+        },
+        lease.signers(),
+        lease.blockhash(),
+        lease.advance_nonce_ix(),
+        Vec::new(),
+        1,
+    )
+    .await;
+    if !sent {
+        mark_opportunity_failed(opportunity_id, REJECT_SEND_FAILED, "transaction not sent".to_string());
+        lease.release();
+        return;
+    }
+
+    let service = submission_label(lease.signers());
+    info!(leg1 = %plan.leg1.address, leg2 = %plan.leg2.address, %service, wallet = %payer, "Direct trade submitted");
+    let instrument = token_symbol(&plan.base_mint.to_string());
+    emit(Event::Submitted {
+        source: "direct",
+        instrument: instrument.clone(),
+        wallet: payer.to_string(),
+        in_amount: plan.amount_in,
+        expected_out: plan.amount_out,
+        service: service.clone(),
+        signature: None,
+    });
+    OPPORTUNITIES_SUBMITTED.with_label_values(&[instrument.as_str()]).inc();
+    mark_opportunity_submitted(opportunity_id, payer.to_string(), service);
+    note_submitted(
+        opportunity_id,
+        "direct",
+        &plan.base_mint.to_string(),
+        payer.to_string(),
+        plan.amount_in,
+        plan.amount_out,
+    );
+    track_landing(&lease, &plan.base_mint.to_string(), venues, opportunity_id);
+    record_trade(
+        &LedgerEntry::new(
+            TradeKind::Arbitrage,
+            plan.base_mint.to_string(),
+            plan.base_mint.to_string(),
+            plan.amount_in,
+            plan.amount_out,
+        )
+        .with_wallet(payer.to_string()),
+    );
 }
 
 // =============================================================================
@@ -306,6 +656,8 @@ async fn run_big_trades_monitor() -> Result<(), anyhow::Error> {
     };
 
     info!(%host, %port, "Connecting to Yellowstone gRPC");
+    set_geyser_connected(false);
+    let mut subscribed_before = false;
 
     loop {
         info!("Connecting and subscribing to Yellowstone");
@@ -340,21 +692,26 @@ async fn run_big_trades_monitor() -> Result<(), anyhow::Error> {
         };
 
         let mut transactions_map = HashMap::new();
-        for (idx, base_token) in BASE_TOKENS.iter().enumerate() {
-            let filter = SubscribeRequestFilterTransactions {
-                vote: Some(false),
-                failed: Some(false),
-                account_include: vec![base_token.mint.clone()],
-                account_exclude: vec![],
-                account_required: vec![],
-                signature: None,
-            };
-            transactions_map.insert(format!("tx_{}", idx), filter);
+        if CONFIG.strategy.watch_flows {
+            for (idx, base_token) in BASE_TOKENS.iter().enumerate() {
+                let filter = SubscribeRequestFilterTransactions {
+                    vote: Some(false),
+                    failed: Some(false),
+                    account_include: vec![base_token.mint.clone()],
+                    account_exclude: vec![],
+                    account_required: vec![],
+                    signature: None,
+                };
+                transactions_map.insert(format!("tx_{}", idx), filter);
+            }
         }
 
         let request = SubscribeRequest {
-            slots: HashMap::new(),
-            accounts: HashMap::new(),
+            slots: pool_slot_filters(),
+            accounts: pool_account_filters()
+                .into_iter()
+                .chain(inventory_account_filters())
+                .collect(),
             transactions: transactions_map,
             transactions_status: HashMap::new(),
             blocks: HashMap::new(),
@@ -369,6 +726,11 @@ async fn run_big_trades_monitor() -> Result<(), anyhow::Error> {
         let (_sink, mut stream) = match client.subscribe_with_request(Some(request)).await {
             Ok((sink, stream)) => {
                 info!("Yellowstone subscribed");
+                if subscribed_before {
+                    GEYSER_RECONNECTS.inc();
+                }
+                subscribed_before = true;
+                set_geyser_connected(true);
                 (sink, stream)
             }
             Err(e) => {
@@ -378,9 +740,28 @@ async fn run_big_trades_monitor() -> Result<(), anyhow::Error> {
             }
         };
 
+        // Reload pool state now that updates are flowing, then trust the cache again
+        if !MARKET_POOLS.is_empty() {
+            match bootstrap_pool_cache().await {
+                Ok(()) => set_pool_cache_live(true),
+                // Stays stale, so direct arbs are not quoted from it until the next reconnect
+                Err(e) => warn!(error = %e, "Pool state cache reload failed; direct execution paused"),
+            }
+        }
+
         loop {
             match stream.next().await {
                 Some(Ok(update)) => {
+                    note_geyser_message();
+                    if let Some(created_at) = &update.created_at {
+                        let created_ms = created_at.seconds * 1000 + created_at.nanos as i64 / 1_000_000;
+                        let lag_ms = (Utc::now().timestamp_millis() - created_ms).max(0);
+                        GEYSER_LAG_SECONDS.observe(lag_ms as f64 / 1000.0);
+                        note_geyser_lag(Duration::from_millis(lag_ms as u64));
+                    }
+                    if apply_inventory_update(&update) || apply_market_update(&update) {
+                        continue;
+                    }
                     tokio::spawn(async move {
                         process_single_trade_yellowstone(update).await;
                     });
@@ -395,5 +776,8 @@ async fn run_big_trades_monitor() -> Result<(), anyhow::Error> {
                 }
             }
         }
+
+        set_geyser_connected(false);
+        set_pool_cache_live(false);
     }
 }